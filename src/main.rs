@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, State, Query},
-    http::{header, StatusCode, HeaderMap},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
     response::{Html, IntoResponse, Response, Json, Redirect},
     routing::{get, post, delete},
     Extension, Router, Form,
@@ -9,10 +9,14 @@ use base64::Engine;
 use handlebars::Handlebars;
 use dotenvy::dotenv;
 use once_cell::sync::Lazy;
-use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{BlockQuoteKind, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use quick_xml::events::{BytesCData, BytesDecl, BytesText, Event as XmlEvent};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, env, fs, path::PathBuf, sync::Arc, time::{Duration, Instant}};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, env, fs, io::Write, path::{Component, PathBuf}, process::{Command, Stdio}, sync::{Arc, Mutex}, time::{Duration, Instant, SystemTime}};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 use tokio::sync::RwLock;
+use tokio_util::io::ReaderStream;
 use syntect::{
     highlighting::ThemeSet,
     html::highlighted_html_for_string,
@@ -25,6 +29,17 @@ use uuid::Uuid;
 const GITHUB_API_BASE: &str = "https://api.github.com";
 static GITHUB_USERNAME: Lazy<String> = Lazy::new(|| env::var("GITHUB_USERNAME").unwrap_or_else(|_| "aryansrao".into()));
 static GITHUB_TOKEN: Lazy<Option<String>> = Lazy::new(|| env::var("GITHUB_TOKEN").ok());
+// How README images are imported: hotlinked from raw.githubusercontent.com, or downloaded locally
+static GITHUB_IMAGES: Lazy<GitHubImages> = Lazy::new(GitHubImages::from_env);
+// Mirrored README images, named by content hash, with a manifest of where each came from. The
+// manifest names the repos using each image, so it lives outside the publicly served images directory
+const MIRROR_DIR: &str = "content/images/github";
+const MIRROR_MANIFEST: &str = ".cache/github-images.json";
+// Largest image accepted when mirroring or bundling README images, or downloading a preview image
+const MAX_IMAGE_DOWNLOAD: usize = 10 * 1024 * 1024;
+// Most of a page read when looking for its preview metadata
+const MAX_PREVIEW_PAGE: usize = 1024 * 1024;
+static MIRROR_MANIFEST_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
 // Admin configuration
 static ADMIN_PASSWORD: Lazy<String> = Lazy::new(|| env::var("ADMIN_PASSWORD").unwrap_or_else(|_| "admin123".into()));
@@ -33,9 +48,174 @@ const LOCKOUT_DURATION_SECS: u64 = 300; // 5 minutes
 const SESSION_DURATION_SECS: u64 = 3600; // 1 hour
 
 // Initialize syntax highlighting sets once
-static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(|| SyntaxSet::load_defaults_newlines());
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
 static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
 
+// Diagram rendering - external renderers for ```mermaid and ```dot fences
+static MERMAID_CLI: Lazy<String> = Lazy::new(|| env::var("MERMAID_CLI").unwrap_or_else(|_| "mmdc".into()));
+static GRAPHVIZ_DOT: Lazy<String> = Lazy::new(|| env::var("GRAPHVIZ_DOT").unwrap_or_else(|_| "dot".into()));
+const DIAGRAM_CACHE_DIR: &str = ".cache/diagrams";
+// Rendered diagrams keyed by content hash
+static DIAGRAM_CACHE: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// Diagrams waiting for the next refresh (hash -> language and source); renders never run inside a request
+static PENDING_DIAGRAMS: Lazy<Mutex<HashMap<String, (String, String)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// When each diagram last failed to render; it is queued again once DIAGRAM_RETRY has passed
+static DIAGRAM_FAILURES: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+const DIAGRAM_RETRY: Duration = Duration::from_secs(300);
+
+// Responsive images - local images under content/images get resized WebP variants
+const IMAGES_DIR: &str = "content/images";
+const IMAGE_CACHE_DIR: &str = ".cache/images";
+static IMAGE_WIDTHS: Lazy<Vec<u32>> = Lazy::new(|| {
+    env::var("IMAGE_WIDTHS")
+        .unwrap_or_else(|_| "480,960,1440".into())
+        .split(',')
+        .filter_map(|w| w.trim().parse().ok())
+        .collect()
+});
+// Source images seen while rendering, keyed by path; re-hashed only when the file changes
+static LOCAL_IMAGES: Lazy<Mutex<HashMap<PathBuf, LocalImage>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// Source file for each content hash, persisted so /media requests after a restart are answered without rendering
+const IMAGE_INDEX: &str = ".cache/images/index.json";
+static IMAGE_SOURCES: Lazy<Mutex<HashMap<String, PathBuf>>> = Lazy::new(|| {
+    let index = fs::read_to_string(IMAGE_INDEX)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    Mutex::new(index)
+});
+
+// Link previews for bare-URL paragraphs, fetched in the background and cached on disk
+const LINK_PREVIEW_CACHE_DIR: &str = ".cache/previews";
+// Preview images are downloaded rather than hotlinked, named by content hash
+const LINK_PREVIEW_IMAGE_DIR: &str = "content/images/previews";
+static LINK_PREVIEWS: Lazy<Mutex<HashMap<String, LinkPreview>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// URLs seen while rendering that have no preview yet
+static PENDING_LINK_PREVIEWS: Lazy<Mutex<std::collections::HashSet<String>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashSet::new()));
+
+// HTML sanitization profiles: SANITIZE_POSTS for hand-written posts, SANITIZE_IMPORTED for GitHub READMEs
+static SANITIZE_POSTS: Lazy<SanitizeProfile> = Lazy::new(|| SanitizeProfile::from_env("SANITIZE_POSTS", SanitizeProfile::Standard));
+static SANITIZE_IMPORTED: Lazy<SanitizeProfile> = Lazy::new(|| SanitizeProfile::from_env("SANITIZE_IMPORTED", SanitizeProfile::Strict));
+
+// Reading time: words per minute for prose and code, characters per minute for CJK text, seconds per image
+static READING_WPM: Lazy<f64> = Lazy::new(|| {
+    env::var("READING_WPM").ok().and_then(|v| v.parse().ok()).filter(|v| *v > 0.0).unwrap_or(200.0)
+});
+static CODE_WPM: Lazy<f64> = Lazy::new(|| {
+    env::var("CODE_WPM").ok().and_then(|v| v.parse().ok()).filter(|v| *v > 0.0).unwrap_or(100.0)
+});
+static CJK_CPM: Lazy<f64> = Lazy::new(|| {
+    env::var("CJK_CPM").ok().and_then(|v| v.parse().ok()).filter(|v| *v > 0.0).unwrap_or(500.0)
+});
+static IMAGE_READING_SECONDS: Lazy<f64> = Lazy::new(|| {
+    env::var("IMAGE_READING_SECONDS").ok().and_then(|v| v.parse().ok()).unwrap_or(12.0)
+});
+
+// Link checker: minimum delay between requests to one host, and how long external results are reused
+static LINK_CHECK_INTERVAL_MS: Lazy<u64> = Lazy::new(|| {
+    env::var("LINK_CHECK_INTERVAL_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(1000)
+});
+static LINK_CHECK_CACHE_HOURS: Lazy<u64> = Lazy::new(|| {
+    env::var("LINK_CHECK_CACHE_HOURS").ok().and_then(|v| v.parse().ok()).unwrap_or(24)
+});
+const LINK_CHECK_CACHE: &str = ".cache/links.json";
+// The admin link check, which runs in the background while the page polls it
+static LINK_CHECK_JOB: Lazy<Mutex<LinkCheckJob>> = Lazy::new(|| Mutex::new(LinkCheckJob::default()));
+
+// Whether feeds carry each post's full HTML or only its summary; ?content=full|summary overrides per feed
+static FEED_CONTENT: Lazy<FeedContent> =
+    Lazy::new(|| env::var("FEED_CONTENT").ok().and_then(|v| FeedContent::parse(&v)).unwrap_or(FeedContent::Full));
+
+// Podcast feed (/podcast.xml): Apple Podcasts category, explicit flag, square cover art and owner email
+static PODCAST_CATEGORY: Lazy<String> = Lazy::new(|| env::var("PODCAST_CATEGORY").unwrap_or_else(|_| "Technology".into()));
+static PODCAST_EXPLICIT: Lazy<bool> = Lazy::new(|| env::var("PODCAST_EXPLICIT").map(|v| v == "true").unwrap_or(false));
+static PODCAST_IMAGE: Lazy<Option<String>> = Lazy::new(|| env::var("PODCAST_IMAGE").ok().filter(|v| !v.is_empty()));
+static PODCAST_EMAIL: Lazy<Option<String>> = Lazy::new(|| env::var("PODCAST_EMAIL").ok().filter(|v| !v.is_empty()));
+
+// WebSub: the hub feeds advertise - an external hub URL, or "builtin" to serve one at /websub
+static WEBSUB_HUB: Lazy<Option<WebSubHub>> = Lazy::new(WebSubHub::from_env);
+// Longest subscription lease the built-in hub grants, and the default when a subscriber asks for none
+static WEBSUB_LEASE_SECONDS: Lazy<i64> = Lazy::new(|| {
+    env::var("WEBSUB_LEASE_SECONDS").ok().and_then(|v| v.parse().ok()).filter(|v| *v > 0).unwrap_or(864_000)
+});
+// Hash of each feed as last published, so only changed feeds are pinged
+const WEBSUB_TOPICS_CACHE: &str = ".cache/websub-topics.json";
+// Feed URLs the built-in hub accepts subscriptions for, as of the last publish
+static WEBSUB_TOPICS: Lazy<Mutex<std::collections::HashSet<String>>> = Lazy::new(|| {
+    let topics: HashMap<String, String> = fs::read_to_string(WEBSUB_TOPICS_CACHE)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    Mutex::new(topics.into_keys().collect())
+});
+// Tries per content delivery; waits 2s, 4s, 8s, ... between them
+const WEBSUB_DELIVERY_ATTEMPTS: u32 = 4;
+// Most subscriptions the built-in hub keeps, and most verifications it runs at once
+const WEBSUB_MAX_SUBSCRIPTIONS: usize = 1000;
+const WEBSUB_MAX_PENDING: usize = 20;
+static WEBSUB_PENDING: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+// Verified subscriptions to the built-in hub
+const WEBSUB_SUBSCRIPTIONS_FILE: &str = ".cache/websub-subscriptions.json";
+static WEBSUB_SUBSCRIPTIONS: Lazy<Mutex<Vec<WebSubSubscription>>> = Lazy::new(|| {
+    let subscriptions = fs::read_to_string(WEBSUB_SUBSCRIPTIONS_FILE)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    Mutex::new(subscriptions)
+});
+
+// IndexNow: the site's key (8-128 letters, digits or dashes), served at /{key}.txt; unset turns submissions off
+static INDEXNOW_KEY: Lazy<Option<String>> = Lazy::new(|| {
+    let key = env::var("INDEXNOW_KEY").ok().filter(|k| !k.is_empty())?;
+    if (8..=128).contains(&key.len()) && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        Some(key)
+    } else {
+        eprintln!("Ignoring INDEXNOW_KEY: it must be 8-128 letters, digits or dashes");
+        None
+    }
+});
+// Where URLs are submitted; any IndexNow engine shares them with the others
+static INDEXNOW_ENDPOINT: Lazy<String> =
+    Lazy::new(|| env::var("INDEXNOW_ENDPOINT").unwrap_or_else(|_| "https://api.indexnow.org/indexnow".into()));
+// Hash of each page as last submitted, so only changed pages are sent
+const INDEXNOW_URLS_CACHE: &str = ".cache/indexnow-urls.json";
+// Recent submissions, newest first, for the admin panel
+const INDEXNOW_LOG_FILE: &str = ".cache/indexnow-log.json";
+const INDEXNOW_LOG_LIMIT: usize = 50;
+static INDEXNOW_LOG: Lazy<Mutex<Vec<IndexNowSubmission>>> = Lazy::new(|| {
+    let log = fs::read_to_string(INDEXNOW_LOG_FILE)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    Mutex::new(log)
+});
+// One submission run at a time, so overlapping content changes don't send the same URLs twice
+static INDEXNOW_RUNNING: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+// Maximum length of auto-generated summaries, in characters
+static SUMMARY_LENGTH: Lazy<usize> = Lazy::new(|| {
+    env::var("SUMMARY_LENGTH").ok().and_then(|v| v.parse().ok()).unwrap_or(160)
+});
+
+// User-defined shortcodes: templates/shortcodes/{name}.html, rendered with the shortcode's arguments
+static SHORTCODE_TEMPLATES: Lazy<Handlebars<'static>> = Lazy::new(|| {
+    let mut hb = Handlebars::new();
+    if let Ok(entries) = fs::read_dir("templates/shortcodes") {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map(|e| e == "html" || e == "hbs").unwrap_or(false) {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    if let Err(e) = hb.register_template_file(name, &path) {
+                        eprintln!("Failed to register shortcode template {}: {}", path.display(), e);
+                    }
+                }
+            }
+        }
+    }
+    hb
+});
+
 // Site configuration for SEO - Enhanced with comprehensive SEO fields
 #[derive(Serialize, Clone)]
 struct SiteConfig {
@@ -61,6 +241,33 @@ struct SiteConfig {
     google_site_verification: String,
     bing_site_verification: String,
     yandex_verification: String,
+    has_blogroll: bool, // Whether to link /blogroll
+}
+
+// A blog on the blogroll: its home page and the feed we read it through
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct BlogrollEntry {
+    title: String,
+    url: String,
+    feed: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+const BLOGROLL_FILE: &str = "content/blogroll.json";
+
+// Blogs we follow, read at startup and again after each content change
+static BLOGROLL: Lazy<Mutex<Arc<Vec<BlogrollEntry>>>> = Lazy::new(|| Mutex::new(Arc::new(load_blogroll())));
+
+// The blogroll as listed in content/blogroll.json; empty when the file is missing
+fn load_blogroll() -> Vec<BlogrollEntry> {
+    let Ok(json) = fs::read_to_string(BLOGROLL_FILE) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        eprintln!("Invalid {}: {}", BLOGROLL_FILE, e);
+        Vec::new()
+    })
 }
 
 impl Default for SiteConfig {
@@ -89,6 +296,7 @@ impl Default for SiteConfig {
             google_site_verification: env::var("GOOGLE_SITE_VERIFICATION").unwrap_or_default(),
             bing_site_verification: env::var("BING_SITE_VERIFICATION").unwrap_or_default(),
             yandex_verification: env::var("YANDEX_VERIFICATION").unwrap_or_default(),
+            has_blogroll: !BLOGROLL.lock().unwrap().is_empty(),
         }
     }
 }
@@ -164,6 +372,12 @@ struct PostForm {
     slug: Option<String>, // For editing existing posts
 }
 
+#[derive(Deserialize)]
+struct WarningsForm {
+    content: String,
+    slug: Option<String>, // File slug from the editor, e.g. "my-post/index" for a bundle
+}
+
 #[derive(Deserialize)]
 struct GitHubImportForm {
     repo_name: String,
@@ -198,6 +412,23 @@ struct Metadata {
     canonical: Option<String>,
     github_repo: Option<String>,
     website: Option<String>,
+    disable_stages: Vec<String>, // Render pipeline stages to skip for this post
+    updated: Option<String>,     // Last significant edit, YYYY-MM-DD
+    enclosure: Option<String>,   // Audio or video URL, with the enclosure_* keys below
+    enclosure_type: Option<String>,
+    enclosure_length: Option<String>,   // Bytes
+    enclosure_duration: Option<String>, // Seconds, MM:SS or HH:MM:SS
+}
+
+// Audio or video published with a post: played on its page and attached in feeds
+#[derive(Serialize, Debug, Clone)]
+struct Enclosure {
+    url: String, // Absolute
+    mime: String,
+    length: u64, // Bytes; 0 when unknown
+    duration_seconds: Option<u64>,
+    duration: Option<String>, // For display, e.g. "12:34"
+    is_video: bool,
 }
 
 // Define blog post structure
@@ -208,6 +439,7 @@ struct Post {
     summary: String,
     date: String,
     date_iso: String, // ISO 8601 format for structured data
+    updated_iso: String,
     tags: Vec<String>,
     filename: String,
     slug: String,
@@ -220,6 +452,23 @@ struct Post {
     word_count: u32,
     github_repo: Option<String>,
     website: Option<String>,
+    excerpt: Option<String>, // HTML above <!--more-->, links unwrapped for use inside cards
+    toc: Vec<TocEntry>,
+    enclosure: Option<Enclosure>,
+    #[serde(skip)]
+    text: String, // Plain text of the content, for search
+    #[serde(skip)]
+    links: Vec<String>,
+    #[serde(skip)]
+    images: Vec<String>,
+    #[serde(skip)]
+    ids: std::collections::HashSet<String>,
+    #[serde(skip)]
+    warnings: Vec<RenderWarning>,
+    #[serde(skip)]
+    published: DateTime<Local>,
+    #[serde(skip)]
+    updated: DateTime<Local>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -238,6 +487,44 @@ struct SearchParams {
     q: Option<String>,
 }
 
+#[derive(Clone, Debug)]
+struct LocalImage {
+    path: PathBuf,
+    hash: String,
+    width: u32,
+    height: u32,
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+// Lightweight post index entry, available to shortcodes before the posts themselves are rendered
+#[derive(Serialize, Debug, Clone)]
+struct PostRef {
+    title: String,
+    slug: String,
+    summary: String,
+    date: String,
+}
+
+// What the renderer knows about the post being rendered and its siblings
+struct RenderContext<'a> {
+    posts: &'a [PostRef],
+    sanitize: SanitizeProfile,
+    slug: &'a str,
+    bundle: Option<&'a std::path::Path>, // Page bundle directory; relative URLs resolve against it
+    disabled_stages: &'a [String],        // Render stages turned off by the post's front matter
+    line_offset: usize,                   // Lines above the Markdown in its file (front matter), for warnings
+    profile: RenderProfile,
+    site_url: &'a str,
+}
+
+// Where rendered HTML is going: the post page, or a feed reader (absolute URLs, nothing interactive)
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RenderProfile {
+    Page,
+    Feed,
+}
+
 // Parse metadata from Markdown file content (supports multiline values)
 fn parse_metadata(content: &str) -> Option<Metadata> {
     let parts: Vec<&str> = content.splitn(3, "---").collect();
@@ -255,6 +542,7 @@ fn parse_metadata(content: &str) -> Option<Metadata> {
             match key {
                 "title" => meta.title = value.to_string(),
                 "date" => meta.date = value.to_string(),
+                "updated" => meta.updated = Some(value.to_string()),
                 "tags" => {
                     // Support both array format [tag1, tag2] and comma-separated
                     let cleaned = value.trim_matches(|c| c == '[' || c == ']');
@@ -264,6 +552,14 @@ fn parse_metadata(content: &str) -> Option<Metadata> {
                         .filter(|s| !s.is_empty())
                         .collect();
                 }
+                "disable_stages" => {
+                    let cleaned = value.trim_matches(|c| c == '[' || c == ']');
+                    meta.disable_stages = cleaned
+                        .split(',')
+                        .map(|s| s.trim().trim_matches('"').trim_matches('\'').to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                }
                 "summary" => meta.summary = value.to_string(),
                 "author" => meta.author = Some(value.to_string()),
                 "image" => meta.image = Some(value.to_string()),
@@ -272,6 +568,10 @@ fn parse_metadata(content: &str) -> Option<Metadata> {
                 "canonical" => meta.canonical = Some(value.to_string()),
                 "github_repo" => meta.github_repo = Some(value.to_string()),
                 "website" | "homepage" => meta.website = Some(value.to_string()),
+                "enclosure" => meta.enclosure = Some(value.to_string()),
+                "enclosure_type" => meta.enclosure_type = Some(value.to_string()),
+                "enclosure_length" => meta.enclosure_length = Some(value.to_string()),
+                "enclosure_duration" => meta.enclosure_duration = Some(value.to_string()),
                 _ => {}
             }
         }
@@ -279,3214 +579,10347 @@ fn parse_metadata(content: &str) -> Option<Metadata> {
     Some(meta)
 }
 
-// Convert Markdown content to HTML with full feature support
-fn markdown_to_html(markdown: &str) -> String {
-    // Enable ALL markdown extensions
-    let options = Options::ENABLE_TABLES
-        | Options::ENABLE_FOOTNOTES
-        | Options::ENABLE_STRIKETHROUGH
-        | Options::ENABLE_TASKLISTS
-        | Options::ENABLE_SMART_PUNCTUATION
-        | Options::ENABLE_HEADING_ATTRIBUTES;
+// ============================================================================
+// Markdown Rendering - an ordered pipeline of stages over pulldown-cmark events
+// ============================================================================
 
-    let parser = Parser::new_ext(markdown, options);
-    
-    let mut html_output = String::new();
-    let mut in_code_block = false;
-    let mut code_lang = String::new();
-    let mut code_content = String::new();
-    let mut in_table_head = false;
-    let mut heading_level: Option<u32> = None;
-    let mut heading_text = String::new();
-    let mut heading_inner = String::new();
-    
-    let slugify = |text: &str| {
-        text.to_lowercase()
-            .chars()
-            .map(|c| if c.is_alphanumeric() { c } else { ' ' })
-            .collect::<String>()
-            .split_whitespace()
-            .collect::<Vec<_>>()
-            .join("-")
-    };
-    
-    let events: Vec<Event> = parser.collect();
-    let mut i = 0;
-    
-    while i < events.len() {
-        // If we're inside a heading, buffer content to add an id
-        if heading_level.is_some() {
-            match &events[i] {
-                Event::End(TagEnd::Heading(_)) => {
-                    let level = heading_level.take().unwrap_or(2);
-                    let slug = slugify(&heading_text);
-                    html_output.push_str(&format!("<h{} id=\"{}\">{}</h{}>", level, slug, heading_inner, level));
-                    heading_text.clear();
-                    heading_inner.clear();
-                    i += 1;
-                    continue;
-                }
-                Event::Text(t) => {
-                    heading_text.push_str(t);
-                    pulldown_cmark::html::push_html(&mut heading_inner, std::iter::once(events[i].clone()));
-                    i += 1;
-                    continue;
-                }
-                Event::Code(t) => {
-                    heading_text.push_str(t);
-                    pulldown_cmark::html::push_html(&mut heading_inner, std::iter::once(events[i].clone()));
-                    i += 1;
-                    continue;
-                }
-                Event::SoftBreak | Event::HardBreak => {
-                    heading_text.push(' ');
-                    pulldown_cmark::html::push_html(&mut heading_inner, std::iter::once(events[i].clone()));
-                    i += 1;
-                    continue;
-                }
-                _ => {
-                    pulldown_cmark::html::push_html(&mut heading_inner, std::iter::once(events[i].clone()));
-                    i += 1;
-                    continue;
-                }
-            }
-        }
-        
-        match &events[i] {
-            Event::Start(Tag::CodeBlock(kind)) => {
-                in_code_block = true;
-                code_content.clear();
-                code_lang = match kind {
-                    CodeBlockKind::Fenced(lang) => lang.to_string(),
-                    CodeBlockKind::Indented => String::new(),
-                };
-            }
-            Event::End(TagEnd::CodeBlock) => {
-                in_code_block = false;
-                // Apply syntax highlighting
-                let highlighted = highlight_code(&code_content, &code_lang);
-                html_output.push_str(&highlighted);
-            }
-            Event::Text(text) if in_code_block => {
-                code_content.push_str(text);
-            }
-            Event::Start(Tag::Heading { level, .. }) => {
-                let lvl = match level {
-                    pulldown_cmark::HeadingLevel::H1 => 1,
-                    pulldown_cmark::HeadingLevel::H2 => 2,
-                    pulldown_cmark::HeadingLevel::H3 => 3,
-                    pulldown_cmark::HeadingLevel::H4 => 4,
-                    pulldown_cmark::HeadingLevel::H5 => 5,
-                    pulldown_cmark::HeadingLevel::H6 => 6,
-                };
-                heading_level = Some(lvl);
-                heading_text.clear();
-                heading_inner.clear();
-            }
-            Event::Start(Tag::Table(_alignments)) => {
-                html_output.push_str("<div class=\"table-container\"><table>");
-            }
-            Event::End(TagEnd::Table) => {
-                html_output.push_str("</table></div>");
-            }
-            Event::Start(Tag::TableHead) => {
-                in_table_head = true;
-                html_output.push_str("<thead><tr>");
-            }
-            Event::End(TagEnd::TableHead) => {
-                in_table_head = false;
-                html_output.push_str("</tr></thead><tbody>");
-            }
-            Event::Start(Tag::TableRow) => {
-                html_output.push_str("<tr>");
-            }
-            Event::End(TagEnd::TableRow) => {
-                html_output.push_str("</tr>");
-            }
-            Event::Start(Tag::TableCell) => {
-                if in_table_head {
-                    html_output.push_str("<th>");
-                } else {
-                    html_output.push_str("<td>");
-                }
-            }
-            Event::End(TagEnd::TableCell) => {
-                if in_table_head {
-                    html_output.push_str("</th>");
-                } else {
-                    html_output.push_str("</td>");
-                }
-            }
-            Event::TaskListMarker(checked) => {
-                let checkbox = if *checked {
-                    r#"<input type="checkbox" checked disabled class="mr-2 h-4 w-4 rounded border-gray-300 text-indigo-600 bg-indigo-600 accent-indigo-600"> "#
-                } else {
-                    r#"<input type="checkbox" disabled class="mr-2 h-4 w-4 rounded border-gray-300 bg-gray-100 dark:bg-gray-700"> "#
-                };
-                html_output.push_str(checkbox);
-            }
-            Event::Start(Tag::FootnoteDefinition(name)) => {
-                html_output.push_str(&format!(
-                    r#"<div class="footnote" id="fn-{}"><sup>{}</sup> "#,
-                    name, name
-                ));
-            }
-            Event::End(TagEnd::FootnoteDefinition) => {
-                html_output.push_str("</div>");
-            }
-            Event::FootnoteReference(name) => {
-                html_output.push_str(&format!(
-                    "<sup><a href=\"#fn-{}\" class=\"footnote-ref\">[{}]</a></sup>",
-                    name, name
-                ));
-            }
-            Event::Start(Tag::Strikethrough) => {
-                html_output.push_str("<del class=\"line-through text-gray-500\">");
-            }
-            Event::End(TagEnd::Strikethrough) => {
-                html_output.push_str("</del>");
-            }
-            Event::Start(Tag::BlockQuote(_)) => {
-                html_output.push_str("<blockquote class=\"border-l-4 border-primary-500 pl-4 my-4 italic text-gray-600 dark:text-gray-400\">");
-            }
-            Event::End(TagEnd::BlockQuote(_)) => {
-                html_output.push_str("</blockquote>");
-            }
-            // Handle all other events with default HTML rendering
-            _ => {
-                let single_event = std::iter::once(events[i].clone());
-                pulldown_cmark::html::push_html(&mut html_output, single_event);
-            }
+// Every Markdown extension the blog supports
+const MARKDOWN_OPTIONS: Options = Options::ENABLE_TABLES
+    .union(Options::ENABLE_FOOTNOTES)
+    .union(Options::ENABLE_STRIKETHROUGH)
+    .union(Options::ENABLE_TASKLISTS)
+    .union(Options::ENABLE_SMART_PUNCTUATION)
+    .union(Options::ENABLE_HEADING_ATTRIBUTES)
+    .union(Options::ENABLE_GFM); // GitHub alerts: > [!NOTE], > [!WARNING], ...
+
+// A heading, for tables of contents
+#[derive(Serialize, Debug, Clone)]
+struct TocEntry {
+    level: u32,
+    id: String,
+    text: String,
+}
+
+// Everything rendering learns about a document, so callers don't re-derive it from the HTML
+#[derive(Debug, Default)]
+struct RenderedDocument {
+    html: String,
+    toc: Vec<TocEntry>,
+    text: String, // Plain text, whitespace collapsed
+    word_count: u32,
+    reading_time: u32,
+    images: Vec<String>, // img src, in document order
+    links: Vec<String>,  // a href, in document order, without duplicates
+    ids: std::collections::HashSet<String>,
+    warnings: Vec<RenderWarning>,
+    excerpt: Option<String>, // HTML above a `<!--more-->` line, when the post has one
+}
+
+// An authoring mistake found while rendering, at a line of the post's file
+#[derive(Serialize, Debug, Clone)]
+struct RenderWarning {
+    line: usize,
+    message: String,
+}
+
+impl RenderedDocument {
+    fn from_html(html: String, toc: Vec<TocEntry>, warnings: Vec<RenderWarning>) -> Self {
+        let (links, images, ids) = extract_links(&html);
+        let (reading_time, word_count) = calculate_reading_time(&html);
+        Self {
+            text: html_to_text(&html),
+            html,
+            toc,
+            word_count,
+            reading_time,
+            images,
+            links,
+            ids,
+            warnings,
+            excerpt: None,
         }
-        i += 1;
     }
-    
-    html_output
 }
 
-fn strip_html_tags(input: &str) -> String {
-    let mut output = String::with_capacity(input.len());
-    let mut in_tag = false;
-    for c in input.chars() {
-        match c {
-            '<' => in_tag = true,
-            '>' => in_tag = false,
-            _ if !in_tag => output.push(c),
-            _ => {}
-        }
+// State shared by the stages of one render
+struct RenderState<'c> {
+    ctx: &'c RenderContext<'c>,
+    fragments: Vec<String>,
+    toc: Vec<TocEntry>,
+    warnings: Vec<RenderWarning>,
+}
+
+impl RenderState<'_> {
+    // Record a warning at a byte offset into the Markdown being rendered
+    fn warn(&mut self, markdown: &str, offset: usize, message: String) {
+        let line = self.ctx.line_offset + markdown[..offset].matches('\n').count() + 1;
+        self.warnings.push(RenderWarning { line, message });
+    }
+
+    // Hold generated HTML back from later stages and sanitization; returns the event standing in for it
+    fn fragment(&mut self, html: String) -> Event<'static> {
+        let placeholder = fragment_placeholder(self.fragments.len());
+        self.fragments.push(html);
+        Event::Html(placeholder.into())
     }
-    output
 }
 
-// Syntax highlighting for code blocks
-fn highlight_code(code: &str, lang: &str) -> String {
-    let syntax = SYNTAX_SET
-        .find_syntax_by_token(lang)
-        .or_else(|| SYNTAX_SET.find_syntax_by_extension(lang))
-        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+// One step of rendering; each hook defaults to passing its input through unchanged
+trait RenderStage {
+    // Name used by the `disable_stages` front matter key
+    fn name(&self) -> &'static str;
 
-    let theme = &THEME_SET.themes["base16-ocean.dark"];
-    
-    match highlighted_html_for_string(code, &SYNTAX_SET, syntax, theme) {
-        Ok(html) => {
-            format!(
-                r#"<div class="code-block relative my-4 rounded-lg overflow-hidden">
-                    <div class="code-header flex items-center justify-between px-4 py-2 bg-gray-800 text-gray-400 text-xs">
-                        <span class="code-lang font-mono">{}</span>
-                        <button class="copy-btn hover:text-white transition-colors" onclick="copyCode(this)">
-                            <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 16H6a2 2 0 01-2-2V6a2 2 0 012-2h8a2 2 0 012 2v2m-6 12h8a2 2 0 002-2v-8a2 2 0 00-2-2h-8a2 2 0 00-2 2v8a2 2 0 002 2z"></path>
-                            </svg>
-                        </button>
-                    </div>
-                    <div class="code-content overflow-x-auto">{}</div>
-                </div>"#,
-                if lang.is_empty() { "text" } else { lang },
-                html
-            )
+    // Markdown source, before parsing
+    fn preprocess(&mut self, markdown: String, _state: &mut RenderState) -> String {
+        markdown
+    }
+
+    // The whole event stream, as left by the stages registered before this one
+    fn transform<'a>(&mut self, events: Vec<Event<'a>>, _state: &mut RenderState) -> Vec<Event<'a>> {
+        events
+    }
+
+    // Serialized HTML, before fragments are spliced back in
+    fn postprocess(&mut self, html: String, _state: &mut RenderState) -> String {
+        html
+    }
+
+    // Final HTML, fragments included
+    fn finalize(&mut self, html: String, _state: &mut RenderState) -> String {
+        html
+    }
+}
+
+struct RenderPipeline {
+    stages: Vec<Box<dyn RenderStage>>,
+}
+
+// Stages `disable_stages` can't turn off: without them author HTML would reach pages and feeds unfiltered
+const REQUIRED_STAGES: [&str; 2] = ["sanitize", "feed"];
+
+impl RenderPipeline {
+    fn new() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    // Stages run in registration order, at each hook
+    fn register(mut self, stage: impl RenderStage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    // The standard stages, minus any the post turns off in its front matter (except REQUIRED_STAGES)
+    fn for_post(ctx: &RenderContext) -> Self {
+        let mut pipeline = Self::new()
+            .register(LintStage)
+            .register(ShortcodeStage)
+            .register(ExcerptStage)
+            .register(BundleUrlStage)
+            .register(LinkPreviewStage)
+            .register(ImageStage)
+            .register(CodeBlockStage)
+            .register(HeadingIdStage)
+            .register(CalloutStage)
+            .register(MarkupStage)
+            .register(FootnoteStage)
+            .register(SanitizeStage(ctx.sanitize));
+        if ctx.profile == RenderProfile::Feed {
+            pipeline = pipeline.register(FeedStage);
         }
-        Err(_) => {
-            format!(
-                r#"<pre class="bg-gray-900 text-gray-100 p-4 rounded-lg overflow-x-auto my-4"><code class="language-{}">{}</code></pre>"#,
-                lang,
-                html_escape::encode_text(code)
-            )
+        pipeline.stages.retain(|stage| {
+            REQUIRED_STAGES.contains(&stage.name()) || !ctx.disabled_stages.iter().any(|name| name == stage.name())
+        });
+        pipeline
+    }
+
+    // Only the stages that report warnings, for checking a draft as it's typed
+    fn for_lint() -> Self {
+        Self::new().register(LintStage).register(ShortcodeStage)
+    }
+
+    fn render(mut self, markdown: &str, ctx: &RenderContext) -> RenderedDocument {
+        let mut state = RenderState { ctx, fragments: Vec::new(), toc: Vec::new(), warnings: Vec::new() };
+
+        let mut markdown = markdown.to_string();
+        for stage in &mut self.stages {
+            markdown = stage.preprocess(markdown, &mut state);
+        }
+
+        let mut events: Vec<Event> = Parser::new_ext(&markdown, MARKDOWN_OPTIONS).collect();
+        for stage in &mut self.stages {
+            events = stage.transform(events, &mut state);
+        }
+
+        let mut html = String::new();
+        pulldown_cmark::html::push_html(&mut html, events.into_iter());
+        for stage in &mut self.stages {
+            html = stage.postprocess(html, &mut state);
+        }
+
+        let html = splice_fragments(&html, &state.fragments);
+        // The excerpt is the rendered document cut at the marker, so it is never rendered twice
+        let (mut html, mut excerpt) = match html.split_once(EXCERPT_MARKER) {
+            Some((above, below)) => (format!("{}{}", above, below), Some(above.to_string())),
+            None => (html, None),
+        };
+        for stage in &mut self.stages {
+            html = stage.finalize(html, &mut state);
+            excerpt = excerpt.map(|excerpt| stage.finalize(excerpt, &mut state));
         }
+
+        state.warnings.sort_by_key(|w| w.line);
+        let mut document = RenderedDocument::from_html(html, state.toc, state.warnings);
+        document.excerpt = excerpt;
+        document
     }
 }
 
-// Calculate reading time based on word count
-fn calculate_reading_time(content: &str) -> (u32, u32) {
-    let word_count = content.split_whitespace().count() as u32;
-    let reading_time = (word_count / 200).max(1); // Average 200 words per minute
-    (reading_time, word_count)
+// Render a post's Markdown with the standard pipeline
+fn render_markdown(markdown: &str, ctx: &RenderContext) -> RenderedDocument {
+    RenderPipeline::for_post(ctx).render(markdown, ctx)
 }
 
-// Retrieve all blog posts from content directory
-fn get_posts(site_config: &SiteConfig) -> Vec<Post> {
-    let content_dir = PathBuf::from("content");
-    let mut posts = Vec::new();
-
-    if let Ok(entries) = fs::read_dir(content_dir) {
-        for entry in entries.flatten() {
-            if entry.path().extension().map(|s| s == "md").unwrap_or(false) {
-                if let Ok(content) = fs::read_to_string(entry.path()) {
-                    if let Some(metadata) = parse_metadata(&content) {
-                        let content_str = content.splitn(3, "---").nth(2).unwrap_or("");
-                        let html_content = markdown_to_html(content_str);
-                        let (reading_time, word_count) = calculate_reading_time(content_str);
-
-                        let date = match DateTime::parse_from_str(
-                            &format!("{} 00:00:00 +0000", metadata.date),
-                            "%Y-%m-%d %H:%M:%S %z",
-                        ) {
-                            Ok(d) => Local.from_utc_datetime(&d.naive_utc()),
-                            Err(_) => entry
-                                .metadata()
-                                .ok()
-                                .and_then(|m| m.modified().ok())
-                                .map(|t| DateTime::<Local>::from(t))
-                                .unwrap_or_else(Local::now),
-                        };
-
-                        let slug = metadata.title.to_lowercase()
-                            .chars()
-                            .map(|c| if c.is_alphanumeric() || c == ' ' { c } else { ' ' })
-                            .collect::<String>()
-                            .split_whitespace()
-                            .collect::<Vec<_>>()
-                            .join("-");
-
-                        let tags_clone = metadata.tags.clone();
-                        let post = Post {
-                            title: metadata.title.clone(),
-                            content: html_content,
-                            summary: if metadata.summary.is_empty() {
-                                content_str.chars().take(160).collect::<String>() + "..."
-                            } else {
-                                metadata.summary
-                            },
-                            date: date.format("%B %d, %Y").to_string(),
-                            date_iso: date.format("%Y-%m-%dT%H:%M:%S%z").to_string(),
-                            tags: tags_clone.clone(),
-                            filename: entry.file_name().to_str().unwrap_or("").to_string(),
-                            slug: slug.clone(),
-                            author: metadata.author.unwrap_or_else(|| site_config.author.clone()),
-                            image: metadata.image.unwrap_or_else(|| format!("{}/og-default.png", site_config.url)),
-                            image_alt: metadata.image_alt.unwrap_or_else(|| metadata.title.clone()),
-                            keywords: metadata.keywords.unwrap_or_else(|| {
-                                tags_clone.iter().take(5).cloned().collect::<Vec<_>>().join(", ")
-                            }),
-                            canonical: metadata.canonical.unwrap_or_else(|| {
-                                format!("{}/blog/{}", site_config.url, slug)
-                            }),
-                            reading_time,
-                            word_count,
-                            website: metadata.website,
-                            github_repo: metadata.github_repo,
-                        };
-                        posts.push(post);
-                    }
+// Index of the event closing the element opened at `events[start]` (same depth)
+fn matching_end(events: &[Event], start: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, event) in events.iter().enumerate().skip(start) {
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
                 }
             }
+            _ => {}
         }
     }
-
-    posts.sort_by(|a, b| b.date_iso.cmp(&a.date_iso));
-    posts
+    events.len() - 1
 }
 
-// Home route handler
-async fn index(Extension(hb): Extension<Arc<Handlebars<'_>>>) -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    let posts_count = posts.len();
-    
-    let mut data = HashMap::new();
-    data.insert("posts", serde_json::to_value(&posts).unwrap());
-    data.insert("posts_count", serde_json::to_value(posts_count).unwrap());
-    data.insert("site", serde_json::to_value(&site_config).unwrap());
-    data.insert("current_year", serde_json::to_value(Local::now().format("%Y").to_string()).unwrap());
-    data.insert("current_date_iso", serde_json::to_value(Local::now().format("%Y-%m-%dT%H:%M:%S%:z").to_string()).unwrap());
-    
-    match hb.render("index.html", &data) {
-        Ok(rendered) => Html(rendered).into_response(),
-        Err(e) => {
-            eprintln!("Failed to render index template: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Template rendering error").into_response()
+// Text content of a run of events (alt text, heading text)
+fn events_text(events: &[Event]) -> String {
+    let mut text = String::new();
+    for event in events {
+        match event {
+            Event::Text(t) | Event::Code(t) => text.push_str(t),
+            Event::SoftBreak | Event::HardBreak => text.push(' '),
+            _ => {}
         }
     }
+    text
 }
 
-// Tag listing route handler
-async fn tag_page(
-    Extension(hb): Extension<Arc<Handlebars<'_>>>,
-    Path(tag): Path<String>,
-) -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let all_posts = get_posts(&site_config);
-    let tag_normalized = tag.to_lowercase();
+// Authoring mistakes, found in the source as written; registered first so line numbers match the file
+struct LintStage;
 
-    let filtered_posts: Vec<Post> = all_posts
-        .into_iter()
-        .filter(|p| p
-            .tags
-            .iter()
-            .any(|t| t.to_lowercase() == tag_normalized))
-        .collect();
+impl RenderStage for LintStage {
+    fn name(&self) -> &'static str {
+        "lint"
+    }
 
-    let mut data = HashMap::new();
-    data.insert("posts", serde_json::to_value(&filtered_posts).unwrap());
-    data.insert("posts_count", serde_json::to_value(filtered_posts.len()).unwrap());
-    // Reuse the index template; tweak description to show the tag context
-    let mut site_override = site_config.clone();
-    site_override.description = format!("Posts tagged with '{}'.", tag);
-    data.insert("site", serde_json::to_value(&site_override).unwrap());
-    data.insert("current_year", serde_json::to_value(Local::now().format("%Y").to_string()).unwrap());
+    fn preprocess(&mut self, markdown: String, state: &mut RenderState) -> String {
+        let (events, ranges): (Vec<Event>, Vec<std::ops::Range<usize>>) =
+            Parser::new_ext(&markdown, MARKDOWN_OPTIONS).into_offset_iter().unzip();
+        let mut heading_ids: HashMap<String, usize> = HashMap::new();
+        let mut last_level = 1; // The post title is the page's h1
 
-    match hb.render("index.html", &data) {
-        Ok(rendered) => Html(rendered).into_response(),
-        Err(e) => {
-            eprintln!("Failed to render tag template: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Template rendering error").into_response()
+        for name in state.ctx.disabled_stages.iter().filter(|name| REQUIRED_STAGES.contains(&name.as_str())) {
+            state.warn(&markdown, 0, format!("`disable_stages` can't turn off `{}`; it always runs", name));
+        }
+
+        for (i, event) in events.iter().enumerate() {
+            let Event::Start(tag) = event else { continue };
+            let start = ranges[i].start;
+            match tag {
+                Tag::CodeBlock(CodeBlockKind::Fenced(info)) => {
+                    let lang = info.split_whitespace().next().unwrap_or("");
+                    if !lang.is_empty() && !is_known_language(lang) {
+                        state.warn(&markdown, start, format!("Unknown code block language `{}`; shown as plain text", lang));
+                    }
+                    if !fence_is_closed(&markdown[ranges[i].clone()]) {
+                        state.warn(&markdown, start, "Code fence is never closed; the rest of the post renders as code".into());
+                    }
+                }
+                Tag::Image { .. } if events_text(&events[i + 1..matching_end(&events, i)]).trim().is_empty() => {
+                    state.warn(&markdown, start, "Image has no alt text".into());
+                }
+                Tag::Link { dest_url, .. } => {
+                    let end = matching_end(&events, i);
+                    let has_image = events[i + 1..end].iter().any(|e| matches!(e, Event::Start(Tag::Image { .. })));
+                    if dest_url.trim().is_empty() {
+                        state.warn(&markdown, start, "Link has no URL".into());
+                    } else if !has_image && events_text(&events[i + 1..end]).trim().is_empty() {
+                        state.warn(&markdown, start, format!("Link to {} has no text", dest_url));
+                    }
+                }
+                Tag::Heading { level, id, .. } => {
+                    let level = *level as u32;
+                    if level > last_level + 1 {
+                        state.warn(&markdown, start, format!("Heading skips from h{} to h{}", last_level, level));
+                    }
+                    last_level = level;
+
+                    let text = events_text(&events[i + 1..matching_end(&events, i)]);
+                    let slug = id.as_deref().map(str::to_string).unwrap_or_else(|| heading_slug(&text));
+                    let line = state.ctx.line_offset + markdown[..start].matches('\n').count() + 1;
+                    if let Some(first) = heading_ids.get(&slug) {
+                        state.warn(&markdown, start, format!("Duplicate heading id `{}` (first used on line {})", slug, first));
+                    } else if !slug.is_empty() {
+                        heading_ids.insert(slug, line);
+                    }
+                }
+                _ => {}
+            }
         }
+        markdown
     }
 }
 
-// Single post route handler
-async fn single_post(
-    Extension(hb): Extension<Arc<Handlebars<'_>>>,
-    Path(post_title): Path<String>,
-) -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    let post = posts.into_iter().find(|p| p.slug == post_title);
+// Whether a fenced code block's source ends with a closing fence of the same kind and length
+fn fence_is_closed(block: &str) -> bool {
+    let mut lines = block.trim_end().lines();
+    let opening = lines.next().unwrap_or("").trim_start();
+    let Some(marker) = opening.chars().next() else { return true };
+    let width = opening.chars().take_while(|&c| c == marker).count();
+    lines.next_back().is_some_and(|closing| {
+        let closing = closing.trim();
+        closing.chars().all(|c| c == marker) && closing.chars().count() >= width
+    })
+}
 
-    if let Some(post) = post {
-        let mut data = HashMap::new();
-        data.insert("post", serde_json::to_value(&post).unwrap());
-        data.insert("site", serde_json::to_value(&site_config).unwrap());
-        data.insert("current_year", serde_json::to_value(Local::now().format("%Y").to_string()).unwrap());
-        
-        match hb.render("single.html", &data) {
-            Ok(rendered) => Html(rendered).into_response(),
-            Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Template rendering error").into_response(),
+// Shortcodes are swapped for placeholders before parsing so Markdown never touches their HTML
+struct ShortcodeStage;
+
+impl RenderStage for ShortcodeStage {
+    fn name(&self) -> &'static str {
+        "shortcodes"
+    }
+
+    fn preprocess(&mut self, markdown: String, state: &mut RenderState) -> String {
+        expand_shortcodes(&markdown, state)
+    }
+}
+
+// Stands in for a `<!--more-->` line until the document is cut into excerpt and body; a private-use
+// string the sanitizer never sees, so it can't be confused with an author's own comment
+const EXCERPT_MARKER: &str = "\u{E000}more\u{E001}";
+
+// The first top-level `<!--more-->` line marks the end of the excerpt
+struct ExcerptStage;
+
+impl RenderStage for ExcerptStage {
+    fn name(&self) -> &'static str {
+        "excerpt"
+    }
+
+    fn transform<'a>(&mut self, events: Vec<Event<'a>>, state: &mut RenderState) -> Vec<Event<'a>> {
+        let mut found = false;
+        let mut depth = 0usize;
+        let mut out = Vec::with_capacity(events.len());
+        let mut i = 0;
+        while i < events.len() {
+            if !found && depth == 0 && matches!(events[i], Event::Start(Tag::HtmlBlock)) {
+                let end = matching_end(&events, i);
+                let block: String = events[i + 1..end]
+                    .iter()
+                    .filter_map(|e| if let Event::Html(html) = e { Some(html.as_ref()) } else { None })
+                    .collect();
+                if block.trim().eq_ignore_ascii_case("<!--more-->") {
+                    found = true;
+                    out.push(state.fragment(EXCERPT_MARKER.to_string()));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            match events[i] {
+                Event::Start(_) => depth += 1,
+                Event::End(_) => depth -= 1,
+                _ => {}
+            }
+            out.push(events[i].clone());
+            i += 1;
         }
-    } else {
-        (StatusCode::NOT_FOUND, "Post not found").into_response()
+        out
     }
 }
 
-// Generate Web App Manifest for PWA support and better SEO
-async fn manifest_json() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let manifest = format!(
-        r##"{{
-  "name": "{name}",
-  "short_name": "{short_name}",
-  "description": "{description}",
-  "start_url": "/",
-  "display": "standalone",
-  "background_color": "#000000",
-  "theme_color": "{theme_color}",
-  "orientation": "portrait-primary",
-  "scope": "/",
-  "lang": "{language}",
-  "dir": "ltr",
-  "categories": ["education", "technology", "programming"],
-  "icons": [
-    {{
-      "src": "/logo.webp",
-      "sizes": "32x32",
-      "type": "image/webp",
-      "purpose": "any"
-    }},
-    {{
-      "src": "/logo.webp",
-      "sizes": "192x192",
-      "type": "image/webp",
-      "purpose": "any maskable"
-    }},
-    {{
-      "src": "/logo.webp",
-      "sizes": "512x512",
-      "type": "image/webp",
-      "purpose": "any maskable"
-    }}
-  ],
-  "prefer_related_applications": false,
-  "shortcuts": [
-    {{
-      "name": "All Posts",
-      "short_name": "Posts",
-      "description": "View all blog posts",
-      "url": "/",
-      "icons": [{{ "src": "/logo.webp", "sizes": "96x96", "type": "image/webp" }}]
-    }}
-  ],
-  "related_applications": [],
-  "id": "{url}/"
-}}"##,
-        name = site_config.title,
-        short_name = site_config.author,
-        description = site_config.description,
-        theme_color = site_config.theme_color,
-        language = site_config.language,
-        url = site_config.url
-    );
-    
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/manifest+json; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=86400")
-        .body(manifest)
-        .unwrap()
+// Relative links and raw HTML URLs in a page bundle point at the bundle's own files
+struct BundleUrlStage;
+
+impl RenderStage for BundleUrlStage {
+    fn name(&self) -> &'static str {
+        "bundle-urls"
+    }
+
+    fn transform<'a>(&mut self, events: Vec<Event<'a>>, state: &mut RenderState) -> Vec<Event<'a>> {
+        let ctx = state.ctx;
+        if ctx.bundle.is_none() {
+            return events;
+        }
+        events
+            .into_iter()
+            .map(|event| match event {
+                Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+                    let dest_url = match bundle_asset(&dest_url, ctx) {
+                        Some((url, _)) => url.into(),
+                        None => dest_url,
+                    };
+                    Event::Start(Tag::Link { link_type, dest_url, title, id })
+                }
+                Event::Html(html) => match rewrite_html_urls(&html, &mut |url, _| bundle_asset(url, ctx).map(|(url, _)| url)) {
+                    Some(resolved) => Event::Html(resolved.into()),
+                    None => Event::Html(html),
+                },
+                Event::InlineHtml(html) => match rewrite_html_urls(&html, &mut |url, _| bundle_asset(url, ctx).map(|(url, _)| url)) {
+                    Some(resolved) => Event::InlineHtml(resolved.into()),
+                    None => Event::InlineHtml(html),
+                },
+                other => other,
+            })
+            .collect()
+    }
 }
 
-// Generate browserconfig.xml for Windows tiles
-async fn browserconfig_xml() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let xml = format!(
-        r#"<?xml version="1.0" encoding="utf-8"?>
-<browserconfig>
-  <msapplication>
-    <tile>
-      <square70x70logo src="/logo.webp"/>
-      <square150x150logo src="/logo.webp"/>
-      <square310x310logo src="/logo.webp"/>
-      <TileColor>{}</TileColor>
-    </tile>
-  </msapplication>
-</browserconfig>"#,
-        site_config.theme_color
-    );
-    
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=86400")
-        .body(xml)
-        .unwrap()
+// A paragraph that is only a URL becomes a preview card
+struct LinkPreviewStage;
+
+impl RenderStage for LinkPreviewStage {
+    fn name(&self) -> &'static str {
+        "link-previews"
+    }
+
+    fn transform<'a>(&mut self, events: Vec<Event<'a>>, state: &mut RenderState) -> Vec<Event<'a>> {
+        let mut out = Vec::with_capacity(events.len());
+        let mut i = 0;
+        while i < events.len() {
+            if let Event::Start(Tag::Paragraph) = &events[i] {
+                if let Some((url, len)) = bare_url_paragraph(&events[i + 1..]) {
+                    out.push(state.fragment(render_link_preview(&url)));
+                    i += len + 2;
+                    continue;
+                }
+            }
+            out.push(events[i].clone());
+            i += 1;
+        }
+        out
+    }
 }
 
-// Generate humans.txt for attribution
-async fn humans_txt() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let content = format!(
-        r#"/* TEAM */
-Author: {}
-Site: {}
-Twitter: {}
-GitHub: https://github.com/{}
-LinkedIn: https://linkedin.com/in/{}
-Location: India
+// Images become <picture> sets (local files) or lazy-loaded <img>, with the alt text collected
+struct ImageStage;
 
-/* SITE */
-Last update: {}
-Language: {}
-Doctype: HTML5
-IDE: VS Code
-Standards: HTML5, CSS3, ES6+
+impl RenderStage for ImageStage {
+    fn name(&self) -> &'static str {
+        "images"
+    }
 
-/* TECHNOLOGY */
-Backend: Rust, Axum
-Frontend: HTML5, CSS3, JavaScript
-Template: Handlebars
-Hosting: Leapcell
+    fn transform<'a>(&mut self, events: Vec<Event<'a>>, state: &mut RenderState) -> Vec<Event<'a>> {
+        let mut out = Vec::with_capacity(events.len());
+        let mut i = 0;
+        while i < events.len() {
+            if let Event::Start(Tag::Image { dest_url, title, .. }) = &events[i] {
+                let end = matching_end(&events, i);
+                let alt = events_text(&events[i + 1..end]);
+                out.push(state.fragment(render_image(dest_url, title, &alt, state.ctx)));
+                i = end + 1;
+                continue;
+            }
+            out.push(events[i].clone());
+            i += 1;
+        }
+        out
+    }
+}
 
-/* THANKS */
-Rust Community
-Axum Framework
-Open Source Contributors
-"#,
-        site_config.author_full_name,
-        site_config.url,
-        site_config.twitter_handle,
-        site_config.github_handle,
-        site_config.linkedin_handle,
-        Local::now().format("%Y-%m-%d"),
-        site_config.language
-    );
-    
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=86400")
-        .body(content)
-        .unwrap()
+// Fenced code: diagrams render to inline SVG; anything else (or a failed render) gets syntax highlighting
+struct CodeBlockStage;
+
+impl RenderStage for CodeBlockStage {
+    fn name(&self) -> &'static str {
+        "code-blocks"
+    }
+
+    fn transform<'a>(&mut self, events: Vec<Event<'a>>, state: &mut RenderState) -> Vec<Event<'a>> {
+        let mut out = Vec::with_capacity(events.len());
+        let mut i = 0;
+        while i < events.len() {
+            if let Event::Start(Tag::CodeBlock(kind)) = &events[i] {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) => lang.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                let end = matching_end(&events, i);
+                let code: String = events[i + 1..end]
+                    .iter()
+                    .filter_map(|e| if let Event::Text(t) = e { Some(t.as_ref()) } else { None })
+                    .collect();
+                let diagram = match lang.as_str() {
+                    "mermaid" | "dot" | "graphviz" => render_diagram(&code, &lang),
+                    _ => None,
+                };
+                let block = diagram.unwrap_or_else(|| match state.ctx.profile {
+                    RenderProfile::Page => highlight_code(&code, &lang),
+                    RenderProfile::Feed => plain_code_block(&code, &lang),
+                });
+                out.push(state.fragment(block));
+                i = end + 1;
+                continue;
+            }
+            out.push(events[i].clone());
+            i += 1;
+        }
+        out
+    }
 }
 
-// Generate security.txt for security researchers
-async fn security_txt() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let content = format!(
-        r#"# Security Policy
-Contact: https://github.com/{}
-Expires: 2027-12-31T23:59:59.000Z
-Preferred-Languages: en
-Canonical: {}/.well-known/security.txt
-"#,
-        site_config.github_handle,
-        site_config.url
-    );
-    
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=86400")
-        .body(content)
-        .unwrap()
+// URL slug for a heading's text
+fn heading_slug(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
 }
 
-// Serve logo.webp as favicon
-async fn serve_logo() -> impl IntoResponse {
-    match fs::read("logo.webp") {
-        Ok(data) => Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "image/webp")
-            .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
-            .body(axum::body::Body::from(data))
-            .unwrap(),
-        Err(_) => Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .body(axum::body::Body::from("Logo not found"))
-            .unwrap(),
+// Headings get an id from their text (unless given one with {#id}) and are recorded for the TOC
+struct HeadingIdStage;
+
+impl RenderStage for HeadingIdStage {
+    fn name(&self) -> &'static str {
+        "heading-ids"
+    }
+
+    fn transform<'a>(&mut self, mut events: Vec<Event<'a>>, state: &mut RenderState) -> Vec<Event<'a>> {
+        for i in 0..events.len() {
+            if let Event::Start(Tag::Heading { level, id, .. }) = &events[i] {
+                let text = events_text(&events[i + 1..matching_end(&events, i)]);
+                let slug = id.as_deref().map(str::to_string).unwrap_or_else(|| heading_slug(&text));
+                state.toc.push(TocEntry { level: *level as u32, id: slug.clone(), text });
+                if let Event::Start(Tag::Heading { id, .. }) = &mut events[i] {
+                    *id = Some(slug.into());
+                }
+            }
+        }
+        events
     }
 }
 
-// Generate dynamic OG image for blog posts
-async fn og_image(Path(slug): Path<String>) -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    
-    // Find the post by slug
-    let post = posts.iter().find(|p| p.slug == slug);
-    
-    let (title, author, date, reading_time, tags) = match post {
-        Some(p) => (
-            p.title.clone(),
-            p.author.clone(),
-            p.date.clone(),
-            format!("{} min read", p.reading_time),
-            p.tags.join(" · "),
-        ),
-        None => (
-            "Post Not Found".to_string(),
-            site_config.author_full_name.clone(),
-            "".to_string(),
-            "".to_string(),
-            "".to_string(),
-        ),
-    };
-    
-    // Generate the OG image
-    match generate_og_image(&title, &author, &date, &reading_time, &tags, &site_config) {
-        Ok(png_data) => Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "image/png")
-            .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
-            .body(axum::body::Body::from(png_data))
-            .unwrap(),
-        Err(e) => {
-            eprintln!("Failed to generate OG image: {}", e);
-            Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .body(axum::body::Body::from("Failed to generate image"))
-                .unwrap()
-        }
+// GitHub-style alerts (> [!NOTE] ...) become callouts
+struct CalloutStage;
+
+impl RenderStage for CalloutStage {
+    fn name(&self) -> &'static str {
+        "callouts"
     }
-}
 
-// Generate default OG image for homepage
-async fn og_image_default() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    
-    match generate_og_image_home(&site_config) {
-        Ok(png_data) => Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "image/png")
-            .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
-            .body(axum::body::Body::from(png_data))
-            .unwrap(),
-        Err(e) => {
-            eprintln!("Failed to generate OG image: {}", e);
-            Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .body(axum::body::Body::from("Failed to generate image"))
-                .unwrap()
-        }
+    fn transform<'a>(&mut self, events: Vec<Event<'a>>, _state: &mut RenderState) -> Vec<Event<'a>> {
+        events
+            .into_iter()
+            .map(|event| match event {
+                Event::Start(Tag::BlockQuote(Some(kind))) => Event::Html(callout_open(kind).into()),
+                Event::End(TagEnd::BlockQuote(Some(_))) => Event::Html("</div>".into()),
+                other => other,
+            })
+            .collect()
     }
 }
 
-// Redirect /blog to home page
-async fn blog_redirect() -> impl IntoResponse {
-    Redirect::permanent("/")
+// The site's classes on tables, task list checkboxes, strikethrough and plain blockquotes
+struct MarkupStage;
+
+impl RenderStage for MarkupStage {
+    fn name(&self) -> &'static str {
+        "markup"
+    }
+
+    fn transform<'a>(&mut self, events: Vec<Event<'a>>, _state: &mut RenderState) -> Vec<Event<'a>> {
+        let mut in_table_head = false;
+        events
+            .into_iter()
+            .map(|event| {
+                let html = match &event {
+                    Event::Start(Tag::Table(_)) => "<div class=\"table-container\"><table>",
+                    Event::End(TagEnd::Table) => "</tbody></table></div>",
+                    Event::Start(Tag::TableHead) => {
+                        in_table_head = true;
+                        "<thead><tr>"
+                    }
+                    Event::End(TagEnd::TableHead) => {
+                        in_table_head = false;
+                        "</tr></thead><tbody>"
+                    }
+                    Event::Start(Tag::TableRow) => "<tr>",
+                    Event::End(TagEnd::TableRow) => "</tr>",
+                    Event::Start(Tag::TableCell) => if in_table_head { "<th>" } else { "<td>" },
+                    Event::End(TagEnd::TableCell) => if in_table_head { "</th>" } else { "</td>" },
+                    Event::TaskListMarker(true) => {
+                        r#"<input type="checkbox" checked disabled class="mr-2 h-4 w-4 rounded border-gray-300 text-indigo-600 bg-indigo-600 accent-indigo-600"> "#
+                    }
+                    Event::TaskListMarker(false) => {
+                        r#"<input type="checkbox" disabled class="mr-2 h-4 w-4 rounded border-gray-300 bg-gray-100 dark:bg-gray-700"> "#
+                    }
+                    Event::Start(Tag::Strikethrough) => "<del class=\"line-through text-gray-500\">",
+                    Event::End(TagEnd::Strikethrough) => "</del>",
+                    Event::Start(Tag::BlockQuote(None)) => {
+                        "<blockquote class=\"border-l-4 border-primary-500 pl-4 my-4 italic text-gray-600 dark:text-gray-400\">"
+                    }
+                    Event::End(TagEnd::BlockQuote(None)) => "</blockquote>",
+                    _ => return event,
+                };
+                Event::InlineHtml(html.into())
+            })
+            .collect()
+    }
 }
 
-// Generate recent posts OG image for /blog/recents.png
-async fn og_image_recents() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    
-    match generate_og_image_recents(&posts, &site_config) {
-        Ok(png_data) => Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "image/png")
-            .header(header::CACHE_CONTROL, "public, max-age=3600") // Cache for 1 hour since it updates
-            .body(axum::body::Body::from(png_data))
-            .unwrap(),
-        Err(e) => {
-            eprintln!("Failed to generate recents OG image: {}", e);
-            Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .body(axum::body::Body::from("Failed to generate image"))
-                .unwrap()
+// Footnotes are numbered by first reference; definitions move to an endnotes section
+struct FootnoteStage;
+
+impl RenderStage for FootnoteStage {
+    fn name(&self) -> &'static str {
+        "footnotes"
+    }
+
+    fn transform<'a>(&mut self, events: Vec<Event<'a>>, _state: &mut RenderState) -> Vec<Event<'a>> {
+        let mut numbers: HashMap<String, usize> = HashMap::new();
+        let mut ref_counts: Vec<usize> = Vec::new();
+        let mut defs: HashMap<String, String> = HashMap::new();
+        let mut out = Vec::with_capacity(events.len());
+        // The definition being collected; it's rendered on its own and placed in the endnotes
+        let mut definition: Option<(String, Vec<Event<'a>>)> = None;
+
+        for event in events {
+            let event = match event {
+                Event::Start(Tag::FootnoteDefinition(name)) => {
+                    definition = Some((name.to_string(), Vec::new()));
+                    continue;
+                }
+                Event::End(TagEnd::FootnoteDefinition) => {
+                    if let Some((name, inner)) = definition.take() {
+                        let mut html = String::new();
+                        pulldown_cmark::html::push_html(&mut html, inner.into_iter());
+                        defs.insert(name, html);
+                    }
+                    continue;
+                }
+                Event::FootnoteReference(name) => {
+                    let next_number = numbers.len() + 1;
+                    let number = *numbers.entry(name.to_string()).or_insert(next_number);
+                    if number > ref_counts.len() {
+                        ref_counts.push(0);
+                    }
+                    ref_counts[number - 1] += 1;
+                    let ref_id = match ref_counts[number - 1] {
+                        1 => format!("fnref-{}", number),
+                        n => format!("fnref-{}-{}", number, n),
+                    };
+                    Event::InlineHtml(
+                        format!(
+                            r##"<sup class="footnote-ref"><a href="#fn-{n}" id="{ref_id}" role="doc-noteref" aria-describedby="footnotes-label">{n}</a></sup>"##,
+                            n = number,
+                            ref_id = ref_id
+                        )
+                        .into(),
+                    )
+                }
+                other => other,
+            };
+            match &mut definition {
+                Some((_, inner)) => inner.push(event),
+                None => out.push(event),
+            }
+        }
+
+        if !numbers.is_empty() {
+            out.push(Event::Html(render_footnotes(&numbers, &ref_counts, &mut defs).into()));
         }
+        out
     }
 }
-fn generate_og_image(
-    title: &str,
-    _author: &str,
-    date: &str,
-    reading_time: &str,
-    tags: &str,
-    _site_config: &SiteConfig,
-) -> Result<Vec<u8>, String> {
-    // Embed logo as base64
-    static LOGO_WEBP: &[u8] = include_bytes!("../logo-small.webp");
-    let logo_base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LOGO_WEBP);
-    
-    // Calculate how many lines we need based on title length
-    let title_char_count = title.len();
-    
-    // Dynamic sizing based on title length - allow up to 5 lines for very long titles
-    let (max_lines, mut title_font_size, title_y_start) = if title_char_count <= 40 {
-        // Short title - 1-2 lines, large font
-        (2, 72, 180)
-    } else if title_char_count <= 80 {
-        // Medium title - 2-3 lines
-        (3, 60, 150)
-    } else if title_char_count <= 120 {
-        // Long title - 3-4 lines
-        (4, 52, 120)
-    } else {
-        // Very long title - 4-5 lines, smaller font
-        (5, 44, 100)
-    };
 
-    let title_x = 100;
-    let title_max_width_px = (1200 - title_x - 100) as f32; // keep 100px right padding to avoid clipping
+// Author HTML is filtered through the post's allowlist profile; fragments are already trusted
+struct SanitizeStage(SanitizeProfile);
 
-    // Wrap based on an estimated pixel width (more reliable than char-count for large fonts).
-    // If it still doesn't fit within max_lines, step the font size down until it does.
-    let mut title_lines = wrap_text_to_width(title, title_max_width_px, title_font_size);
-    while title_lines.len() > max_lines && title_font_size > 32 {
-        title_font_size -= 4;
-        title_lines = wrap_text_to_width(title, title_max_width_px, title_font_size);
+impl RenderStage for SanitizeStage {
+    fn name(&self) -> &'static str {
+        "sanitize"
     }
 
-    let line_height = ((title_font_size as f32) * 1.18).round() as i32;
-    let title_line_count = title_lines.len().min(max_lines);
-    
-    // Build title SVG elements with gradient text
-    let mut title_svg = String::new();
-    for (i, line) in title_lines.iter().take(max_lines).enumerate() {
-        let y = title_y_start + (i as i32 * line_height);
-        let escaped_line = html_escape::encode_text(line);
-        title_svg.push_str(&format!(
-            r##"<text x="100" y="{}" font-family="Geist" font-size="{}" font-weight="700" fill="url(#textGradient)">{}</text>"##,
-            y, title_font_size, escaped_line
-        ));
+    fn postprocess(&mut self, html: String, _state: &mut RenderState) -> String {
+        sanitize_html(&html, self.0)
     }
-    
-    // Meta info Y position - adjust based on actual lines used
-    let meta_y = title_y_start + (title_line_count as i32 * line_height) + 50;
-    
-    let escaped_date = html_escape::encode_text(date);
-    let escaped_reading = html_escape::encode_text(reading_time);
-    let escaped_tags = html_escape::encode_text(tags);
-    
-    let svg = format!(
-        r##"<svg width="1200" height="630" viewBox="0 0 1200 630" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
-  <defs>
-    <!-- Grid pattern -->
-    <pattern id="grid" width="40" height="40" patternUnits="userSpaceOnUse">
-      <path d="M 40 0 L 0 0 0 40" fill="none" stroke="#1a1a1a" stroke-width="1"/>
-    </pattern>
-    <!-- Text gradient - white to gray like Glimpse -->
-    <linearGradient id="textGradient" x1="0%" y1="0%" x2="100%" y2="100%">
-      <stop offset="0%" style="stop-color:#ffffff"/>
-      <stop offset="50%" style="stop-color:#b0b0b0"/>
-      <stop offset="100%" style="stop-color:#606060"/>
-    </linearGradient>
-  </defs>
-  
-  <!-- Pure black background -->
-  <rect width="1200" height="630" fill="#000000"/>
-  <!-- Grid overlay -->
-  <rect width="1200" height="630" fill="url(#grid)"/>
-  
-  <!-- Title with gradient -->
-  {}
-  
-  <!-- Meta info line - date and reading time only -->
-  <text x="100" y="{}" font-family="Geist" font-size="24" font-weight="500" fill="#777777">{} · {}</text>
-  
-  <!-- Tags -->
-  <text x="100" y="{}" font-family="Geist" font-size="20" font-weight="400" fill="#555555">{}</text>
-  
-  <!-- Author at bottom left -->
-  <text x="100" y="560" font-family="Geist" font-size="22" font-weight="500" fill="#555555">By </text>
-  <text x="135" y="560" font-family="Geist" font-size="22" font-weight="600" fill="#c0c0c0">Aryan S Rao</text>
-  
-  <!-- Logo at bottom right - equal distance from bottom and right (50px) -->
-  <image x="1070" y="500" width="80" height="80" xlink:href="data:image/webp;base64,{}"/>
-</svg>"##,
-        title_svg,
-        meta_y,
-        escaped_date,
-        escaped_reading,
-        meta_y + 40,
-        escaped_tags,
-        logo_base64,
-    );
-    
-    svg_to_png(&svg)
 }
 
-fn generate_og_image_home(site_config: &SiteConfig) -> Result<Vec<u8>, String> {
-    // Embed logo as base64
-    static LOGO_WEBP: &[u8] = include_bytes!("../logo-small.webp");
-    let logo_base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LOGO_WEBP);
-    
-    let escaped_title = html_escape::encode_text(&site_config.title);
-    let escaped_tagline = html_escape::encode_text(&site_config.tagline);
-    let escaped_author = html_escape::encode_text(&site_config.author_full_name);
-    
-    let svg = format!(
-        r##"<svg width="1200" height="630" viewBox="0 0 1200 630" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
-  <defs>
-    <!-- Background gradient - pure black to dark gray -->
-    <linearGradient id="bgGradient" x1="0%" y1="0%" x2="100%" y2="100%">
-      <stop offset="0%" style="stop-color:#000000"/>
-      <stop offset="50%" style="stop-color:#080808"/>
-      <stop offset="100%" style="stop-color:#0f0f0f"/>
-    </linearGradient>
-    <!-- Text gradient - white to gray like Glimpse -->
-    <linearGradient id="titleGradient" x1="0%" y1="0%" x2="100%" y2="100%">
-      <stop offset="0%" style="stop-color:#ffffff"/>
-      <stop offset="40%" style="stop-color:#c0c0c0"/>
-      <stop offset="100%" style="stop-color:#707070"/>
-    </linearGradient>
-  </defs>
-  
-  <!-- Pure black background with gradient -->
-  <rect width="1200" height="630" fill="url(#bgGradient)"/>
-  
-  <!-- Logo - top left -->
-  <image x="60" y="50" width="100" height="100" xlink:href="data:image/webp;base64,{}"/>
-  
-  <!-- Main title - centered, HUGE with gradient -->
-  <text x="600" y="320" font-family="Geist" font-size="110" font-weight="700" fill="url(#titleGradient)" text-anchor="middle">{}</text>
-  
-  <!-- Tagline - centered -->
-  <text x="600" y="400" font-family="Geist" font-size="28" font-weight="500" fill="#777777" text-anchor="middle">{}</text>
-  
-  <!-- Tech stack -->
-  <text x="600" y="455" font-family="Geist" font-size="20" font-weight="400" fill="#555555" text-anchor="middle">Rust · Web Development · Open Source</text>
-  
-  <!-- Author at bottom - centered -->
-  <text x="600" y="560" font-family="Geist" font-size="22" font-weight="500" fill="#666666" text-anchor="middle">{}</text>
-</svg>"##,
-        logo_base64,
-        escaped_title,
-        escaped_tagline,
-        escaped_author,
-    );
-    
-    svg_to_png(&svg)
+// Feed profile only: URLs made absolute against the post's page and interactive markup removed
+struct FeedStage;
+
+impl RenderStage for FeedStage {
+    fn name(&self) -> &'static str {
+        "feed"
+    }
+
+    fn finalize(&mut self, html: String, state: &mut RenderState) -> String {
+        match reqwest::Url::parse(&format!("{}/blog/{}", state.ctx.site_url, state.ctx.slug)) {
+            Ok(base) => feed_safe_html(&html, &base),
+            Err(_) => html,
+        }
+    }
 }
 
-fn estimate_text_width_px(text: &str, font_size_px: i32) -> f32 {
-    let mut units = 0.0f32;
-    for ch in text.chars() {
-        units += match ch {
-            ' ' => 0.28,
-            '-' | '–' | '—' | '·' | '|' => 0.35,
-            '.' | ',' | ':' | ';' | '!' | '?' => 0.28,
-            'A'..='Z' => 0.62,
-            'a'..='z' => 0.52,
-            '0'..='9' => 0.55,
-            _ => 0.60,
-        };
-    }
-    units * (font_size_px as f32)
-}
-
-fn hard_wrap_word_to_width(word: &str, max_width_px: f32, font_size_px: i32) -> Vec<String> {
-    // Worst-case fallback for a single unbreakable word that's wider than the allowed width.
-    let mut parts: Vec<String> = Vec::new();
-    let mut current = String::new();
+// Endnotes section: one <li> per referenced footnote in reference order, each with ↩ links back
+fn render_footnotes(
+    numbers: &HashMap<String, usize>,
+    ref_counts: &[usize],
+    defs: &mut HashMap<String, String>,
+) -> String {
+    let mut ordered: Vec<(&String, &usize)> = numbers.iter().collect();
+    ordered.sort_by_key(|(_, n)| **n);
+
+    let mut html = String::from(
+        r#"<section class="footnotes" role="doc-endnotes" aria-labelledby="footnotes-label"><h2 id="footnotes-label" class="sr-only">Footnotes</h2><ol>"#,
+    );
+    for (name, &number) in ordered {
+        let backrefs = (1..=ref_counts[number - 1])
+            .map(|n| {
+                let (ref_id, label) = if n == 1 {
+                    (format!("fnref-{}", number), String::new())
+                } else {
+                    (format!("fnref-{}-{}", number, n), format!("<sup>{}</sup>", n))
+                };
+                format!(
+                    r##"<a href="#{}" class="footnote-backref" role="doc-backlink" aria-label="Back to reference {}">↩{}</a>"##,
+                    ref_id, number, label
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
 
-    for ch in word.chars() {
-        let mut next = current.clone();
-        next.push(ch);
-        if !current.is_empty() && estimate_text_width_px(&next, font_size_px) > max_width_px {
-            parts.push(current);
-            current = ch.to_string();
+        let mut content = defs.remove(name.as_str()).unwrap_or_default();
+        // Keep the back-links inside the definition's last paragraph when there is one
+        if content.trim_end().ends_with("</p>") {
+            let at = content.rfind("</p>").unwrap_or(content.len());
+            content.insert_str(at, &format!(" {}", backrefs));
         } else {
-            current = next;
+            content.push_str(&backrefs);
         }
+        html.push_str(&format!(r#"<li id="fn-{}">{}</li>"#, number, content));
     }
+    html.push_str("</ol></section>");
+    html
+}
 
-    if !current.is_empty() {
-        parts.push(current);
-    }
+// ============================================================================
+// Shortcodes - {{< name args >}} embeds inside Markdown
+// ============================================================================
 
-    parts
+// Generated HTML (shortcodes, highlighted code, diagrams) is held back as a fragment and
+// represented by a placeholder until sanitization is done, so only author HTML gets filtered
+fn fragment_placeholder(index: usize) -> String {
+    format!("\u{E000}fragment-{}\u{E001}", index)
 }
 
-fn wrap_text_to_width(text: &str, max_width_px: f32, font_size_px: i32) -> Vec<String> {
-    let words: Vec<&str> = text.split_whitespace().collect();
-    let mut lines: Vec<String> = Vec::new();
-    let mut current = String::new();
-
-    for word in words {
-        // Keep a standalone dash attached to the previous word if possible.
-        if word == "-" && !current.is_empty() {
-            let candidate = format!("{} -", current);
-            if estimate_text_width_px(&candidate, font_size_px) <= max_width_px {
-                current = candidate;
-                continue;
+// Replace every shortcode outside code with a placeholder; returns the rewritten Markdown, with the
+// rendered HTML for each placeholder held as a fragment and any problems recorded as warnings
+fn expand_shortcodes(markdown: &str, state: &mut RenderState) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut fence: Option<String> = None;
+    let mut line_start = 0;
+
+    for line in markdown.split_inclusive('\n') {
+        let offset = line_start;
+        line_start += line.len();
+        let trimmed = line.trim_start();
+
+        // Fenced code blocks pass through untouched
+        if let Some(marker) = &fence {
+            if trimmed.starts_with(marker.as_str()) {
+                fence = None;
             }
+            output.push_str(line);
+            continue;
         }
-
-        let candidate = if current.is_empty() {
-            word.to_string()
-        } else {
-            format!("{} {}", current, word)
-        };
-
-        if estimate_text_width_px(&candidate, font_size_px) <= max_width_px {
-            current = candidate;
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            let ch = trimmed.chars().next().unwrap_or('`');
+            let len = trimmed.chars().take_while(|c| *c == ch).count();
+            fence = Some(ch.to_string().repeat(len));
+            output.push_str(line);
             continue;
         }
 
-        if current.is_empty() {
-            // A single word is too wide; hard-wrap it.
-            let wrapped = hard_wrap_word_to_width(word, max_width_px, font_size_px);
-            lines.extend(wrapped);
-        } else {
-            lines.push(current);
-            current = String::new();
+        if !line.contains("{{<") {
+            output.push_str(line);
+            continue;
+        }
 
-            // Re-try placing the word on a new line (or hard-wrap it).
-            if estimate_text_width_px(word, font_size_px) <= max_width_px {
-                current = word.to_string();
-            } else {
-                let wrapped = hard_wrap_word_to_width(word, max_width_px, font_size_px);
-                lines.extend(wrapped);
+        // A shortcode alone on its line becomes its own block; inline ones stay in the paragraph
+        let standalone = trimmed.starts_with("{{<") && trimmed.trim_end().ends_with(">}}")
+            && trimmed.matches("{{<").count() == 1;
+
+        let mut rest = line;
+        let mut code_ticks = 0usize; // Length of the backtick run that opened the current code span
+        while !rest.is_empty() {
+            if rest.starts_with('`') {
+                let run = rest.chars().take_while(|c| *c == '`').count();
+                if code_ticks == 0 {
+                    code_ticks = run;
+                } else if code_ticks == run {
+                    code_ticks = 0;
+                }
+                output.push_str(&rest[..run]);
+                rest = &rest[run..];
+                continue;
+            }
+            if code_ticks == 0 && rest.starts_with("{{<") {
+                if let Some(end) = rest.find(">}}") {
+                    let html = render_shortcode(rest[3..end].trim(), state.ctx).unwrap_or_else(|message| {
+                        state.warn(markdown, offset + line.len() - rest.len(), message);
+                        String::new()
+                    });
+                    let placeholder = fragment_placeholder(state.fragments.len());
+                    state.fragments.push(html);
+                    if standalone {
+                        output.push('\n');
+                        output.push_str(&placeholder);
+                        output.push('\n');
+                    } else {
+                        output.push_str(&placeholder);
+                    }
+                    rest = &rest[end + 3..];
+                    continue;
+                }
             }
+            let ch = rest.chars().next().unwrap_or(' ');
+            output.push(ch);
+            rest = &rest[ch.len_utf8()..];
         }
     }
 
-    if !current.is_empty() {
-        lines.push(current);
-    }
-
-    lines
+    output
 }
 
-fn ellipsize_to_width(text: &str, max_width_px: f32, font_size_px: i32) -> String {
-    if estimate_text_width_px(text, font_size_px) <= max_width_px {
-        return text.to_string();
+// Put each fragment back in place of its placeholder, unwrapping block-level ones from <p>
+fn splice_fragments(html: &str, fragments: &[String]) -> String {
+    let mut html = html.to_string();
+    for (i, rendered) in fragments.iter().enumerate() {
+        let placeholder = fragment_placeholder(i);
+        html = html
+            .replace(&format!("<p>{}</p>", placeholder), rendered)
+            .replace(&placeholder, rendered);
     }
+    html
+}
 
-    let ellipsis = "…";
-    let mut out = String::new();
-    for ch in text.chars() {
-        let mut candidate = out.clone();
-        candidate.push(ch);
-        let with_ellipsis = format!("{}{}", candidate, ellipsis);
-        if estimate_text_width_px(&with_ellipsis, font_size_px) > max_width_px {
+// Split shortcode arguments into positional values and key="value" pairs
+fn parse_shortcode_args(input: &str) -> (Vec<String>, HashMap<String, String>) {
+    let mut positional = Vec::new();
+    let mut named = HashMap::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
             break;
         }
-        out = candidate;
-    }
 
-    if out.is_empty() {
-        ellipsis.to_string()
-    } else {
-        format!("{}{}", out, ellipsis)
-    }
-}
+        let mut key = String::new();
+        let mut value = String::new();
+        let mut target_is_value = false;
+        let mut quote: Option<char> = None;
+        while let Some(&c) = chars.peek() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), c) => if target_is_value { value.push(c) } else { key.push(c) },
+                (None, '"') | (None, '\'') => quote = Some(c),
+                (None, c) if c.is_whitespace() => break,
+                (None, '=') if !target_is_value => target_is_value = true,
+                (None, c) => if target_is_value { value.push(c) } else { key.push(c) },
+            }
+            chars.next();
+        }
 
-fn generate_og_image_recents(posts: &[Post], _site_config: &SiteConfig) -> Result<Vec<u8>, String> {
-    // Embed logo as base64
-    static LOGO_WEBP: &[u8] = include_bytes!("../logo-small.webp");
-    let logo_base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LOGO_WEBP);
-    
-    // Take the latest post
-    let post = posts
-        .first()
-        .ok_or_else(|| "No posts available".to_string())?;
-    
-    // Wrap latest post title to fit in one line, truncate if needed
-    let title_font_size = 42;
-    let title_max_width_px = 880.0;
-    let title_lines = wrap_text_to_width(&post.title, title_max_width_px, title_font_size);
-    let mut title = title_lines.get(0).cloned().unwrap_or_default();
-    if title_lines.len() > 1 {
-        title = ellipsize_to_width(&title, title_max_width_px, title_font_size);
+        if target_is_value {
+            named.insert(key, value);
+        } else {
+            positional.push(key);
+        }
     }
-    let title = html_escape::encode_text(&title).to_string();
 
-    let date_str = &post.date;
-    
-    let svg = format!(
-        r##"<svg width="1200" height="630" viewBox="0 0 1200 630" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
-  <defs>
-    <!-- Text gradient -->
-    <linearGradient id="textGradient" x1="0%" y1="0%" x2="100%" y2="100%">
-      <stop offset="0%" style="stop-color:#ffffff"/>
-      <stop offset="50%" style="stop-color:#b0b0b0"/>
-      <stop offset="100%" style="stop-color:#606060"/>
-    </linearGradient>
-  </defs>
-  
-  <!-- Background -->
-  <rect width="1200" height="630" fill="#0a0a0a"/>
-  
-  <!-- Logo and BLOGS text centered together -->
-  <image x="220" y="120" width="100" height="100" xlink:href="data:image/webp;base64,{}"/>
-  <text x="350" y="230" font-family="Geist" font-size="160" font-weight="700" fill="url(#textGradient)">BLOGS</text>
-  
-  <!-- Divider line -->
-  <line x1="200" y1="315" x2="1000" y2="315" stroke="#1a1a1a" stroke-width="2"/>
-  
-  <!-- Latest Post section -->
-  <text x="160" y="385" font-family="Geist" font-size="20" font-weight="600" fill="#888888">LATEST POST</text>
-  
-  <!-- Post title (single line, centered) -->
-  <text x="600" y="465" font-family="Geist" font-size="42" font-weight="700" fill="#ffffff" text-anchor="middle">{}</text>
-  
-  <!-- Post date -->
-  <text x="600" y="515" font-family="Geist" font-size="20" font-weight="500" fill="#888888" text-anchor="middle">{}</text>
-</svg>"##,
-        logo_base64,
-        title,
-        date_str,
-    );
-    
-    svg_to_png(&svg)
+    (positional, named)
 }
 
-fn svg_to_png(svg_str: &str) -> Result<Vec<u8>, String> {
-    use resvg::tiny_skia::Pixmap;
-    use resvg::usvg::{Options, Tree, fontdb};
-    
-    // Embed Geist fonts directly for deployment compatibility
-    static GEIST_MEDIUM: &[u8] = include_bytes!("../fonts/Geist-Medium.ttf");
-    static GEIST_SEMIBOLD: &[u8] = include_bytes!("../fonts/Geist-SemiBold.ttf");
-    static GEIST_BOLD: &[u8] = include_bytes!("../fonts/Geist-Bold.ttf");
-    
-    let mut fontdb = fontdb::Database::new();
-    fontdb.load_font_data(GEIST_MEDIUM.to_vec());
-    fontdb.load_font_data(GEIST_SEMIBOLD.to_vec());
-    fontdb.load_font_data(GEIST_BOLD.to_vec());
-    
-    let opt = Options {
-        fontdb: std::sync::Arc::new(fontdb),
-        ..Options::default()
-    };
-    
-    let tree = Tree::from_str(svg_str, &opt)
-        .map_err(|e| format!("Failed to parse SVG: {}", e))?;
-    
-    let size = tree.size();
-    let mut pixmap = Pixmap::new(size.width() as u32, size.height() as u32)
-        .ok_or("Failed to create pixmap")?;
-    
-    resvg::render(&tree, resvg::tiny_skia::Transform::default(), &mut pixmap.as_mut());
-    
-    pixmap.encode_png()
-        .map_err(|e| format!("Failed to encode PNG: {}", e))
+// Gist references: `user/id` or a bare id, where the id is hex
+fn is_gist_id(gist: &str) -> bool {
+    let (user, id) = gist.split_once('/').unwrap_or(("", gist));
+    user.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !id.is_empty()
+        && id.chars().all(|c| c.is_ascii_hexdigit())
 }
 
-// Generate XML Sitemap for SEO - Enhanced with comprehensive metadata
-async fn sitemap() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    let today = Local::now().format("%Y-%m-%d").to_string();
-    
-    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
-<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
-        xmlns:news="http://www.google.com/schemas/sitemap-news/0.9"
-        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1"
-        xmlns:xhtml="http://www.w3.org/1999/xhtml"
-        xmlns:mobile="http://www.google.com/schemas/sitemap-mobile/1.0">
-"#);
-
-    // Homepage - highest priority with OG image
-    xml.push_str(&format!(
-        r#"  <url>
-    <loc>{}</loc>
-    <lastmod>{}</lastmod>
-    <changefreq>daily</changefreq>
-    <priority>1.0</priority>
-    <image:image>
-      <image:loc>{}/og.png</image:loc>
-      <image:title>{}</image:title>
-    </image:image>
-    <mobile:mobile/>
-  </url>
-"#,
-        site_config.url,
-        today,
-        site_config.url,
-        html_escape::encode_text(&site_config.title)
-    ));
+// HTML for one shortcode, or the warning explaining why it renders as nothing
+fn render_shortcode(inner: &str, ctx: &RenderContext) -> Result<String, String> {
+    let (name, args) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+    let (positional, named) = parse_shortcode_args(args);
+    // Named arguments win; otherwise fall back to the nth positional one
+    let arg = |key: &str, index: usize| -> String {
+        named.get(key).cloned().or_else(|| positional.get(index).cloned()).unwrap_or_default()
+    };
 
-    // Collect all unique tags first (before consuming posts)
-    let mut all_tags: Vec<String> = posts.iter()
-        .flat_map(|p| p.tags.iter().cloned())
-        .collect();
-    all_tags.sort();
-    all_tags.dedup();
-
-    // Blog posts with dynamic OG images
-    for post in &posts {
-        xml.push_str(&format!(
-            r#"  <url>
-    <loc>{}/blog/{}</loc>
-    <lastmod>{}</lastmod>
-    <changefreq>weekly</changefreq>
-    <priority>0.8</priority>
-    <image:image>
-      <image:loc>{}/blog/{}/og.png</image:loc>
-      <image:title>{}</image:title>
-      <image:caption>{}</image:caption>
-    </image:image>
-    <mobile:mobile/>
-  </url>
-"#,
-            site_config.url,
-            post.slug,
-            post.date_iso.split('T').next().unwrap_or(&post.date_iso),
-            site_config.url,
-            post.slug,
-            html_escape::encode_text(&post.title),
-            html_escape::encode_text(&post.summary)
-        ));
+    // Templates in templates/shortcodes/ take precedence so built-ins can be overridden
+    if SHORTCODE_TEMPLATES.has_template(name) {
+        let mut data = serde_json::Map::new();
+        for (key, value) in &named {
+            data.insert(key.clone(), serde_json::Value::String(value.clone()));
+        }
+        data.insert("args".to_string(), serde_json::to_value(&positional).unwrap());
+        return SHORTCODE_TEMPLATES
+            .render(name, &data)
+            .map_err(|e| format!("Shortcode `{}` failed to render: {}", name, e));
     }
 
-    // Add tag pages to sitemap
-    for tag in all_tags {
-        xml.push_str(&format!(
-            r#"  <url>
-    <loc>{}/tags/{}</loc>
-    <lastmod>{}</lastmod>
-    <changefreq>weekly</changefreq>
-    <priority>0.6</priority>
-    <mobile:mobile/>
-  </url>
-"#,
-            site_config.url,
-            tag,
-            today
-        ));
-    }
+    let html = match name {
+        "youtube" => {
+            let id = arg("id", 0);
+            format!(
+                r#"<div class="embed embed-youtube"><iframe src="https://www.youtube-nocookie.com/embed/{}" title="YouTube video" loading="lazy" allow="accelerometer; encrypted-media; gyroscope; picture-in-picture" allowfullscreen></iframe></div>"#,
+                html_escape::encode_double_quoted_attribute(&id)
+            )
+        }
+        "gist" => {
+            // The embed is a <script> the sanitizer never sees, so only well-formed ids get one
+            let gist = arg("id", 0).trim_matches('/').to_string();
+            if !is_gist_id(&gist) {
+                return Err(format!("Shortcode `gist` needs `user/id` with a hex id, got `{}`", gist));
+            }
+            let url = format!("https://gist.github.com/{}", gist);
+            format!(
+                r#"<div class="embed embed-gist"><script src="{url}.js"></script><noscript><a href="{url}">View gist {label}</a></noscript></div>"#,
+                url = html_escape::encode_double_quoted_attribute(&url),
+                label = html_escape::encode_text(&gist)
+            )
+        }
+        "github" => {
+            let repo = arg("repo", 0);
+            format!(
+                r#"<a class="embed-card embed-github" href="https://github.com/{}"><span class="embed-card-label">GitHub</span><span class="embed-card-title">{}</span></a>"#,
+                html_escape::encode_double_quoted_attribute(&repo),
+                html_escape::encode_text(&repo)
+            )
+        }
+        "figure" => {
+            let src = arg("src", 0);
+            if src.is_empty() || is_unsafe_url(&src) {
+                return Err(format!("Shortcode `figure` has a missing or unsafe src `{}`", src));
+            }
+            let caption = arg("caption", 1);
+            let alt = named.get("alt").cloned().unwrap_or_else(|| caption.clone());
+            let figcaption = if caption.is_empty() {
+                String::new()
+            } else {
+                format!("<figcaption>{}</figcaption>", html_escape::encode_text(&caption))
+            };
+            format!(
+                r#"<figure class="figure"><img src="{}" alt="{}" loading="lazy">{}</figure>"#,
+                html_escape::encode_double_quoted_attribute(&src),
+                html_escape::encode_double_quoted_attribute(&alt),
+                figcaption
+            )
+        }
+        "post" => {
+            let slug = arg("slug", 0);
+            match ctx.posts.iter().find(|p| p.slug == slug) {
+                Some(post) => format!(
+                    r#"<a class="embed-card embed-post" href="/blog/{}"><span class="embed-card-label">{}</span><span class="embed-card-title">{}</span><span class="embed-card-summary">{}</span></a>"#,
+                    html_escape::encode_double_quoted_attribute(&post.slug),
+                    html_escape::encode_text(&post.date),
+                    html_escape::encode_text(&post.title),
+                    html_escape::encode_text(&post.summary)
+                ),
+                None => return Err(format!("Shortcode `post` references unknown post `{}`", slug)),
+            }
+        }
+        _ => return Err(format!("Unknown shortcode `{}`", name)),
+    };
+    Ok(html)
+}
 
-    xml.push_str("</urlset>");
+// ============================================================================
+// Link Previews - cards for paragraphs that are just a URL
+// ============================================================================
 
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=3600")
-        .header("X-Robots-Tag", "noindex")
-        .body(xml)
-        .unwrap()
+// What a card shows, from the target's OpenGraph tags or, for GitHub repos, the GitHub API
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LinkPreview {
+    url: String,
+    title: String,
+    description: Option<String>,
+    image: Option<String>, // Local copy of the og:image, under /images/previews/
+    site_name: Option<String>,
+    stars: Option<u32>,
+    language: Option<String>,
 }
 
-// Generate robots.txt - Enhanced with comprehensive directives
-async fn robots_txt() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let content = format!(
-        r#"# Robots.txt for {}
-# Generated automatically - Optimized for SEO
+// The URL a paragraph consists of (plain text or an <autolink>), and how many events it spans
+fn bare_url_paragraph(events: &[Event]) -> Option<(String, usize)> {
+    let mut text = String::new();
+    for (n, event) in events.iter().enumerate() {
+        match event {
+            Event::Text(t) => text.push_str(t),
+            Event::Start(Tag::Link { link_type: pulldown_cmark::LinkType::Autolink, .. }) | Event::End(TagEnd::Link) => {}
+            Event::End(TagEnd::Paragraph) => {
+                let url = text.trim();
+                let is_url = (url.starts_with("https://") || url.starts_with("http://"))
+                    && !url.contains(char::is_whitespace);
+                return is_url.then(|| (url.to_string(), n));
+            }
+            _ => return None,
+        }
+    }
+    None
+}
 
-# Allow all search engines
-User-agent: *
-Allow: /
-Allow: /blog/
-Allow: /rss.xml
-Allow: /atom.xml
-Allow: /sitemap.xml
-
-# Optimize crawl budget
-Disallow: /api/
-Disallow: /admin/
-Disallow: /*.json$
-Disallow: /*?*
-
-# Crawl-delay for politeness (1 second between requests)
-Crawl-delay: 1
-
-# Google-specific directives
-User-agent: Googlebot
-Allow: /
-Crawl-delay: 0
-
-# Google Image Bot
-User-agent: Googlebot-Image
-Allow: /
-Allow: /*.png$
-Allow: /*.jpg$
-Allow: /*.jpeg$
-Allow: /*.webp$
-Allow: /*.gif$
-
-# Bing
-User-agent: Bingbot
-Allow: /
-Crawl-delay: 1
-
-# DuckDuckGo
-User-agent: DuckDuckBot
-Allow: /
-
-# Yandex
-User-agent: Yandex
-Allow: /
-Crawl-delay: 2
+fn link_preview_path(url: &str) -> PathBuf {
+    PathBuf::from(LINK_PREVIEW_CACHE_DIR).join(format!("{}.json", &content_hash(url)[..16]))
+}
 
-# Baidu
-User-agent: Baiduspider
-Allow: /
-Crawl-delay: 2
+// Cached preview for a URL; unknown URLs are queued for the next refresh and render as plain links
+fn link_preview(url: &str) -> Option<LinkPreview> {
+    if let Some(preview) = LINK_PREVIEWS.lock().unwrap().get(url) {
+        return Some(preview.clone());
+    }
+    let preview: Option<LinkPreview> = fs::read_to_string(link_preview_path(url))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok());
+    match &preview {
+        Some(p) => {
+            LINK_PREVIEWS.lock().unwrap().insert(url.to_string(), p.clone());
+        }
+        None => {
+            PENDING_LINK_PREVIEWS.lock().unwrap().insert(url.to_string());
+        }
+    }
+    preview
+}
 
-# AI and Specialized Bots
-User-agent: GPTBot
-Allow: /
+fn render_link_preview(url: &str) -> String {
+    let href = html_escape::encode_double_quoted_attribute(url);
+    let preview = match link_preview(url) {
+        Some(p) => p,
+        None => return format!(r#"<p><a href="{}">{}</a></p>"#, href, html_escape::encode_text(url)),
+    };
 
-User-agent: ChatGPT-User
-Allow: /
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_string()))
+        .unwrap_or_default();
+    let label = preview.site_name.clone().unwrap_or(host);
+    // Only images downloaded by refresh_link_previews; third-party URLs would be hotlinked
+    let image = preview
+        .image
+        .as_deref()
+        .filter(|src| src.starts_with("/images/previews/") && resolve_local_image(src).is_some())
+        .map(|src| {
+            format!(
+                r#"<img class="embed-card-image" src="{}" alt="" loading="lazy" decoding="async">"#,
+                html_escape::encode_double_quoted_attribute(src)
+            )
+        })
+        .unwrap_or_default();
+    let description = preview
+        .description
+        .as_deref()
+        .map(|d| format!(r#"<span class="embed-card-summary">{}</span>"#, html_escape::encode_text(&truncate_at_word(d, 200))))
+        .unwrap_or_default();
+    let meta = [
+        preview.stars.map(|s| format!("★ {}", s)),
+        preview.language.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    let meta = if meta.is_empty() {
+        String::new()
+    } else {
+        format!(r#"<span class="embed-card-meta">{}</span>"#, html_escape::encode_text(&meta.join(" · ")))
+    };
+    let class = if preview.stars.is_some() { "embed-card embed-unfurl embed-github" } else { "embed-card embed-unfurl" };
 
-User-agent: CCBot
-Allow: /
+    format!(
+        r#"<a class="{}" href="{}">{}<span class="embed-card-label">{}</span><span class="embed-card-title">{}</span>{}{}</a>"#,
+        class,
+        href,
+        image,
+        html_escape::encode_text(&label),
+        html_escape::encode_text(&preview.title),
+        description,
+        meta
+    )
+}
 
-User-agent: anthropic-ai
-Allow: /
+// owner/repo for a github.com repository URL
+fn github_repo_path(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    if parsed.host_str()?.trim_start_matches("www.") != "github.com" {
+        return None;
+    }
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        [owner, repo] => Some(format!("{}/{}", owner, repo.trim_end_matches(".git"))),
+        _ => None,
+    }
+}
 
-User-agent: Claude-Web
-Allow: /
+// Fetch card data: the GitHub API for repos, OpenGraph/meta tags for everything else
+async fn fetch_link_preview(url: &str) -> Result<LinkPreview, String> {
+    if let Some(full_name) = github_repo_path(url) {
+        if let Ok(repo) = fetch_github_repo(&full_name).await {
+            return Ok(LinkPreview {
+                url: url.to_string(),
+                title: repo.full_name,
+                description: repo.description,
+                image: None,
+                site_name: Some("GitHub".to_string()),
+                stars: Some(repo.stargazers_count),
+                language: repo.language,
+            });
+        }
+    }
 
-# Sitemaps
-Sitemap: {}/sitemap.xml
-Sitemap: {}/sitemap-posts.xml
+    let client = reqwest::Client::builder()
+        .user_agent("axum-blog")
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client.get(url).send().await.map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch {}: {}", url, response.status()));
+    }
+    let final_url = response.url().clone();
 
-# Host directive
-Host: {}
-"#,
-        site_config.title,
-        site_config.url,
-        site_config.url,
-        site_config.url.replace("https://", "").replace("http://", "")
-    );
+    // Only the head matters: read until `</head>`, and never more than MAX_PREVIEW_PAGE
+    let mut response = response;
+    let mut data = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", url, e))?
+    {
+        // The closing tag may straddle chunks, so search from just before the new one
+        let from = data.len().saturating_sub("</head>".len());
+        data.extend_from_slice(&chunk);
+        if let Some(end) = data[from..].windows(7).position(|w| w.eq_ignore_ascii_case(b"</head>")) {
+            data.truncate(from + end);
+            break;
+        }
+        if data.len() >= MAX_PREVIEW_PAGE {
+            data.truncate(MAX_PREVIEW_PAGE);
+            break;
+        }
+    }
+    let head = String::from_utf8_lossy(&data);
+    let head = head.as_ref();
+
+    let mut meta: HashMap<String, String> = HashMap::new();
+    let mut title_tag = None;
+    let mut rest = head;
+    while let Some(lt) = rest.find('<') {
+        rest = &rest[lt..];
+        let end = match find_tag_end(rest) {
+            Some(e) => e + 1,
+            None => break,
+        };
+        let tag = parse_tag(&rest[..end]);
+        rest = &rest[end..];
+        match tag.name.to_lowercase().as_str() {
+            "meta" if !tag.closing => {
+                let attr = |name: &str| {
+                    tag.attrs
+                        .iter()
+                        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                        .and_then(|(_, v)| v.as_deref())
+                        .map(|v| html_escape::decode_html_entities(v).trim().to_string())
+                };
+                if let (Some(key), Some(content)) = (attr("property").or_else(|| attr("name")), attr("content")) {
+                    if !content.is_empty() {
+                        meta.entry(key.to_lowercase()).or_insert(content);
+                    }
+                }
+            }
+            "title" if !tag.closing && title_tag.is_none() => {
+                let text = &rest[..rest.find('<').unwrap_or(rest.len())];
+                title_tag = Some(html_escape::decode_html_entities(text).trim().to_string());
+            }
+            _ => {}
+        }
+    }
 
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=86400")
-        .body(content)
-        .unwrap()
+    let pick = |keys: &[&str]| keys.iter().find_map(|k| meta.get(*k).cloned());
+    let title = pick(&["og:title", "twitter:title"])
+        .or(title_tag)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| format!("No title found for {}", url))?;
+    // Only https images are downloaded; a failed download leaves the card without one
+    let image_url = pick(&["og:image", "og:image:url", "twitter:image"])
+        .and_then(|src| final_url.join(&src).ok())
+        .filter(|u| u.scheme() == "https");
+    let image = match image_url {
+        Some(src) => match download_preview_image(src.as_str()).await {
+            Ok(local) => Some(local),
+            Err(e) => {
+                eprintln!("Link preview: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    Ok(LinkPreview {
+        url: url.to_string(),
+        title,
+        description: pick(&["og:description", "twitter:description", "description"]),
+        image,
+        site_name: pick(&["og:site_name"]),
+        stars: None,
+        language: None,
+    })
 }
 
-// Generate RSS Feed
-async fn rss_feed() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    
-    let mut rss = format!(
-        r#"<?xml version="1.0" encoding="UTF-8"?>
-<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:content="http://purl.org/rss/1.0/modules/content/">
-  <channel>
-    <title>{}</title>
-    <link>{}</link>
-    <description>{}</description>
-    <language>{}</language>
-    <lastBuildDate>{}</lastBuildDate>
-    <atom:link href="{}/rss.xml" rel="self" type="application/rss+xml"/>
-    <generator>Axum Blog Engine</generator>
-"#,
-        html_escape::encode_text(&site_config.title),
-        site_config.url,
-        html_escape::encode_text(&site_config.description),
-        site_config.language,
-        Local::now().format("%a, %d %b %Y %H:%M:%S %z"),
-        site_config.url
-    );
+// Download a card image into LINK_PREVIEW_IMAGE_DIR (once per distinct content) and return its local URL
+async fn download_preview_image(url: &str) -> Result<String, String> {
+    let data = download_bytes(url).await?;
+    let ext = image_extension(&data, url).ok_or_else(|| format!("{} is not an image", url))?;
+    let file = format!("{}.{}", &format!("{:x}", Sha256::digest(&data))[..16], ext);
+
+    let path = PathBuf::from(LINK_PREVIEW_IMAGE_DIR).join(&file);
+    if !path.is_file() {
+        fs::create_dir_all(LINK_PREVIEW_IMAGE_DIR)
+            .map_err(|e| format!("Failed to create {}: {}", LINK_PREVIEW_IMAGE_DIR, e))?;
+        fs::write(&path, &data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(format!("/images/previews/{}", file))
+}
 
-    for post in posts.iter().take(20) {
-        rss.push_str(&format!(
-            r#"    <item>
-      <title>{}</title>
-      <link>{}/blog/{}</link>
-      <guid isPermaLink="true">{}/blog/{}</guid>
-      <pubDate>{}</pubDate>
-      <description><![CDATA[{}]]></description>
-      <author>{}</author>
-      <enclosure url="{}/blog/{}/og.png" type="image/png" length="0"/>
-      {}
-    </item>
-"#,
-            html_escape::encode_text(&post.title),
-            site_config.url,
-            post.slug,
-            site_config.url,
-            post.slug,
-            post.date_iso,
-            post.summary,
-            site_config.author,
-            site_config.url,
-            post.slug,
-            post.tags.iter().map(|t| format!("<category>{}</category>", html_escape::encode_text(t))).collect::<Vec<_>>().join("\n      ")
-        ));
+// Fetch previews for every URL queued since the last refresh, caching successes on disk.
+// Failures (e.g. offline) stay plain links and are retried on the next refresh
+async fn refresh_link_previews() {
+    let pending: Vec<String> = PENDING_LINK_PREVIEWS.lock().unwrap().drain().collect();
+    for url in pending {
+        match fetch_link_preview(&url).await {
+            Ok(preview) => {
+                let _ = fs::create_dir_all(LINK_PREVIEW_CACHE_DIR);
+                if let Ok(json) = serde_json::to_string_pretty(&preview) {
+                    let _ = fs::write(link_preview_path(&url), json);
+                }
+                LINK_PREVIEWS.lock().unwrap().insert(url, preview);
+            }
+            Err(e) => eprintln!("Link preview: {}", e),
+        }
     }
+}
 
-    rss.push_str("  </channel>\n</rss>");
+// ============================================================================
+// Responsive Images - <picture> markup and on-demand WebP variants
+// ============================================================================
 
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=3600")
-        .body(rss)
-        .unwrap()
+// Map an image URL from a post to a file under content/images, if it refers to one
+fn resolve_local_image(src: &str) -> Option<PathBuf> {
+    let path = src.split(['?', '#']).next().unwrap_or("");
+    let relative = path.strip_prefix("/images/")?;
+    let relative = PathBuf::from(relative);
+    // Only plain path segments - no `..` escaping the images directory
+    if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let full = PathBuf::from(IMAGES_DIR).join(relative);
+    full.is_file().then_some(full)
 }
 
-// Generate Atom Feed (alternative to RSS, preferred by some readers)
-async fn atom_feed() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    let now = Local::now().format("%Y-%m-%dT%H:%M:%S%:z").to_string();
-    
-    let mut atom = format!(
-        r#"<?xml version="1.0" encoding="UTF-8"?>
-<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="{}">
-  <title>{}</title>
-  <subtitle>{}</subtitle>
-  <link href="{}/atom.xml" rel="self" type="application/atom+xml"/>
-  <link href="{}" rel="alternate" type="text/html"/>
-  <id>{}/</id>
-  <updated>{}</updated>
-  <author>
-    <name>{}</name>
-    <uri>{}</uri>
-  </author>
-  <generator uri="https://github.com/aryansrao/aryansrao-blogs">Axum Blog Engine</generator>
-  <icon>{}/logo.webp</icon>
-  <logo>{}/logo.webp</logo>
-  <rights>© {} {}</rights>
-"#,
-        site_config.language,
-        html_escape::encode_text(&site_config.title),
-        html_escape::encode_text(&site_config.description),
-        site_config.url,
-        site_config.url,
-        site_config.url,
-        now,
-        html_escape::encode_text(&site_config.author_full_name),
-        site_config.url,
-        site_config.url,
-        site_config.url,
-        site_config.copyright_year,
-        site_config.author_full_name
-    );
+// Resolve a relative URL in a page bundle to its public URL and the file it names
+fn bundle_asset(url: &str, ctx: &RenderContext) -> Option<(String, PathBuf)> {
+    let dir = ctx.bundle?;
+    if url.is_empty() || url.starts_with(['/', '#', '?']) {
+        return None;
+    }
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    let (path, suffix) = url.split_at(end);
+    // Anything with a scheme (https:, mailto:, ...) is not a bundle asset
+    if path.split('/').next().unwrap_or("").contains(':') {
+        return None;
+    }
+    let relative = PathBuf::from(path.trim_start_matches("./"));
+    if relative.as_os_str().is_empty()
+        || relative.components().any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    Some((
+        format!("/blog/{}/{}", ctx.slug, path.trim_start_matches("./")) + suffix,
+        dir.join(relative),
+    ))
+}
 
-    for post in posts.iter().take(20) {
-        atom.push_str(&format!(
-            r#"  <entry>
-    <title>{}</title>
-    <link href="{}/blog/{}" rel="alternate" type="text/html"/>
-    <link href="{}/blog/{}/og.png" rel="enclosure" type="image/png" title="OG Image"/>
-    <id>{}/blog/{}</id>
-    <published>{}</published>
-    <updated>{}</updated>
-    <author>
-      <name>{}</name>
-    </author>
-    <summary type="html"><![CDATA[{}]]></summary>
-    {}
-  </entry>
-"#,
-            html_escape::encode_text(&post.title),
-            site_config.url,
-            post.slug,
-            site_config.url,
-            post.slug,
-            site_config.url,
-            post.slug,
-            post.date_iso,
-            post.date_iso,
-            html_escape::encode_text(&post.author),
-            post.summary,
-            post.tags.iter().map(|t| format!("<category term=\"{}\"/>", html_escape::encode_text(t))).collect::<Vec<_>>().join("\n    ")
-        ));
+// Hash and dimensions of a local image, cached until the file's size or mtime changes
+fn local_image_info(path: &PathBuf) -> Option<LocalImage> {
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta.modified().ok();
+    if let Some(cached) = LOCAL_IMAGES.lock().unwrap().get(path) {
+        if cached.modified == modified && cached.len == meta.len() {
+            return Some(cached.clone());
+        }
     }
 
-    atom.push_str("</feed>");
+    let bytes = fs::read(path).ok()?;
+    let (width, height) = image::ImageReader::new(std::io::Cursor::new(&bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()?;
+    let info = LocalImage {
+        path: path.clone(),
+        hash: format!("{:x}", Sha256::digest(&bytes)),
+        width,
+        height,
+        modified,
+        len: meta.len(),
+    };
+    LOCAL_IMAGES.lock().unwrap().insert(path.clone(), info.clone());
+
+    let mut sources = IMAGE_SOURCES.lock().unwrap();
+    if sources.get(&info.hash) != Some(path) {
+        sources.insert(info.hash.clone(), path.clone());
+        let _ = fs::create_dir_all(IMAGE_CACHE_DIR);
+        if let Ok(json) = serde_json::to_string_pretty(&*sources) {
+            let _ = fs::write(IMAGE_INDEX, json);
+        }
+    }
+    Some(info)
+}
 
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=3600")
-        .body(atom)
-        .unwrap()
+// Widths generated for an image: each configured width below its own, plus full size
+fn variant_widths(image: &LocalImage) -> Vec<u32> {
+    let mut widths: Vec<u32> = IMAGE_WIDTHS.iter().copied().filter(|w| *w < image.width).collect();
+    widths.push(image.width);
+    widths
 }
 
-// Generate dedicated posts sitemap for large sites
-async fn sitemap_posts() -> impl IntoResponse {
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    
-    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>
-<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
-        xmlns:news="http://www.google.com/schemas/sitemap-news/0.9"
-        xmlns:image="http://www.google.com/schemas/sitemap-image/1.1"
-        xmlns:xhtml="http://www.w3.org/1999/xhtml">
-"#);
+fn render_image(src: &str, title: &str, alt: &str, ctx: &RenderContext) -> String {
+    let src = if is_unsafe_url(src) { "" } else { src };
+    let (src, local) = match bundle_asset(src, ctx) {
+        Some((url, path)) => (url, path.is_file().then_some(path)),
+        None => (src.to_string(), resolve_local_image(src)),
+    };
+    let title_attr = if title.is_empty() {
+        String::new()
+    } else {
+        format!(r#" title="{}""#, html_escape::encode_double_quoted_attribute(title))
+    };
+    let alt = html_escape::encode_double_quoted_attribute(alt);
+
+    match local.and_then(|path| local_image_info(&path)) {
+        Some(image) => {
+            let srcset = variant_widths(&image)
+                .iter()
+                .map(|w| format!("/media/{}-{}.webp {}w", image.hash, w, w))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                r#"<picture><source type="image/webp" srcset="{}" sizes="(max-width: 1200px) 100vw, 1136px"><img src="{}" alt="{}"{} width="{}" height="{}" loading="lazy" decoding="async"></picture>"#,
+                srcset,
+                html_escape::encode_double_quoted_attribute(&src),
+                alt,
+                title_attr,
+                image.width,
+                image.height
+            )
+        }
+        None => format!(
+            r#"<img src="{}" alt="{}"{} loading="lazy" decoding="async">"#,
+            html_escape::encode_double_quoted_attribute(&src),
+            alt,
+            title_attr
+        ),
+    }
+}
 
-    for post in posts {
-        let lastmod = post.date_iso.split('T').next().unwrap_or(&post.date_iso);
-        xml.push_str(&format!(
-            r#"  <url>
-    <loc>{}/blog/{}</loc>
-    <lastmod>{}</lastmod>
-    <changefreq>monthly</changefreq>
-    <priority>0.8</priority>
-    <image:image>
-      <image:loc>{}</image:loc>
-      <image:title>{}</image:title>
-      <image:caption>{}</image:caption>
-    </image:image>
-  </url>
-"#,
-            site_config.url,
-            post.slug,
-            lastmod,
-            post.image,
-            html_escape::encode_text(&post.title),
-            html_escape::encode_text(&post.summary)
-        ));
+// Resize and encode one variant, reusing the on-disk copy when there is one
+fn image_variant(image: &LocalImage, width: u32) -> Result<Vec<u8>, String> {
+    let cache_path = PathBuf::from(IMAGE_CACHE_DIR).join(format!("{}-{}.webp", image.hash, width));
+    if let Ok(data) = fs::read(&cache_path) {
+        return Ok(data);
     }
 
-    xml.push_str("</urlset>");
+    let source = image::open(&image.path).map_err(|e| format!("Failed to decode {}: {}", image.path.display(), e))?;
+    let resized = if width < image.width {
+        source.resize(width, u32::MAX, image::imageops::FilterType::Lanczos3)
+    } else {
+        source
+    };
 
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
-        .header(header::CACHE_CONTROL, "public, max-age=86400")
-        .body(xml)
-        .unwrap()
-}
+    let mut data = Vec::new();
+    resized
+        .to_rgba8()
+        .write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::WebP)
+        .map_err(|e| format!("Failed to encode WebP: {}", e))?;
 
-// ============================================================================
-// GitHub Integration - Fetch READMEs from repositories
-// ============================================================================
+    let _ = fs::create_dir_all(IMAGE_CACHE_DIR);
+    let _ = fs::write(&cache_path, &data);
+    Ok(data)
+}
 
-#[derive(Debug, Deserialize)]
-struct GitHubRepo {
-    name: String,
-    full_name: String,
-    description: Option<String>,
-    html_url: String,
-    pushed_at: Option<String>,
-    updated_at: Option<String>,
-    language: Option<String>,
-    stargazers_count: u32,
-    fork: bool,
-    archived: bool,
-    topics: Option<Vec<String>>,
-    homepage: Option<String>,
+// Opening markup for a GitHub-style alert; the body follows and is closed with </div>
+fn callout_open(kind: BlockQuoteKind) -> String {
+    let (class, title, icon_path) = match kind {
+        BlockQuoteKind::Note => ("note", "Note", "M13 16h-1v-4h-1m1-4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z"),
+        BlockQuoteKind::Tip => ("tip", "Tip", "M9.663 17h4.673M12 3v1m6.364 1.636l-.707.707M21 12h-1M4 12H3m3.343-5.657l-.707-.707m2.828 9.9a5 5 0 117.072 0l-.548.547A3.374 3.374 0 0014 18.469V19a2 2 0 11-4 0v-.531c0-.895-.356-1.754-.988-2.386l-.548-.547z"),
+        BlockQuoteKind::Important => ("important", "Important", "M7 8h10M7 12h4m1 8l-4-4H5a2 2 0 01-2-2V6a2 2 0 012-2h14a2 2 0 012 2v8a2 2 0 01-2 2h-3l-4 4z"),
+        BlockQuoteKind::Warning => ("warning", "Warning", "M12 9v2m0 4h.01m-6.938 4h13.856c1.54 0 2.502-1.667 1.732-3L13.732 4c-.77-1.333-2.694-1.333-3.464 0L3.34 16c-.77 1.333.192 3 1.732 3z"),
+        BlockQuoteKind::Caution => ("caution", "Caution", "M10 14l2-2m0 0l2-2m-2 2l-2-2m2 2l2 2m7-2a9 9 0 11-18 0 9 9 0 0118 0z"),
+    };
+    format!(
+        r#"<div class="callout callout-{class}" role="note"><p class="callout-title"><svg class="callout-icon" fill="none" stroke="currentColor" viewBox="0 0 24 24" aria-hidden="true"><path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="{icon_path}"></path></svg>{title}</p>"#
+    )
 }
 
-#[derive(Debug, Deserialize)]
-struct GitHubReadme {
-    content: Option<String>,
+// Rendered HTML to plain text with entities decoded and whitespace collapsed
+fn html_to_text(html: &str) -> String {
+    let text = strip_html_tags(html);
+    html_escape::decode_html_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
-#[derive(Debug, Serialize)]
-struct SyncResult {
-    success: bool,
-    message: String,
-    repos_synced: Vec<String>,
-    errors: Vec<String>,
+// Text of the first paragraph that has any (READMEs often open with a paragraph of badges)
+fn first_paragraph_text(html: &str) -> String {
+    let mut rest = html;
+    while let Some(start) = rest.find("<p") {
+        let after = &rest[start..];
+        let end = after.find("</p>").unwrap_or(after.len());
+        let text = html_to_text(&after[..end]);
+        if !text.is_empty() {
+            return text;
+        }
+        rest = &after[end..];
+        if rest.is_empty() {
+            break;
+        }
+        rest = &rest[1..];
+    }
+    html_to_text(html)
 }
 
-// Fetch all public repos for the configured GitHub user
-async fn fetch_github_repos() -> Result<Vec<GitHubRepo>, String> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/users/{}/repos?sort=updated&per_page=100", GITHUB_API_BASE, GITHUB_USERNAME.as_str());
-    
-    let mut request = client
-        .get(&url)
-        .header("User-Agent", "axum-blog")
-        .header("Accept", "application/vnd.github.v3+json");
-    
-    if let Some(token) = GITHUB_TOKEN.as_ref() {
-        request = request.header("Authorization", format!("Bearer {}", token));
+// Shorten to at most `max` characters, cutting at a word boundary
+fn truncate_at_word(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
     }
-    
-    let response = request
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch repos: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Err(format!("GitHub API error: {}", response.status()));
-    }
-    
-    let repos: Vec<GitHubRepo> = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse repos: {}", e))?;
-    
-    // Filter out forks and archived repos
-    Ok(repos.into_iter().filter(|r| !r.fork && !r.archived).collect())
+    let cut: String = text.chars().take(max).collect();
+    let cut = match cut.rfind(char::is_whitespace) {
+        Some(i) if i > 0 => &cut[..i],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation()))
 }
 
-// Fetch README content for a specific repo
-async fn fetch_readme(repo_name: &str) -> Result<String, String> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/repos/{}/{}/readme", GITHUB_API_BASE, GITHUB_USERNAME.as_str(), repo_name);
-    
-    let mut request = client
-        .get(&url)
-        .header("User-Agent", "axum-blog")
-        .header("Accept", "application/vnd.github.v3+json");
-    
-    if let Some(token) = GITHUB_TOKEN.as_ref() {
-        request = request.header("Authorization", format!("Bearer {}", token));
-    }
-    
-    let response = request
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch README: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Err(format!("No README found for {}", repo_name));
+// Drop <a> tags but keep their text, so excerpt HTML can sit inside a linked card
+fn unwrap_links(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(lt) = rest.find('<') {
+        out.push_str(&rest[..lt]);
+        rest = &rest[lt..];
+        let Some(end) = find_tag_end(rest) else {
+            // An unclosed `<` is text, not a tag
+            break;
+        };
+        let tag = &rest[..=end];
+        let name = parse_tag(tag).name.to_ascii_lowercase();
+        if name != "a" {
+            out.push_str(tag);
+        }
+        rest = &rest[end + 1..];
     }
-    
-    let readme: GitHubReadme = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse README: {}", e))?;
-    
-    if let Some(content) = readme.content {
-        // GitHub returns base64 encoded content
-        let decoded = base64::engine::general_purpose::STANDARD
-            .decode(content.replace('\n', ""))
-            .map_err(|e| format!("Failed to decode README: {}", e))?;
-        
-        String::from_utf8(decoded)
-            .map_err(|e| format!("Invalid UTF-8 in README: {}", e))
-    } else {
-        Err("README content is empty".to_string())
+    out.push_str(rest);
+    out
+}
+
+fn strip_html_tags(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut in_tag = false;
+    for c in input.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => output.push(c),
+            _ => {}
+        }
     }
+    output
 }
 
-// Create a blog post from a GitHub repo README
-fn create_post_from_readme(repo: &GitHubRepo, readme_content: &str) -> String {
-    let date = repo.pushed_at.as_ref()
-        .or(repo.updated_at.as_ref())
-        .map(|d| d.split('T').next().unwrap_or("2025-01-01"))
-        .unwrap_or("2025-01-01");
-    
-    let description = repo.description.as_deref().unwrap_or("A GitHub project");
+// Syntax highlighting for code blocks
+fn highlight_code(code: &str, lang: &str) -> String {
+    let syntax = find_syntax(lang).unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+
+    let theme = &THEME_SET.themes["base16-ocean.dark"];
     
-    let mut tags = vec!["github".to_string(), "project".to_string()];
-    if let Some(lang) = &repo.language {
-        tags.push(lang.to_lowercase());
-    }
-    if let Some(topics) = &repo.topics {
-        for topic in topics.iter().take(3) {
-            tags.push(topic.clone());
+    match highlighted_html_for_string(code, &SYNTAX_SET, syntax, theme) {
+        Ok(html) => {
+            format!(
+                r#"<div class="code-block relative my-4 rounded-lg overflow-hidden">
+                    <div class="code-header flex items-center justify-between px-4 py-2 bg-gray-800 text-gray-400 text-xs">
+                        <span class="code-lang font-mono">{}</span>
+                        <button class="copy-btn hover:text-white transition-colors" onclick="copyCode(this)">
+                            <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 16H6a2 2 0 01-2-2V6a2 2 0 012-2h8a2 2 0 012 2v2m-6 12h8a2 2 0 002-2v-8a2 2 0 00-2-2h-8a2 2 0 00-2 2v8a2 2 0 002 2z"></path>
+                            </svg>
+                        </button>
+                    </div>
+                    <div class="code-content overflow-x-auto">{}</div>
+                </div>"#,
+                if lang.is_empty() { "text" } else { lang },
+                html
+            )
+        }
+        Err(_) => {
+            format!(
+                r#"<pre class="bg-gray-900 text-gray-100 p-4 rounded-lg overflow-x-auto my-4"><code class="language-{}">{}</code></pre>"#,
+                lang,
+                html_escape::encode_text(code)
+            )
         }
     }
+}
 
-    let homepage = repo
-        .homepage
-        .as_deref()
-        .map(str::trim)
-        .filter(|h| !h.is_empty())
-        .unwrap_or("");
-    let website_line = if homepage.is_empty() {
-        String::new()
+// Unhighlighted code block for feeds: no inline colours, header or copy button
+fn plain_code_block(code: &str, lang: &str) -> String {
+    if lang.is_empty() {
+        format!("<pre><code>{}</code></pre>", html_escape::encode_text(code))
     } else {
-        format!("\nwebsite: \"{}\"", homepage)
-    };
-    
-    let tags_str = tags.iter()
-        .map(|t| format!("\"{}\"", t))
-        .collect::<Vec<_>>()
-        .join(", ");
-    
-    format!(
-        r#"---
-title: "{}"
-date: "{}"
-tags: [{}]
-summary: "{}"
-author: "{}"
-keywords: "{}, github, open source"
-github_repo: "{}"{website}
----
+        format!(
+            r#"<pre><code class="language-{}">{}</code></pre>"#,
+            html_escape::encode_double_quoted_attribute(lang),
+            html_escape::encode_text(code)
+        )
+    }
+}
 
-{}
-"#,
-        repo.name,
-        date,
-        tags_str,
-        description.replace('"', "'"),
-        GITHUB_USERNAME.as_str(),
-        repo.name,
-        repo.full_name,
-        readme_content,
-        website = website_line
-    )
+// Syntax definition for a fence language, by name or file extension
+fn find_syntax(lang: &str) -> Option<&'static syntect::parsing::SyntaxReference> {
+    SYNTAX_SET
+        .find_syntax_by_token(lang)
+        .or_else(|| SYNTAX_SET.find_syntax_by_extension(lang))
 }
 
-// Sync all GitHub repos - creates/updates markdown files
-async fn sync_github_repos() -> impl IntoResponse {
-    let mut result = SyncResult {
-        success: true,
-        message: String::new(),
-        repos_synced: Vec::new(),
-        errors: Vec::new(),
-    };
-    
-    // Ensure content directory exists
-    let content_dir = PathBuf::from("content");
-    if !content_dir.exists() {
-        if let Err(e) = fs::create_dir_all(&content_dir) {
-            result.success = false;
-            result.message = format!("Failed to create content directory: {}", e);
-            return Json(result);
-        }
+// Fence languages that render as something other than plain text, or are plain text on purpose
+fn is_known_language(lang: &str) -> bool {
+    matches!(lang, "mermaid" | "dot" | "graphviz" | "text" | "plain" | "plaintext" | "txt")
+        || find_syntax(lang).is_some()
+}
+
+// Hex-encoded SHA-256 of some content, used as a stable cache key
+fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+fn diagram_figure(svg: &str, lang: &str) -> String {
+    format!(r#"<figure class="diagram diagram-{}">{}</figure>"#, lang, svg)
+}
+
+// Inline SVG for a mermaid/graphviz block, from the memory or disk cache. Unrendered diagrams are
+// queued for the next refresh and show as highlighted source until then
+fn render_diagram(source: &str, lang: &str) -> Option<String> {
+    let hash = content_hash(&format!("{}\n{}", lang, source));
+    if let Some(cached) = DIAGRAM_CACHE.lock().unwrap().get(&hash) {
+        return Some(cached.clone());
     }
-    
-    // Fetch repos
-    let repos = match fetch_github_repos().await {
-        Ok(r) => r,
-        Err(e) => {
-            result.success = false;
-            result.message = e;
-            return Json(result);
-        }
-    };
-    
-    println!("📡 Found {} repositories for {}", repos.len(), GITHUB_USERNAME.as_str());
-    
-    for repo in repos {
-        println!("  → Fetching README for {}...", repo.name);
-        
-        match fetch_readme(&repo.name).await {
-            Ok(readme) => {
-                let post_content = create_post_from_readme(&repo, &readme);
-                let filename = format!("github-{}.md", repo.name.to_lowercase().replace(' ', "-"));
-                let filepath = content_dir.join(&filename);
-                
-                match fs::write(&filepath, &post_content) {
-                    Ok(_) => {
-                        println!("    ✅ Created {}", filename);
-                        result.repos_synced.push(repo.name);
-                    }
-                    Err(e) => {
-                        let err = format!("Failed to write {}: {}", filename, e);
-                        println!("    ❌ {}", err);
-                        result.errors.push(err);
-                    }
+
+    let cache_path = PathBuf::from(DIAGRAM_CACHE_DIR).join(format!("{}.svg", hash));
+    if let Ok(svg) = fs::read_to_string(&cache_path) {
+        let html = diagram_figure(&svg, lang);
+        DIAGRAM_CACHE.lock().unwrap().insert(hash, html.clone());
+        return Some(html);
+    }
+
+    let recently_failed = DIAGRAM_FAILURES
+        .lock()
+        .unwrap()
+        .get(&hash)
+        .is_some_and(|failed| failed.elapsed() < DIAGRAM_RETRY);
+    if !recently_failed {
+        PENDING_DIAGRAMS.lock().unwrap().insert(hash, (lang.to_string(), source.to_string()));
+    }
+    None
+}
+
+// Render every diagram queued since the last refresh off the async runtime, caching successes on disk.
+// Failures (e.g. the CLI isn't installed) keep the highlighted fallback and are retried after DIAGRAM_RETRY
+async fn refresh_diagrams() {
+    let pending: Vec<(String, (String, String))> = PENDING_DIAGRAMS.lock().unwrap().drain().collect();
+    for (hash, (lang, source)) in pending {
+        let rendered = {
+            let (hash, lang) = (hash.clone(), lang.clone());
+            tokio::task::spawn_blocking(move || {
+                if lang == "mermaid" {
+                    run_mermaid(&source, &hash)
+                } else {
+                    run_graphviz(&source)
                 }
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()))
+        };
+        match rendered {
+            Ok(svg) => {
+                let svg = sanitize_svg(&svg);
+                let _ = fs::create_dir_all(DIAGRAM_CACHE_DIR);
+                let _ = fs::write(PathBuf::from(DIAGRAM_CACHE_DIR).join(format!("{}.svg", hash)), &svg);
+                DIAGRAM_FAILURES.lock().unwrap().remove(&hash);
+                DIAGRAM_CACHE.lock().unwrap().insert(hash, diagram_figure(&svg, &lang));
             }
             Err(e) => {
-                println!("    ⚠️  {}", e);
-                result.errors.push(e);
+                eprintln!("Failed to render {} diagram: {}", lang, e);
+                DIAGRAM_FAILURES.lock().unwrap().insert(hash, Instant::now());
             }
         }
     }
-    
-    result.message = format!(
-        "Synced {} repos, {} errors",
-        result.repos_synced.len(),
-        result.errors.len()
-    );
-    
-    println!("✨ GitHub sync complete: {}", result.message);
-    
-    Json(result)
 }
 
-// List all GitHub repos (without syncing)
-async fn list_github_repos() -> impl IntoResponse {
-    match fetch_github_repos().await {
-        Ok(repos) => {
-            let repo_list: Vec<serde_json::Value> = repos.iter().map(|r| {
-                serde_json::json!({
-                    "name": r.name,
-                    "description": r.description,
-                    "url": r.html_url,
-                    "language": r.language,
-                    "stars": r.stargazers_count,
-                    "updated": r.pushed_at
-                })
-            }).collect();
-            
-            Json(serde_json::json!({
-                "success": true,
-                "username": GITHUB_USERNAME.as_str(),
-                "count": repo_list.len(),
-                "repos": repo_list
-            }))
-        }
-        Err(e) => {
-            Json(serde_json::json!({
-                "success": false,
-                "error": e
-            }))
-        }
+// Graphviz reads the graph on stdin and writes SVG to stdout
+fn run_graphviz(source: &str) -> Result<String, String> {
+    let mut child = Command::new(GRAPHVIZ_DOT.as_str())
+        .arg("-Tsvg")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", GRAPHVIZ_DOT.as_str(), e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(source.as_bytes())
+            .map_err(|e| format!("Failed to write graph source: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run {}: {}", GRAPHVIZ_DOT.as_str(), e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
     }
+    String::from_utf8(output.stdout).map_err(|e| format!("Invalid UTF-8 in SVG: {}", e))
 }
 
-// Search posts by title, summary, tags, and content
-async fn search_posts(Query(params): Query<SearchParams>) -> impl IntoResponse {
-    let query = params.q.unwrap_or_default().trim().to_lowercase();
-    if query.is_empty() {
-        return Json(serde_json::json!({ "results": [] }));
+// mermaid-cli only works with files, so stage the source next to the cache. Labels are drawn as SVG
+// text rather than <foreignObject> HTML, which sanitize_svg removes
+fn run_mermaid(source: &str, hash: &str) -> Result<String, String> {
+    fs::create_dir_all(DIAGRAM_CACHE_DIR).map_err(|e| format!("Failed to create diagram cache: {}", e))?;
+    let input = PathBuf::from(DIAGRAM_CACHE_DIR).join(format!("{}.mmd", hash));
+    let output = PathBuf::from(DIAGRAM_CACHE_DIR).join(format!("{}.mmd.svg", hash));
+    let config = PathBuf::from(DIAGRAM_CACHE_DIR).join("mermaid-config.json");
+    fs::write(&input, source).map_err(|e| format!("Failed to write diagram source: {}", e))?;
+    fs::write(&config, r#"{"htmlLabels": false, "flowchart": {"htmlLabels": false}}"#)
+        .map_err(|e| format!("Failed to write mermaid config: {}", e))?;
+
+    let result = Command::new(MERMAID_CLI.as_str())
+        .arg("-i")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .arg("-c")
+        .arg(&config)
+        .stdin(Stdio::null())
+        .output();
+    let _ = fs::remove_file(&input);
+
+    let result = result.map_err(|e| format!("Failed to start {}: {}", MERMAID_CLI.as_str(), e))?;
+    if !result.status.success() {
+        let _ = fs::remove_file(&output);
+        return Err(String::from_utf8_lossy(&result.stderr).trim().to_string());
     }
 
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    let mut results: Vec<SearchResult> = Vec::new();
+    let svg = fs::read_to_string(&output).map_err(|e| format!("Failed to read rendered SVG: {}", e));
+    let _ = fs::remove_file(&output);
+    svg
+}
 
-    for post in posts {
-        let haystack = format!(
-            "{} {} {} {}",
-            post.title,
-            post.summary,
-            post.tags.join(" "),
-            strip_html_tags(&post.content)
-        )
-        .to_lowercase();
+// A single start or end tag split into its parts
+struct RawTag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attrs: Vec<(String, Option<String>)>,
+}
 
-        if haystack.contains(&query) {
-            results.push(SearchResult {
-                title: post.title,
-                slug: post.slug,
-                summary: post.summary,
-                date: post.date.clone(),
-                date_iso: post.date_iso,
-                tags: post.tags,
-                reading_time: post.reading_time,
-            });
+// Find the end of a tag starting at `<`, skipping any `>` inside quoted attribute values
+fn find_tag_end(input: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (i, c) in input.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '>') => return Some(i),
+            _ => {}
         }
     }
-
-    Json(serde_json::json!({ "results": results }))
+    None
 }
 
-// ============================================================================
-// Admin Panel - Routes & Handlers
-// ============================================================================
-
-// Helper to check if request has valid session
-async fn is_authenticated(headers: &HeaderMap, state: &AdminState) -> bool {
-    if let Some(cookie) = headers.get("cookie") {
-        if let Ok(cookie_str) = cookie.to_str() {
-            for part in cookie_str.split(';') {
-                let part = part.trim();
-                if part.starts_with("session=") {
-                    let token = &part[8..];
-                    let sessions = state.sessions.read().await;
-                    if let Some(session) = sessions.get(token) {
-                        let now = Instant::now();
-                        if now.duration_since(session.created_at) < Duration::from_secs(SESSION_DURATION_SECS) {
-                            return true;
-                        }
-                    }
+// Parse `<name attr="value" ...>`; the name keeps its case (SVG is case-sensitive) and
+// attribute values keep their original, still entity-encoded, text
+fn parse_tag(tag: &str) -> RawTag {
+    let inner = tag.trim_start_matches('<').trim_end_matches('>');
+    let closing = inner.starts_with('/');
+    let self_closing = inner.ends_with('/');
+    let inner = inner.trim_start_matches('/').trim_end_matches('/');
+
+    let name_end = inner.find(|c: char| c.is_whitespace()).unwrap_or(inner.len());
+    let name = inner[..name_end].to_string();
+    let mut rest = &inner[name_end..];
+    let mut attrs = Vec::new();
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let key_end = rest.find(|c: char| c.is_whitespace() || c == '=').unwrap_or(rest.len());
+        let key = rest[..key_end].to_string();
+        rest = rest[key_end..].trim_start();
+
+        let value = if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            match after_eq.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let body = &after_eq[1..];
+                    let end = body.find(q).unwrap_or(body.len());
+                    rest = body.get(end + 1..).unwrap_or("");
+                    Some(body[..end].to_string())
+                }
+                _ => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    rest = &after_eq[end..];
+                    Some(after_eq[..end].to_string())
                 }
             }
+        } else {
+            None
+        };
+
+        if key.is_empty() {
+            // Stray `=` or quote - skip a character so we always make progress
+            rest = rest.get(1..).unwrap_or("");
+            continue;
         }
+        attrs.push((key, value));
     }
-    false
+
+    RawTag { name, closing, self_closing, attrs }
 }
 
-// Add secure headers to response
-fn add_security_headers(mut response: Response) -> Response {
-    let headers = response.headers_mut();
-    headers.insert(
-        header::X_FRAME_OPTIONS,
-        "DENY".parse().unwrap(),
-    );
-    headers.insert(
-        header::X_CONTENT_TYPE_OPTIONS,
-        "nosniff".parse().unwrap(),
-    );
-    headers.insert(
-        "X-XSS-Protection",
-        "1; mode=block".parse().unwrap(),
-    );
-    headers.insert(
-        header::STRICT_TRANSPORT_SECURITY,
-        "max-age=31536000; includeSubDomains".parse().unwrap(),
-    );
-    headers.insert(
-        header::CONTENT_SECURITY_POLICY,
-        "default-src 'self'; script-src 'self' 'unsafe-inline' fonts.googleapis.com; style-src 'self' 'unsafe-inline' fonts.googleapis.com; font-src 'self' fonts.gstatic.com; img-src 'self' data: https:".parse().unwrap(),
-    );
-    response
+fn render_tag(tag: &RawTag) -> String {
+    if tag.closing {
+        return format!("</{}>", tag.name);
+    }
+    let mut out = format!("<{}", tag.name);
+    for (key, value) in &tag.attrs {
+        match value {
+            Some(v) => out.push_str(&format!(" {}=\"{}\"", key, v.replace('"', "&quot;"))),
+            None => out.push_str(&format!(" {}", key)),
+        }
+    }
+    out.push_str(if tag.self_closing { "/>" } else { ">" });
+    out
 }
 
-// Get client IP for rate limiting
-fn get_client_ip(headers: &HeaderMap) -> String {
-    headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.split(',').next().unwrap_or("unknown").to_string())
-        .unwrap_or_else(|| "unknown".to_string())
+// True for URLs that would execute script when followed or loaded
+fn is_unsafe_url(value: &str) -> bool {
+    let decoded = html_escape::decode_html_entities(value);
+    let normalized: String = decoded
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    normalized.starts_with("javascript:")
+        || normalized.starts_with("vbscript:")
+        || (normalized.starts_with("data:") && !normalized.starts_with("data:image/"))
 }
 
-// Admin login page
-async fn admin_login_page(
-    headers: HeaderMap,
-    State(state): State<AdminState>,
-) -> impl IntoResponse {
-    // If already logged in, redirect to dashboard
-    if is_authenticated(&headers, &state).await {
-        return Redirect::to("/admin/dashboard").into_response();
+// Skip past the closing tag of an element whose start tag was just consumed
+fn skip_element<'a>(rest: &'a str, name: &str) -> &'a str {
+    let close = format!("</{}", name);
+    match rest.to_ascii_lowercase().find(&close) {
+        Some(i) => rest[i..].find('>').map(|e| &rest[i + e + 1..]).unwrap_or(""),
+        None => "",
     }
-    
-    add_security_headers(Html(ADMIN_LOGIN_HTML.to_string()).into_response())
 }
 
-// Admin login submit
-async fn admin_login_submit(
-    headers: HeaderMap,
-    State(state): State<AdminState>,
-    Form(form): Form<LoginForm>,
-) -> impl IntoResponse {
-    let client_ip = get_client_ip(&headers);
-    
-    // Check rate limiting
-    {
-        let mut attempts = state.login_attempts.write().await;
-        if let Some(data) = attempts.get_mut(&client_ip) {
-            // Check if locked
-            if let Some(locked_until) = data.locked_until {
-                if Instant::now() < locked_until {
-                    let remaining = locked_until.duration_since(Instant::now()).as_secs();
-                    return Html(format!(
-                        r#"<!DOCTYPE html><html><head><meta charset="UTF-8"><title>Locked</title></head>
-                        <body style="background:#000;color:#fff;font-family:system-ui;display:flex;justify-content:center;align-items:center;height:100vh;margin:0">
-                        <div style="text-align:center"><h1>Too Many Attempts</h1><p>Try again in {} seconds</p><a href="/admin" style="color:#666">Back</a></div>
-                        </body></html>"#, remaining
-                    )).into_response();
-                } else {
-                    // Reset after lockout expires
-                    data.attempts = 0;
-                    data.locked_until = None;
-                }
+// Elements sanitize_svg drops with their content: script, embedded documents, HTML islands, stylesheets
+// (which can restyle the whole page) and animations (whose to/values can set an href to a script URL)
+const SVG_DROPPED_ELEMENTS: &[&str] = &[
+    "script", "iframe", "object", "embed", "foreignobject", "style", "animate", "set", "animatemotion",
+    "animatetransform", "discard",
+];
+
+// Strip scripts, event handlers and script URLs from renderer output before inlining it
+fn sanitize_svg(svg: &str) -> String {
+    // Drop the XML prolog and doctype - only the <svg> element is embedded
+    let mut rest = svg.find("<svg").map(|i| &svg[i..]).unwrap_or("");
+    let mut out = String::with_capacity(rest.len());
+
+    while let Some(lt) = rest.find('<') {
+        out.push_str(&rest[..lt]);
+        rest = &rest[lt..];
+
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map(|e| &rest[e + 3..]).unwrap_or("");
+            continue;
+        }
+        let end = match find_tag_end(rest) {
+            Some(e) => e,
+            None => {
+                rest = "";
+                break;
             }
-            
-            // Reset if first attempt was more than lockout duration ago
-            if Instant::now().duration_since(data.first_attempt) > Duration::from_secs(LOCKOUT_DURATION_SECS) {
-                data.attempts = 0;
-                data.first_attempt = Instant::now();
+        };
+        let mut tag = parse_tag(&rest[..=end]);
+        rest = &rest[end + 1..];
+
+        let name = tag.name.to_ascii_lowercase();
+        if SVG_DROPPED_ELEMENTS.contains(&name.as_str()) {
+            // Skip the element along with everything inside it
+            if !tag.closing && !tag.self_closing {
+                rest = skip_element(rest, &name);
             }
+            continue;
         }
+
+        tag.attrs.retain(|(key, value)| {
+            let key = key.to_ascii_lowercase();
+            if key.starts_with("on") {
+                return false;
+            }
+            let is_url_attr = matches!(key.as_str(), "href" | "xlink:href" | "src" | "action" | "formaction");
+            !(is_url_attr && value.as_deref().map(is_unsafe_url).unwrap_or(false))
+        });
+        out.push_str(&render_tag(&tag));
     }
-    
-    // Verify password
-    if form.password == ADMIN_PASSWORD.as_str() {
-        // Clear login attempts
-        {
-            let mut attempts = state.login_attempts.write().await;
-            attempts.remove(&client_ip);
+
+    out.push_str(rest);
+    out
+}
+
+// ============================================================================
+// HTML Sanitization - allowlist filter for author HTML in rendered Markdown
+// ============================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SanitizeProfile {
+    Off,
+    Standard, // Hand-written posts: formatting, media and inline SVG
+    Strict,   // Imported READMEs: what GitHub itself renders, no embeds or inline styles
+}
+
+// Tags and attributes allowed by every profile (roughly GitHub's README allowlist)
+const SANITIZE_BASE_TAGS: &[&str] = &[
+    "a", "abbr", "b", "blockquote", "br", "caption", "center", "code", "col", "colgroup", "dd", "del",
+    "details", "div", "dl", "dt", "em", "figcaption", "figure", "h1", "h2", "h3", "h4", "h5", "h6",
+    "hr", "i", "img", "input", "ins", "kbd", "li", "mark", "ol", "p", "picture", "pre", "q", "rp",
+    "rt", "ruby", "s", "samp", "section", "small", "source", "span", "strike", "strong", "sub",
+    "summary", "sup", "table", "tbody", "td", "tfoot", "th", "thead", "tr", "tt", "u", "ul", "var",
+    "wbr", "svg", "path",
+];
+const SANITIZE_BASE_ATTRIBUTES: &[&str] = &[
+    "id", "class", "title", "lang", "dir", "role", "href", "src", "srcset", "sizes", "media", "type",
+    "alt", "width", "height", "align", "valign", "colspan", "rowspan", "scope", "start", "reversed",
+    "open", "datetime", "cite", "checked", "disabled", "loading", "decoding", "target", "rel",
+    // Inline SVG icons
+    "viewbox", "xmlns", "fill", "stroke", "stroke-width", "stroke-linecap", "stroke-linejoin", "d",
+];
+// Extra tags and attributes for hand-written posts
+const SANITIZE_STANDARD_TAGS: &[&str] = &[
+    "article", "aside", "audio", "cite", "footer", "header", "iframe", "nav", "time", "track", "video",
+    "circle", "defs", "ellipse", "g", "line", "lineargradient", "polygon", "polyline", "rect", "stop",
+    "text", "tspan",
+];
+const SANITIZE_STANDARD_ATTRIBUTES: &[&str] = &[
+    "style", "controls", "autoplay", "muted", "loop", "playsinline", "poster", "preload", "kind",
+    "srclang", "label", "allow", "allowfullscreen", "frameborder", "referrerpolicy",
+    "x", "y", "x1", "y1", "x2", "y2", "cx", "cy", "r", "rx", "ry", "points", "transform", "offset",
+    "stop-color", "opacity", "font-size", "font-weight", "text-anchor",
+];
+// Elements removed together with everything inside them
+const SANITIZE_DROPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "template", "noscript", "iframe", "object", "embed", "applet", "frame",
+    "frameset", "textarea", "select", "title", "head", "math",
+];
+// Attributes holding URLs that must not be script URLs
+const SANITIZE_URL_ATTRIBUTES: &[&str] = &["href", "src", "srcset", "cite", "poster", "action", "formaction", "xlink:href"];
+
+impl SanitizeProfile {
+    fn from_env(key: &str, default: Self) -> Self {
+        match env::var(key).map(|v| v.trim().to_lowercase()).as_deref() {
+            Ok("off") | Ok("none") => Self::Off,
+            Ok("standard") => Self::Standard,
+            Ok("strict") => Self::Strict,
+            Ok(other) => {
+                eprintln!("Unknown {} value '{}', using default", key, other);
+                default
+            }
+            Err(_) => default,
         }
-        
-        // Create session
-        let token = Uuid::new_v4().to_string();
-        {
-            let mut sessions = state.sessions.write().await;
-            sessions.insert(token.clone(), SessionData {
-                created_at: Instant::now(),
-                last_activity: Instant::now(),
-            });
+    }
+
+    fn allows_tag(self, name: &str) -> bool {
+        match self {
+            Self::Off => true,
+            Self::Standard => SANITIZE_BASE_TAGS.contains(&name) || SANITIZE_STANDARD_TAGS.contains(&name),
+            Self::Strict => SANITIZE_BASE_TAGS.contains(&name),
         }
-        
-        // Set cookie and redirect
-        Response::builder()
-            .status(StatusCode::SEE_OTHER)
-            .header("Location", "/admin/dashboard")
-            .header("Set-Cookie", format!("session={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}", token, SESSION_DURATION_SECS))
-            .body("".to_string())
-            .unwrap()
-            .into_response()
-    } else {
-        // Record failed attempt
-        {
-            let mut attempts = state.login_attempts.write().await;
-            let data = attempts.entry(client_ip.clone()).or_insert(LoginAttemptData {
-                attempts: 0,
-                first_attempt: Instant::now(),
-                locked_until: None,
-            });
-            data.attempts += 1;
-            
-            if data.attempts >= MAX_LOGIN_ATTEMPTS {
-                data.locked_until = Some(Instant::now() + Duration::from_secs(LOCKOUT_DURATION_SECS));
+    }
+
+    fn allows_attribute(self, name: &str) -> bool {
+        if name.starts_with("aria-") {
+            return true;
+        }
+        match self {
+            Self::Off => true,
+            Self::Standard => {
+                SANITIZE_BASE_ATTRIBUTES.contains(&name) || SANITIZE_STANDARD_ATTRIBUTES.contains(&name)
             }
+            Self::Strict => SANITIZE_BASE_ATTRIBUTES.contains(&name),
         }
-        
-        Html(format!(
-            r#"<!DOCTYPE html><html><head><meta charset="UTF-8"><title>Login Failed</title></head>
-            <body style="background:#000;color:#fff;font-family:system-ui;display:flex;justify-content:center;align-items:center;height:100vh;margin:0">
-            <div style="text-align:center"><h1>Invalid Password</h1><a href="/admin" style="color:#666">Try Again</a></div>
-            </body></html>"#
-        )).into_response()
     }
 }
 
-// Admin logout
-async fn admin_logout(State(state): State<AdminState>, headers: HeaderMap) -> impl IntoResponse {
-    // Remove session
-    if let Some(cookie) = headers.get("cookie") {
-        if let Ok(cookie_str) = cookie.to_str() {
-            for part in cookie_str.split(';') {
-                let part = part.trim();
-                if part.starts_with("session=") {
-                    let token = &part[8..];
-                    let mut sessions = state.sessions.write().await;
-                    sessions.remove(token);
-                }
+// Filter rendered HTML against the profile's allowlist: unknown tags are unwrapped, dangerous
+// elements dropped with their content, and event handlers and script URLs removed
+fn sanitize_html(html: &str, profile: SanitizeProfile) -> String {
+    if profile == SanitizeProfile::Off {
+        return html.to_string();
+    }
+
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(lt) = rest.find('<') {
+        out.push_str(&rest[..lt]);
+        rest = &rest[lt..];
+
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map(|e| &rest[e + 3..]).unwrap_or("");
+            continue;
+        }
+        let starts_tag = rest[1..]
+            .chars()
+            .next()
+            .map(|c| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'))
+            .unwrap_or(false);
+        let end = match find_tag_end(rest) {
+            Some(e) if starts_tag => e,
+            _ => {
+                // A stray `<` in author HTML - keep it as text
+                out.push_str("&lt;");
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let raw = &rest[..=end];
+        rest = &rest[end + 1..];
+
+        // Doctypes, CDATA and processing instructions have no place in a post body
+        if raw.starts_with("<!") || raw.starts_with("<?") {
+            continue;
+        }
+
+        let mut tag = parse_tag(raw);
+        let name = tag.name.to_ascii_lowercase();
+        let src_is_https = tag.attrs.iter().any(|(k, v)| {
+            k.eq_ignore_ascii_case("src") && v.as_deref().map(|v| v.trim().starts_with("https://")).unwrap_or(false)
+        });
+        let allowed = profile.allows_tag(&name)
+            // Task list checkboxes are the only inputs Markdown produces
+            && (name != "input" || tag.attrs.iter().any(|(k, v)| k.eq_ignore_ascii_case("type") && v.as_deref() == Some("checkbox")))
+            // Embedded frames must load over https
+            && (name != "iframe" || tag.closing || src_is_https);
+
+        if !allowed {
+            if SANITIZE_DROPPED_ELEMENTS.contains(&name.as_str()) && !tag.closing && !tag.self_closing {
+                rest = skip_element(rest, &name);
+            }
+            continue;
+        }
+
+        tag.attrs.retain(|(key, value)| {
+            let key = key.to_ascii_lowercase();
+            if key.starts_with("on") || !profile.allows_attribute(&key) {
+                return false;
+            }
+            let value = value.as_deref().unwrap_or("");
+            if key == "srcset" {
+                return !value.split(',').any(|candidate| is_unsafe_url(candidate.trim()));
             }
+            !(SANITIZE_URL_ATTRIBUTES.contains(&key.as_str()) && is_unsafe_url(value))
+        });
+        if name == "input" {
+            // Checkboxes are display-only
+            tag.attrs.retain(|(k, _)| !k.eq_ignore_ascii_case("disabled"));
+            tag.attrs.push(("disabled".to_string(), None));
+        }
+        if name == "a" && tag.attrs.iter().any(|(k, _)| k.eq_ignore_ascii_case("target")) {
+            // Links opening a new tab must not get a handle on this page
+            tag.attrs.retain(|(k, _)| !k.eq_ignore_ascii_case("rel"));
+            tag.attrs.push(("rel".to_string(), Some("noopener noreferrer".to_string())));
         }
+        out.push_str(&render_tag(&tag));
     }
-    
-    Response::builder()
-        .status(StatusCode::SEE_OTHER)
-        .header("Location", "/admin")
-        .header("Set-Cookie", "session=; Path=/; HttpOnly; Max-Age=0")
-        .body("".to_string())
-        .unwrap()
+
+    out.push_str(rest);
+    out
 }
 
-// Admin dashboard
-async fn admin_dashboard(
-    headers: HeaderMap,
-    State(state): State<AdminState>,
-) -> impl IntoResponse {
-    if !is_authenticated(&headers, &state).await {
-        return Redirect::to("/admin").into_response();
+// Resolve an attribute's URL (still entity-encoded) against `base`; absolute URLs are kept as written
+fn absolute_url(value: &str, base: &reqwest::Url) -> String {
+    let decoded = html_escape::decode_html_entities(value.trim());
+    if decoded.is_empty() || reqwest::Url::parse(&decoded).is_ok() {
+        return value.to_string();
     }
-    
-    let site_config = SiteConfig::default();
-    let posts = get_posts(&site_config);
-    let github_links = state.github_links.read().await;
-    
-    let mut posts_html = String::from("<table class=\"posts-table\"><thead><tr><th>Title</th><th>Date</th><th>Read Time</th><th style=\"text-align:right\">Actions</th></tr></thead><tbody>");
-    
-    if posts.is_empty() {
-        posts_html.push_str("<tr><td colspan=\"4\" style=\"text-align:center;padding:3rem\"><div class=\"empty-state\"><div class=\"empty-state-icon\">📝</div><div>No posts yet</div></div></td></tr>");
-    } else {
-        for post in &posts {
-            let is_github_linked = github_links.contains_key(&post.slug);
-            let github_badge = if is_github_linked {
-                " <span style=\"background:#1a1a1a;padding:2px 6px;border-radius:3px;font-size:11px;margin-left:8px\">GitHub</span>"
-            } else {
-                ""
-            };
-            
-            posts_html.push_str(&format!(
-                "<tr><td><div class=\"post-title\">{}{}</div></td><td class=\"post-meta\">{}</td><td class=\"post-meta\">{} min</td><td style=\"text-align:right\"><div class=\"table-actions\"><a href=\"/admin/edit/{}\" class=\"btn-sm\">Edit</a><button onclick=\"deletePost('{}')\" class=\"btn-sm btn-danger\">Delete</button></div></td></tr>",
-                html_escape::encode_text(&post.title),
-                github_badge,
-                post.date,
-                post.reading_time,
-                post.slug,
-                post.slug
-            ));
-        }
+    match base.join(&decoded) {
+        Ok(url) => html_escape::encode_double_quoted_attribute(url.as_str()).into_owned(),
+        Err(_) => value.to_string(),
     }
-    
-    posts_html.push_str("</tbody></table>");
-    
-    let dashboard_html = ADMIN_DASHBOARD_HTML
-        .replace("{{posts_count}}", &posts.len().to_string())
-        .replace("{{posts_list}}", &posts_html);
-    
-    Html(dashboard_html).into_response()
 }
 
-// Admin new post page
-async fn admin_new_post(headers: HeaderMap, State(state): State<AdminState>) -> impl IntoResponse {
-    if !is_authenticated(&headers, &state).await {
-        return Redirect::to("/admin").into_response();
-    }
-    Html(ADMIN_EDITOR_HTML.replace("{{mode}}", "new").replace("{{title}}", "").replace("{{content}}", "").replace("{{tags}}", "").replace("{{summary}}", "").replace("{{slug}}", "")).into_response()
-}
+// Rendered HTML as feed readers need it: every URL absolute, no scripts, buttons, forms or event
+// handlers, and no inline styles outside SVG. Embedded frames become links to what they showed
+fn feed_safe_html(html: &str, base: &reqwest::Url) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    let mut svg_depth = 0usize;
 
-// Admin edit post page
-async fn admin_edit_post(
-    headers: HeaderMap,
-    State(state): State<AdminState>,
-    Path(slug): Path<String>,
-) -> impl IntoResponse {
-    if !is_authenticated(&headers, &state).await {
-        return Redirect::to("/admin").into_response();
-    }
-    
-    // Find the post file
-    let content_path = PathBuf::from("content");
-    let mut post_content = String::new();
-    let mut post_title = String::new();
-    let mut post_tags = String::new();
-    let mut post_summary = String::new();
-    let mut found_file_slug = String::new();
-    
-    if let Ok(entries) = fs::read_dir(&content_path) {
-        for entry in entries.flatten() {
-            if let Ok(content) = fs::read_to_string(entry.path()) {
-                if let Some(meta) = parse_metadata(&content) {
-                    // Generate slug from title (same way as get_posts)
-                    let title_slug = meta.title.to_lowercase()
-                        .chars()
-                        .map(|c| if c.is_alphanumeric() || c == ' ' { c } else { ' ' })
-                        .collect::<String>()
-                        .split_whitespace()
-                        .collect::<Vec<_>>()
-                        .join("-");
-                    
-                    let file_slug = entry.path()
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("")
-                        .to_string();
-                    
-                    if title_slug == slug {
-                        post_title = meta.title;
-                        post_tags = meta.tags.join(", ");
-                        post_summary = meta.summary;
-                        found_file_slug = file_slug;
-                        // Get content after frontmatter
-                        let parts: Vec<&str> = content.splitn(3, "---").collect();
-                        if parts.len() >= 3 {
-                            post_content = parts[2].trim().to_string();
-                        }
-                        break;
+    while let Some(lt) = rest.find('<') {
+        out.push_str(&rest[..lt]);
+        rest = &rest[lt..];
+
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map(|e| &rest[e + 3..]).unwrap_or("");
+            continue;
+        }
+        let starts_tag = rest[1..].chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '/');
+        let end = match find_tag_end(rest) {
+            Some(e) if starts_tag => e,
+            _ => {
+                out.push('<');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let mut tag = parse_tag(&rest[..=end]);
+        rest = &rest[end + 1..];
+
+        let name = tag.name.to_ascii_lowercase();
+        match name.as_str() {
+            "script" | "noscript" | "button" | "form" | "textarea" | "select" => {
+                if !tag.closing && !tag.self_closing {
+                    rest = skip_element(rest, &name);
+                }
+                continue;
+            }
+            "iframe" => {
+                if !tag.closing {
+                    let src = tag.attrs.iter().find(|(k, _)| k.eq_ignore_ascii_case("src")).and_then(|(_, v)| v.as_deref());
+                    if let Some(src) = src {
+                        let url = absolute_url(src, base);
+                        out.push_str(&format!("<p><a href=\"{}\">{}</a></p>", url, url));
+                    }
+                    if !tag.self_closing {
+                        rest = skip_element(rest, &name);
                     }
                 }
+                continue;
+            }
+            "svg" if tag.closing => svg_depth = svg_depth.saturating_sub(1),
+            "svg" if !tag.self_closing => svg_depth += 1,
+            _ => {}
+        }
+
+        tag.attrs.retain(|(key, _)| {
+            let key = key.to_ascii_lowercase();
+            !key.starts_with("on") && (svg_depth > 0 || key != "style")
+        });
+        for (key, value) in &mut tag.attrs {
+            let Some(value) = value else { continue };
+            // References inside an SVG to its own elements stay local
+            if svg_depth > 0 && value.starts_with('#') {
+                continue;
+            }
+            match key.to_ascii_lowercase().as_str() {
+                "href" | "xlink:href" | "src" | "poster" | "cite" => *value = absolute_url(value, base),
+                "srcset" => {
+                    *value = value
+                        .split(',')
+                        .map(|candidate| {
+                            let candidate = candidate.trim();
+                            match candidate.split_once(char::is_whitespace) {
+                                Some((url, descriptor)) => format!("{} {}", absolute_url(url, base), descriptor.trim()),
+                                None => absolute_url(candidate, base),
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }
+                _ => {}
             }
         }
+        out.push_str(&render_tag(&tag));
     }
-    
-    Html(ADMIN_EDITOR_HTML
-        .replace("{{mode}}", "edit")
-        .replace("{{title}}", &html_escape::encode_text(&post_title))
-        .replace("{{content}}", &html_escape::encode_text(&post_content))
-        .replace("{{tags}}", &html_escape::encode_text(&post_tags))
-        .replace("{{summary}}", &html_escape::encode_text(&post_summary))
-        .replace("{{slug}}", &found_file_slug)
-    ).into_response()
+
+    out.push_str(rest);
+    out
 }
 
-// Save post (create or update)
-async fn admin_save_post(
-    headers: HeaderMap,
-    State(state): State<AdminState>,
-    Form(form): Form<PostForm>,
-) -> impl IntoResponse {
-    if !is_authenticated(&headers, &state).await {
-        return Json(serde_json::json!({"success": false, "error": "Not authenticated"})).into_response();
-    }
-    
-    let slug = form.slug.clone().unwrap_or_else(|| {
-        form.title.to_lowercase()
-            .chars()
-            .map(|c| if c.is_alphanumeric() { c } else { '-' })
-            .collect::<String>()
-            .split('-')
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            .join("-")
-    });
-    
-    // Check if editing existing post to preserve date and github_repo
-    let file_path = format!("content/{}.md", slug);
-    let (existing_date, existing_github_repo, existing_website) = if let Ok(existing_content) = fs::read_to_string(&file_path) {
-        let mut date = None;
-        let mut github_repo = None;
-        let mut website = None;
-        if let Some((front_matter, _)) = existing_content.strip_prefix("---").and_then(|s| s.split_once("---")) {
-            for line in front_matter.lines() {
-                let parts: Vec<&str> = line.splitn(2, ':').collect();
-                if parts.len() == 2 {
-                    let key = parts[0].trim();
-                    let value = parts[1].trim().trim_matches('"');
-                    if key == "date" {
-                        date = Some(value.to_string());
-                    } else if key == "github_repo" {
-                        github_repo = Some(value.to_string());
-                    } else if key == "website" {
-                        website = Some(value.to_string());
-                    }
-                }
+// CJK scripts are written without spaces, so they are read (and counted) per character
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}'   // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}'   // Hangul syllables
+        | '\u{F900}'..='\u{FAFF}'   // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2FA1F}' // Supplementary ideographs
+    )
+}
+
+// Estimate reading time from rendered HTML: prose words, code tokens and CJK characters each at
+// their own rate, plus a fixed allowance per image. Returns (minutes, words)
+fn calculate_reading_time(html: &str) -> (u32, u32) {
+    // Text is collected first so words split across inline tags (highlighted code spans) count once
+    let (mut prose, mut code, mut images) = (String::new(), String::new(), 0u32);
+    let mut code_depth = 0usize;
+    let mut rest = html;
+
+    loop {
+        let lt = rest.find('<').unwrap_or(rest.len());
+        let text = html_escape::decode_html_entities(&rest[..lt]);
+        if code_depth > 0 { &mut code } else { &mut prose }.push_str(&text);
+        rest = &rest[lt..];
+        if rest.is_empty() {
+            break;
+        }
+
+        let end = match find_tag_end(rest) {
+            Some(e) => e + 1,
+            None => break,
+        };
+        let tag = parse_tag(&rest[..end]);
+        rest = &rest[end..];
+        let name = tag.name.to_lowercase();
+        let class = tag
+            .attrs
+            .iter()
+            .find(|(k, _)| k == "class")
+            .and_then(|(_, v)| v.as_deref())
+            .unwrap_or("");
+
+        match (name.as_str(), tag.closing) {
+            ("pre", false) => code_depth += 1,
+            ("pre", true) => code_depth = code_depth.saturating_sub(1),
+            ("img", false) => images += 1,
+            ("figure", false) if class.split_whitespace().any(|c| c == "diagram") => images += 1,
+            // Not read: icons, copy buttons, the language label above code blocks
+            ("svg", false) | ("button", false) | ("script", false) | ("style", false) if !tag.self_closing => {
+                rest = skip_element(rest, &name);
             }
+            ("div", false) if class.split_whitespace().any(|c| c == "code-header") => {
+                rest = skip_element(rest, "div");
+            }
+            _ => {}
         }
-        (date, github_repo, website)
-    } else {
-        (None, None, None)
-    };
-    
-    let date = existing_date.unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
-    
-    let github_repo_line = existing_github_repo
-        .map(|repo| format!("\ngithub_repo: \"{}\"", repo))
-        .unwrap_or_default();
-    let website_line = existing_website
-        .map(|site| format!("\nwebsite: \"{}\"", site))
-        .unwrap_or_default();
-    
-    let markdown_content = format!(
-        r#"---
-title: "{}"
-date: "{}"
-tags: [{}]
-summary: "{}"{github_repo}{website}
----
+        // Block boundaries separate words even without whitespace between the tags
+        if matches!(name.as_str(), "p" | "br" | "li" | "td" | "th" | "tr" | "div" | "pre" | "blockquote"
+            | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "figcaption" | "section" | "dt" | "dd")
+        {
+            if code_depth > 0 { &mut code } else { &mut prose }.push(' ');
+        }
+    }
 
-{}"#,
-        form.title,
-        date,
-        form.tags.split(',').map(|t| format!("\"{}\"", t.trim())).collect::<Vec<_>>().join(", "),
-        form.summary,
-    form.content,
-    github_repo = github_repo_line,
-    website = website_line
-    );
-    
-    if let Err(e) = fs::write(&file_path, markdown_content) {
-        return Json(serde_json::json!({"success": false, "error": e.to_string()})).into_response();
+    let mut prose_words = 0u32;
+    for word in prose.split(|c: char| c.is_whitespace() || is_cjk(c)) {
+        // Punctuation-only tokens (dashes, arrows) aren't words
+        if word.chars().any(char::is_alphanumeric) {
+            prose_words += 1;
+        }
     }
-    
-    Json(serde_json::json!({"success": true, "slug": slug})).into_response()
+    let cjk_chars = prose.chars().filter(|c| is_cjk(*c)).count() as u32;
+    let code_words = code.split_whitespace().count() as u32;
+
+    let minutes = prose_words as f64 / *READING_WPM
+        + code_words as f64 / *CODE_WPM
+        + cjk_chars as f64 / *CJK_CPM
+        + images as f64 * *IMAGE_READING_SECONDS / 60.0;
+    ((minutes.round() as u32).max(1), prose_words + code_words + cjk_chars)
 }
 
-// Delete post
-async fn admin_delete_post(
-    headers: HeaderMap,
-    State(state): State<AdminState>,
-    Path(slug): Path<String>,
-) -> impl IntoResponse {
-    if !is_authenticated(&headers, &state).await {
-        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
-    }
-    
-    let file_path = format!("content/{}.md", slug);
-    
-    // Also try github- prefix
-    let github_file_path = format!("content/github-{}.md", slug);
-    
-    let result = fs::remove_file(&file_path).or_else(|_| fs::remove_file(&github_file_path));
-    
-    match result {
-        Ok(_) => {
-            // Remove from GitHub links if exists
-            let mut links = state.github_links.write().await;
-            links.remove(&slug);
-            state.save_github_links(&links);
-            
-            Json(serde_json::json!({"success": true}))
+// URL slug for a post title
+fn title_slug(title: &str) -> String {
+    title.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// Post sources: content/*.md plus page bundles (content/<name>/index.md)
+fn post_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Ok(entries) = fs::read_dir("content") {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let index = path.join("index.md");
+                if index.is_file() {
+                    files.push(index);
+                }
+            } else if path.extension().map(|s| s == "md").unwrap_or(false) {
+                files.push(path);
+            }
         }
-        Err(e) => Json(serde_json::json!({"success": false, "error": e.to_string()}))
     }
+    files
 }
 
-// Admin GitHub import page
-async fn admin_github_page(headers: HeaderMap, State(state): State<AdminState>) -> impl IntoResponse {
-    if !is_authenticated(&headers, &state).await {
-        return Redirect::to("/admin").into_response();
+// Directory of the page bundle whose post has this slug
+fn find_bundle_dir(slug: &str) -> Option<PathBuf> {
+    post_files()
+        .into_iter()
+        .filter(|path| path.file_name().map(|n| n == "index.md").unwrap_or(false))
+        .find(|path| {
+            fs::read_to_string(path)
+                .ok()
+                .and_then(|content| parse_metadata(&content))
+                .map(|meta| title_slug(&meta.title) == slug)
+                .unwrap_or(false)
+        })
+        .and_then(|path| path.parent().map(PathBuf::from))
+}
+
+// A front matter date (YYYY-MM-DD) as midnight UTC
+fn parse_front_matter_date(value: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_str(&format!("{} 00:00:00 +0000", value), "%Y-%m-%d %H:%M:%S %z")
+        .ok()
+        .map(|d| Local.from_utc_datetime(&d.naive_utc()))
+}
+
+// Seconds from "3723", "62:03" or "1:02:03"
+fn parse_duration(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .split(':')
+        .try_fold(0u64, |total, part| Some(total * 60 + part.trim().parse::<u64>().ok()?))
+}
+
+// "12:34", or "1:02:03" from an hour up
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
     }
-    Html(ADMIN_GITHUB_HTML.to_string()).into_response()
 }
 
-// List GitHub repos as JSON (for admin)
-async fn admin_list_repos(headers: HeaderMap, State(state): State<AdminState>) -> impl IntoResponse {
-    if !is_authenticated(&headers, &state).await {
-        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+impl Enclosure {
+    // From the enclosure front matter keys; type and length are filled in from the file when it's local
+    fn from_metadata(metadata: &Metadata, ctx: &RenderContext) -> Option<Self> {
+        let url = metadata.enclosure.as_deref().map(str::trim).filter(|u| !u.is_empty())?;
+        let (public, file) = match bundle_asset(url, ctx) {
+            Some((public, file)) => (public, Some(file)),
+            None => (url.to_string(), resolve_local_image(url)),
+        };
+        let url = if public.starts_with('/') { format!("{}{}", ctx.site_url, public) } else { public };
+
+        let mime = metadata
+            .enclosure_type
+            .clone()
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| mime_for_path(std::path::Path::new(url.split(['?', '#']).next().unwrap_or(""))).to_string());
+        let length = metadata
+            .enclosure_length
+            .as_deref()
+            .and_then(|l| l.trim().parse().ok())
+            .or_else(|| file.and_then(|f| fs::metadata(f).ok()).map(|m| m.len()))
+            .unwrap_or(0);
+        let duration_seconds = metadata.enclosure_duration.as_deref().and_then(parse_duration);
+        Some(Self {
+            is_video: mime.starts_with("video/"),
+            url,
+            mime,
+            length,
+            duration_seconds,
+            duration: duration_seconds.map(format_duration),
+        })
     }
-    
-    match fetch_github_repos().await {
-        Ok(repos) => {
-            let github_links = state.github_links.read().await;
-            let repo_list: Vec<serde_json::Value> = repos.iter().map(|r| {
-                let is_linked = github_links.values().any(|link| link.repo_name == r.name);
-                serde_json::json!({
-                    "name": r.name,
-                    "full_name": r.full_name,
-                    "description": r.description,
-                    "url": r.html_url,
-                    "language": r.language,
-                    "stars": r.stargazers_count,
-                    "updated": r.pushed_at,
-                    "is_linked": is_linked
-                })
-            }).collect();
-            
-            Json(serde_json::json!({
-                "success": true,
-                "repos": repo_list
-            }))
+}
+
+// Retrieve all blog posts from content directory
+fn get_posts(site_config: &SiteConfig) -> Vec<Post> {
+    get_posts_for(site_config, RenderProfile::Page)
+}
+
+// A post file with its front matter parsed, before rendering
+struct PostSource {
+    filename: String, // Relative to content/; bundles are named by their directory, e.g. "my-post/index.md"
+    content: String,
+    metadata: Metadata,
+    date: DateTime<Local>,
+    updated: DateTime<Local>,
+    modified: Option<DateTime<Local>>, // The file's modification time
+    slug: String,
+    bundle: Option<PathBuf>,
+}
+
+// Every post file's front matter, without rendering anything
+fn post_sources() -> Vec<PostSource> {
+    let mut sources = Vec::new();
+    for path in post_files() {
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Some(metadata) = parse_metadata(&content) {
+                let modified = fs::metadata(&path)
+                    .ok()
+                    .and_then(|m| m.modified().ok())
+                    .map(DateTime::<Local>::from);
+                let date = parse_front_matter_date(&metadata.date)
+                    .unwrap_or_else(|| modified.unwrap_or_else(Local::now));
+                // Only an explicit `updated` counts as an edit, and never before the date
+                let updated = metadata
+                    .updated
+                    .as_deref()
+                    .and_then(parse_front_matter_date)
+                    .map_or(date, |updated| updated.max(date));
+
+                let slug = title_slug(&metadata.title);
+
+                // Bundles are named by their directory, e.g. "my-post/index.md"
+                let filename = path
+                    .strip_prefix("content")
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let bundle = path
+                    .file_name()
+                    .filter(|n| *n == "index.md")
+                    .and_then(|_| path.parent().map(PathBuf::from));
+                sources.push(PostSource { filename, content, metadata, date, updated, modified, slug, bundle });
+            }
         }
-        Err(e) => Json(serde_json::json!({"success": false, "error": e}))
     }
+    sources
 }
 
-// Import a single GitHub repo
-async fn admin_import_repo(
-    headers: HeaderMap,
-    State(state): State<AdminState>,
-    Form(form): Form<GitHubImportForm>,
-) -> impl IntoResponse {
-    if !is_authenticated(&headers, &state).await {
-        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+// What shortcodes may reference: every post, from its front matter
+fn post_index(sources: &[PostSource]) -> Vec<PostRef> {
+    sources
+        .iter()
+        .map(|source| PostRef {
+            title: source.metadata.title.clone(),
+            slug: source.slug.clone(),
+            summary: source.metadata.summary.clone(),
+            date: source.date.format("%B %d, %Y").to_string(),
+        })
+        .collect()
+}
+
+// All posts, newest first, with content rendered for the page or for feeds
+fn get_posts_for(site_config: &SiteConfig, profile: RenderProfile) -> Vec<Post> {
+    // Front matter first, so shortcodes can reference any post while rendering
+    let sources = post_sources();
+    let index = post_index(&sources);
+
+    let mut posts = Vec::new();
+    for PostSource { filename, content, metadata, date, updated, slug, bundle, .. } in sources {
+        let content_str = content.splitn(3, "---").nth(2).unwrap_or("");
+        let line_offset = content[..content.len() - content_str.len()].matches('\n').count();
+        // Imported READMEs are third-party content and get the stricter allowlist
+        let sanitize = if metadata.github_repo.is_some() {
+            *SANITIZE_IMPORTED
+        } else {
+            *SANITIZE_POSTS
+        };
+        let ctx = RenderContext {
+            posts: &index,
+            sanitize,
+            slug: &slug,
+            bundle: bundle.as_deref(),
+            disabled_stages: &metadata.disable_stages,
+            line_offset,
+            profile,
+            site_url: &site_config.url,
+        };
+        let document = render_markdown(content_str, &ctx);
+        let enclosure = Enclosure::from_metadata(&metadata, &ctx);
+        let excerpt = document.excerpt.clone();
+        // Front matter wins; otherwise summarise the excerpt or the first paragraph as plain text
+        let summary = if !metadata.summary.is_empty() {
+            metadata.summary
+        } else if let Some(excerpt) = &excerpt {
+            truncate_at_word(&html_to_text(excerpt), *SUMMARY_LENGTH)
+        } else {
+            truncate_at_word(&first_paragraph_text(&document.html), *SUMMARY_LENGTH)
+        };
+
+        let tags_clone = metadata.tags.clone();
+        let post = Post {
+            title: metadata.title.clone(),
+            content: document.html,
+            summary,
+            date: date.format("%B %d, %Y").to_string(),
+            date_iso: date.format("%Y-%m-%dT%H:%M:%S%z").to_string(),
+            updated_iso: updated.format("%Y-%m-%dT%H:%M:%S%z").to_string(),
+            tags: tags_clone.clone(),
+            filename,
+            slug: slug.clone(),
+            author: metadata.author.unwrap_or_else(|| site_config.author.clone()),
+            image: metadata.image.unwrap_or_else(|| format!("{}/og-default.png", site_config.url)),
+            image_alt: metadata.image_alt.unwrap_or_else(|| metadata.title.clone()),
+            keywords: metadata.keywords.unwrap_or_else(|| {
+                tags_clone.iter().take(5).cloned().collect::<Vec<_>>().join(", ")
+            }),
+            canonical: metadata.canonical.unwrap_or_else(|| {
+                format!("{}/blog/{}", site_config.url, slug)
+            }),
+            reading_time: document.reading_time,
+            word_count: document.word_count,
+            website: metadata.website,
+            github_repo: metadata.github_repo,
+            excerpt: excerpt.map(|html| unwrap_links(&html)),
+            toc: document.toc,
+            enclosure,
+            text: document.text,
+            links: document.links,
+            images: document.images,
+            ids: document.ids,
+            warnings: document.warnings,
+            published: date,
+            updated,
+        };
+        posts.push(post);
     }
-    
-    // Fetch repo info
-    let repos = match fetch_github_repos().await {
-        Ok(r) => r,
-        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
-    };
-    
-    let repo = match repos.iter().find(|r| r.name == form.repo_name) {
-        Some(r) => r,
-        None => return Json(serde_json::json!({"success": false, "error": "Repo not found"})),
-    };
-    
-    // Fetch README
-    let readme_content = match fetch_readme(&form.repo_name).await {
-        Ok(c) => c,
-        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
-    };
-    
-    // Create post
-    let post_content = create_post_from_readme(repo, &readme_content);
-    let slug = format!("github-{}", form.repo_name.to_lowercase());
-    let file_path = format!("content/{}.md", slug);
-    
-    if let Err(e) = fs::write(&file_path, &post_content) {
-        return Json(serde_json::json!({"success": false, "error": e.to_string()}));
+
+    posts.sort_by(|a, b| b.date_iso.cmp(&a.date_iso));
+    posts
+}
+
+// Background work after posts change on disk: admin saves, GitHub imports and syncs, webhooks
+fn on_content_changed() {
+    after_content_change(true);
+}
+
+// The same work at startup, except that IndexNow only records the pages as they are: a restart
+// isn't a change, and resubmitting everything on each deploy would get the key throttled
+fn on_startup() {
+    after_content_change(false);
+}
+
+fn after_content_change(submit_to_indexnow: bool) {
+    *BLOGROLL.lock().unwrap() = Arc::new(load_blogroll());
+    tokio::spawn(async move {
+        // Rendering queues any bare URLs that still need a preview and any diagrams not yet rendered
+        let _ = tokio::task::spawn_blocking(|| get_posts(&SiteConfig::default())).await;
+        refresh_link_previews().await;
+        refresh_diagrams().await;
+        // Pages may now show cards and diagrams without any file under content/ having changed
+        CONTENT_GENERATION.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        // After previews and diagrams, so feeds are published with their final content
+        websub_publish_changes().await;
+        indexnow_submit_changes(submit_to_indexnow).await;
+    });
+}
+
+// ============================================================================
+// Conditional GET - ETag / Last-Modified validators and 304 responses
+// ============================================================================
+
+// HTTP-date (RFC 9110), e.g. "Sun, 06 Nov 1994 08:49:37 GMT"
+fn http_date(time: DateTime<Local>) -> String {
+    time.with_timezone(&chrono::Utc).format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+// Newest change among `posts`: the Last-Modified of any page or feed built from them
+fn newest_update(posts: &[Post]) -> Option<DateTime<Local>> {
+    posts.iter().map(|p| p.updated).max()
+}
+
+// Quoted strong ETag from a hash of some parts
+fn etag_for(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
     }
-    
-    // Save link
-    let auto_sync = form.auto_sync.as_deref() == Some("on");
-    {
-        let mut links = state.github_links.write().await;
-        links.insert(slug.clone(), GitHubLink {
-            repo_name: form.repo_name.clone(),
-            repo_full_name: repo.full_name.clone(),
-            last_synced: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-            auto_sync,
+    format!("\"{}\"", &format!("{:x}", hasher.finalize())[..32])
+}
+
+// Bumped when pages change without any file under content/ changing
+static CONTENT_GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+// Templates and configuration are read at startup, so validators from a previous run never match
+static VALIDATOR_SEED: Lazy<String> = Lazy::new(|| Uuid::new_v4().to_string());
+// Largest body the validator layer hashes for an ETag; anything bigger, or binary, goes out without one
+const MAX_HASHED_BODY: u64 = 1024 * 1024;
+
+// Path, size and modification time of every file under `dir`
+fn file_stamps(dir: &std::path::Path, stamps: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.metadata() {
+            Ok(meta) if meta.is_dir() => file_stamps(&path, stamps),
+            Ok(meta) => {
+                let modified = meta
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map_or(0, |d| d.as_nanos());
+                stamps.push(format!("{}:{}:{}", path.display(), meta.len(), modified));
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+// Part of every OG image's ETag: bump it when the drawing code changes, so cached images are redrawn
+const OG_IMAGE_VERSION: &str = "1";
+
+// ETag for a page or feed, known without rendering it: everything under content/ (posts, bundles,
+// images, the blogroll), the background generation, the year shown in footers, and the URL itself
+fn content_etag(uri: &str) -> String {
+    let mut stamps = Vec::new();
+    file_stamps(std::path::Path::new("content"), &mut stamps);
+    stamps.sort();
+    let generation = CONTENT_GENERATION.load(std::sync::atomic::Ordering::Relaxed).to_string();
+    let year = Local::now().format("%Y").to_string();
+    etag_for(&[VALIDATOR_SEED.as_str(), &generation, &year, &stamps.join("\n"), uri])
+}
+
+// Whether the client's copy is current. If-None-Match takes precedence over If-Modified-Since
+fn is_not_modified(headers: &HeaderMap, etag: Option<&str>, last_modified: Option<&str>) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+        // Weak comparison, so W/"x" matches "x"
+        let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+        return etag.is_some_and(|etag| {
+            if_none_match.split(',').any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
         });
-        state.save_github_links(&links);
     }
-    
-    Json(serde_json::json!({
-        "success": true,
-        "slug": slug,
-        "message": format!("Imported {} successfully", form.repo_name)
-    }))
+    let since = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok());
+    let modified = last_modified.and_then(|v| DateTime::parse_from_rfc2822(v).ok());
+    matches!((since, modified), (Some(since), Some(modified)) if modified <= since)
 }
 
-// Sync a specific linked repo
-async fn admin_sync_repo(
-    headers: HeaderMap,
-    State(state): State<AdminState>,
-    Path(slug): Path<String>,
-) -> impl IntoResponse {
-    if !is_authenticated(&headers, &state).await {
-        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+// 304 carrying the validators and caching headers of the full response
+fn not_modified(headers: &HeaderMap) -> Response {
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    for name in [header::ETAG, header::LAST_MODIFIED, header::CACHE_CONTROL, header::VARY] {
+        if let Some(value) = headers.get(&name) {
+            response.headers_mut().insert(name, value.clone());
+        }
     }
-    
-    let link = {
-        let links = state.github_links.read().await;
-        links.get(&slug).cloned()
-    };
-    
-    let link = match link {
-        Some(l) => l,
-        None => return Json(serde_json::json!({"success": false, "error": "Not a GitHub linked post"})),
-    };
-    
-    // Fetch repo info
-    let repos = match fetch_github_repos().await {
-        Ok(r) => r,
-        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
-    };
-    
-    let repo = match repos.iter().find(|r| r.name == link.repo_name) {
-        Some(r) => r,
-        None => return Json(serde_json::json!({"success": false, "error": "Repo not found"})),
-    };
-    
-    // Fetch README
-    let readme_content = match fetch_readme(&link.repo_name).await {
-        Ok(c) => c,
-        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
-    };
-    
-    // Update post
-    let post_content = create_post_from_readme(repo, &readme_content);
-    let file_path = format!("content/{}.md", slug);
-    
-    if let Err(e) = fs::write(&file_path, &post_content) {
-        return Json(serde_json::json!({"success": false, "error": e.to_string()}));
+    response
+}
+
+// For handlers with expensive bodies: answer 304 before doing the work when the validators match
+fn check_not_modified(
+    request_headers: &HeaderMap,
+    etag: &str,
+    last_modified: Option<DateTime<Local>>,
+    cache_control: &str,
+) -> Option<Response> {
+    let last_modified = last_modified.map(http_date);
+    if !is_not_modified(request_headers, Some(etag), last_modified.as_deref()) {
+        return None;
     }
-    
-    // Update sync time
-    {
-        let mut links = state.github_links.write().await;
-        if let Some(link) = links.get_mut(&slug) {
-            link.last_synced = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ETAG, HeaderValue::from_str(etag).ok()?);
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_str(cache_control).ok()?);
+    if let Some(last_modified) = last_modified {
+        headers.insert(header::LAST_MODIFIED, HeaderValue::from_str(&last_modified).ok()?);
+    }
+    Some(not_modified(&headers))
+}
+
+// Validator layer for pages and feeds: the ETag comes from content_etag, so a matching request gets
+// its 304 before the handler renders anything. `cache_control` is what the routes send with a 200
+async fn content_validators(
+    State(cache_control): State<Option<&'static str>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return next.run(request).await;
+    }
+    let etag = content_etag(&request.uri().to_string());
+    if is_not_modified(request.headers(), Some(&etag), None) {
+        let mut headers = HeaderMap::new();
+        if let Ok(value) = HeaderValue::from_str(&etag) {
+            headers.insert(header::ETAG, value);
         }
-        state.save_github_links(&links);
+        if let Some(cache_control) = cache_control {
+            headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(cache_control));
+        }
+        return not_modified(&headers);
     }
-    
-    Json(serde_json::json!({"success": true, "message": "Synced successfully"}))
+
+    let request_headers = request.headers().clone();
+    let mut response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response.headers_mut().entry(header::ETAG).or_insert(value);
+    }
+    // If-Modified-Since on its own can only be answered from the handler's Last-Modified
+    let header_str = |name| response.headers().get(name).and_then(|v: &HeaderValue| v.to_str().ok());
+    if is_not_modified(&request_headers, header_str(header::ETAG), header_str(header::LAST_MODIFIED)) {
+        return not_modified(response.headers());
+    }
+    response
 }
 
-// GitHub webhook handler for auto-sync
-async fn github_webhook(
-    State(state): State<AdminState>,
-    Json(payload): Json<WebhookPayload>,
-) -> impl IntoResponse {
-    // Check if this is a push to main/master
-    let is_main_push = payload.git_ref
-        .as_ref()
-        .map(|r| r == "refs/heads/main" || r == "refs/heads/master")
-        .unwrap_or(false);
-    
-    if !is_main_push {
-        return Json(serde_json::json!({"success": true, "message": "Ignoring non-main branch push"}));
+// Whether a response is small text worth buffering to hash for an ETag
+fn is_hashable(response: &Response) -> bool {
+    use axum::body::HttpBody;
+    let text = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|mime| mime.starts_with("text/") || mime.starts_with("application/") && (mime.contains("json") || mime.contains("xml")));
+    text && response.body().size_hint().exact().is_some_and(|len| len <= MAX_HASHED_BODY)
+}
+
+// Response validator layer for everything else: successful public GETs get an ETag (a hash of the
+// body, for small text the handler didn't set one for) and a 304 when the client already has that version
+async fn conditional_get(request: axum::extract::Request, next: axum::middleware::Next) -> Response {
+    let path = request.uri().path();
+    let conditional = matches!(*request.method(), Method::GET | Method::HEAD)
+        && !path.starts_with("/admin")
+        && !path.starts_with("/api/github");
+    let request_headers = request.headers().clone();
+    let response = next.run(request).await;
+    if !conditional || response.status() != StatusCode::OK {
+        return response;
     }
-    
-    let repo_name = match payload.repository {
-        Some(r) => r.name,
-        None => return Json(serde_json::json!({"success": false, "error": "No repository in payload"})),
-    };
-    
-    // Find linked post with auto_sync enabled
-    let slug = {
-        let links = state.github_links.read().await;
-        links.iter()
-            .find(|(_, link)| link.repo_name == repo_name && link.auto_sync)
-            .map(|(slug, _)| slug.clone())
-    };
-    
-    let slug = match slug {
-        Some(s) => s,
-        None => return Json(serde_json::json!({"success": true, "message": "No auto-sync configured for this repo"})),
-    };
-    
-    // Fetch and update
-    let repos = match fetch_github_repos().await {
-        Ok(r) => r,
-        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
-    };
-    
-    let repo = match repos.iter().find(|r| r.name == repo_name) {
-        Some(r) => r,
-        None => return Json(serde_json::json!({"success": false, "error": "Repo not found"})),
-    };
-    
-    let readme_content = match fetch_readme(&repo_name).await {
-        Ok(c) => c,
-        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
+
+    let hash = !response.headers().contains_key(header::ETAG) && is_hashable(&response);
+    let (mut parts, body) = response.into_parts();
+    let body = if hash {
+        let Ok(bytes) = axum::body::to_bytes(body, MAX_HASHED_BODY as usize).await else {
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        };
+        let etag = format!("\"{}\"", &format!("{:x}", Sha256::digest(&bytes))[..32]);
+        parts.headers.insert(header::ETAG, HeaderValue::from_str(&etag).unwrap());
+        axum::body::Body::from(bytes)
+    } else {
+        body
     };
+
+    let header_str = |name| parts.headers.get(name).and_then(|v: &HeaderValue| v.to_str().ok());
+    if is_not_modified(&request_headers, header_str(header::ETAG), header_str(header::LAST_MODIFIED)) {
+        return not_modified(&parts.headers);
+    }
+    Response::from_parts(parts, body)
+}
+
+// Set Last-Modified on a response built from posts
+fn with_last_modified(mut response: Response, last_modified: Option<DateTime<Local>>) -> Response {
+    if let Some(value) = last_modified.and_then(|time| HeaderValue::from_str(&http_date(time)).ok()) {
+        response.headers_mut().insert(header::LAST_MODIFIED, value);
+    }
+    response
+}
+
+// Home route handler
+async fn index(Extension(hb): Extension<Arc<Handlebars<'_>>>) -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    let posts_count = posts.len();
     
-    let post_content = create_post_from_readme(repo, &readme_content);
-    let file_path = format!("content/{}.md", slug);
+    let mut data = HashMap::new();
+    data.insert("posts", serde_json::to_value(&posts).unwrap());
+    data.insert("posts_count", serde_json::to_value(posts_count).unwrap());
+    data.insert("site", serde_json::to_value(&site_config).unwrap());
+    data.insert("current_year", serde_json::to_value(Local::now().format("%Y").to_string()).unwrap());
+    let last_modified = newest_update(&posts);
+    let updated = last_modified.unwrap_or_else(Local::now);
+    data.insert("current_date_iso", serde_json::to_value(updated.format("%Y-%m-%dT%H:%M:%S%:z").to_string()).unwrap());
     
-    if let Err(e) = fs::write(&file_path, &post_content) {
-        return Json(serde_json::json!({"success": false, "error": e.to_string()}));
+    match hb.render("index.html", &data) {
+        Ok(rendered) => with_last_modified(Html(rendered).into_response(), last_modified),
+        Err(e) => {
+            eprintln!("Failed to render index template: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Template rendering error").into_response()
+        }
     }
-    
-    // Update sync time
-    {
-        let mut links = state.github_links.write().await;
-        if let Some(link) = links.get_mut(&slug) {
-            link.last_synced = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+}
+
+// Tag listing route handler
+async fn tag_page(
+    Extension(hb): Extension<Arc<Handlebars<'_>>>,
+    Path(tag): Path<String>,
+) -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let all_posts = get_posts(&site_config);
+    let tag_normalized = tag.to_lowercase();
+
+    let filtered_posts: Vec<Post> = all_posts
+        .into_iter()
+        .filter(|p| p
+            .tags
+            .iter()
+            .any(|t| t.to_lowercase() == tag_normalized))
+        .collect();
+
+    let mut data = HashMap::new();
+    data.insert("posts", serde_json::to_value(&filtered_posts).unwrap());
+    data.insert("posts_count", serde_json::to_value(filtered_posts.len()).unwrap());
+    // Reuse the index template; tweak description to show the tag context
+    let mut site_override = site_config.clone();
+    site_override.description = format!("Posts tagged with '{}'.", tag);
+    data.insert("site", serde_json::to_value(&site_override).unwrap());
+    data.insert("current_year", serde_json::to_value(Local::now().format("%Y").to_string()).unwrap());
+    data.insert("tag", serde_json::to_value(&tag).unwrap());
+    // The tag as a URL path segment, for the tag feed links
+    data.insert("tag_path", serde_json::to_value(url_segment(&tag)).unwrap());
+
+    match hb.render("index.html", &data) {
+        Ok(rendered) => with_last_modified(Html(rendered).into_response(), newest_update(&filtered_posts)),
+        Err(e) => {
+            eprintln!("Failed to render tag template: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Template rendering error").into_response()
+        }
+    }
+}
+
+// Single post route handler
+async fn single_post(
+    Extension(hb): Extension<Arc<Handlebars<'_>>>,
+    Path(post_title): Path<String>,
+) -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    let post = posts.into_iter().find(|p| p.slug == post_title);
+
+    if let Some(post) = post {
+        let mut data = HashMap::new();
+        data.insert("post", serde_json::to_value(&post).unwrap());
+        data.insert("site", serde_json::to_value(&site_config).unwrap());
+        data.insert("current_year", serde_json::to_value(Local::now().format("%Y").to_string()).unwrap());
+        
+        match hb.render("single.html", &data) {
+            Ok(rendered) => with_last_modified(Html(rendered).into_response(), Some(post.updated)),
+            Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Template rendering error").into_response(),
+        }
+    } else {
+        (StatusCode::NOT_FOUND, "Post not found").into_response()
+    }
+}
+
+// Generate Web App Manifest for PWA support and better SEO
+async fn manifest_json() -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let manifest = format!(
+        r##"{{
+  "name": "{name}",
+  "short_name": "{short_name}",
+  "description": "{description}",
+  "start_url": "/",
+  "display": "standalone",
+  "background_color": "#000000",
+  "theme_color": "{theme_color}",
+  "orientation": "portrait-primary",
+  "scope": "/",
+  "lang": "{language}",
+  "dir": "ltr",
+  "categories": ["education", "technology", "programming"],
+  "icons": [
+    {{
+      "src": "/logo.webp",
+      "sizes": "32x32",
+      "type": "image/webp",
+      "purpose": "any"
+    }},
+    {{
+      "src": "/logo.webp",
+      "sizes": "192x192",
+      "type": "image/webp",
+      "purpose": "any maskable"
+    }},
+    {{
+      "src": "/logo.webp",
+      "sizes": "512x512",
+      "type": "image/webp",
+      "purpose": "any maskable"
+    }}
+  ],
+  "prefer_related_applications": false,
+  "shortcuts": [
+    {{
+      "name": "All Posts",
+      "short_name": "Posts",
+      "description": "View all blog posts",
+      "url": "/",
+      "icons": [{{ "src": "/logo.webp", "sizes": "96x96", "type": "image/webp" }}]
+    }}
+  ],
+  "related_applications": [],
+  "id": "{url}/"
+}}"##,
+        name = site_config.title,
+        short_name = site_config.author,
+        description = site_config.description,
+        theme_color = site_config.theme_color,
+        language = site_config.language,
+        url = site_config.url
+    );
+    
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/manifest+json; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=86400")
+        .body(manifest)
+        .unwrap()
+}
+
+// Generate browserconfig.xml for Windows tiles
+async fn browserconfig_xml() -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<browserconfig>
+  <msapplication>
+    <tile>
+      <square70x70logo src="/logo.webp"/>
+      <square150x150logo src="/logo.webp"/>
+      <square310x310logo src="/logo.webp"/>
+      <TileColor>{}</TileColor>
+    </tile>
+  </msapplication>
+</browserconfig>"#,
+        site_config.theme_color
+    );
+    
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=86400")
+        .body(xml)
+        .unwrap()
+}
+
+// Generate humans.txt for attribution
+async fn humans_txt() -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let content = format!(
+        r#"/* TEAM */
+Author: {}
+Site: {}
+Twitter: {}
+GitHub: https://github.com/{}
+LinkedIn: https://linkedin.com/in/{}
+Location: India
+
+/* SITE */
+Last update: {}
+Language: {}
+Doctype: HTML5
+IDE: VS Code
+Standards: HTML5, CSS3, ES6+
+
+/* TECHNOLOGY */
+Backend: Rust, Axum
+Frontend: HTML5, CSS3, JavaScript
+Template: Handlebars
+Hosting: Leapcell
+
+/* THANKS */
+Rust Community
+Axum Framework
+Open Source Contributors
+"#,
+        site_config.author_full_name,
+        site_config.url,
+        site_config.twitter_handle,
+        site_config.github_handle,
+        site_config.linkedin_handle,
+        Local::now().format("%Y-%m-%d"),
+        site_config.language
+    );
+    
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=86400")
+        .body(content)
+        .unwrap()
+}
+
+// Generate security.txt for security researchers
+async fn security_txt() -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let content = format!(
+        r#"# Security Policy
+Contact: https://github.com/{}
+Expires: 2027-12-31T23:59:59.000Z
+Preferred-Languages: en
+Canonical: {}/.well-known/security.txt
+"#,
+        site_config.github_handle,
+        site_config.url
+    );
+    
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=86400")
+        .body(content)
+        .unwrap()
+}
+
+// Serve logo.webp as favicon
+async fn serve_logo() -> impl IntoResponse {
+    match fs::read("logo.webp") {
+        Ok(data) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "image/webp")
+            .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
+            .body(axum::body::Body::from(data))
+            .unwrap(),
+        Err(_) => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(axum::body::Body::from("Logo not found"))
+            .unwrap(),
+    }
+}
+
+// Content type for a static file, by extension
+fn mime_for_path(path: &std::path::Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        Some("pdf") => "application/pdf",
+        Some("mp4") => "video/mp4",
+        Some("m4v") => "video/x-m4v",
+        Some("mov") => "video/quicktime",
+        Some("webm") => "video/webm",
+        Some("mp3") => "audio/mpeg",
+        Some("m4a") => "audio/mp4",
+        Some("aac") => "audio/aac",
+        Some("ogg") | Some("oga") => "audio/ogg",
+        Some("opus") => "audio/opus",
+        Some("wav") => "audio/wav",
+        Some("flac") => "audio/flac",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("csv") => "text/csv; charset=utf-8",
+        Some("json") => "application/json",
+        Some("zip") => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+// The single range a `Range: bytes=` header asks for, clamped to the file. None serves the whole
+// file (no Range header, several ranges, or an If-Range that no longer matches); Some(None) is unsatisfiable
+fn byte_range(headers: &HeaderMap, len: u64, etag: &str) -> Option<Option<(u64, u64)>> {
+    let range = headers.get(header::RANGE)?.to_str().ok()?.trim().strip_prefix("bytes=")?;
+    if range.contains(',') {
+        return None;
+    }
+    if let Some(if_range) = headers.get(header::IF_RANGE) {
+        if if_range.to_str().ok()?.trim() != etag {
+            return None;
+        }
+    }
+    let (start, end) = range.split_once('-')?;
+    let last = len.saturating_sub(1);
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => (len.saturating_sub(suffix.parse().ok()?), last),
+        (start, "") => (start.parse().ok()?, last),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(last)),
+    };
+    if len == 0 || start > end || start >= len {
+        return Some(None);
+    }
+    Some(Some((start, end)))
+}
+
+// A static file with the headers in `response` (type, ETag, caching, policy), streamed from disk.
+// A single byte range gets 206 Partial Content with only that slice read, so audio and video players can seek
+async fn file_response(
+    request_headers: &HeaderMap,
+    file: &std::path::Path,
+    etag: &str,
+    response: axum::http::response::Builder,
+) -> std::io::Result<Response> {
+    let mut handle = tokio::fs::File::open(file).await?;
+    let len = handle.metadata().await?.len();
+    let response = response.header(header::ETAG, etag).header(header::ACCEPT_RANGES, "bytes");
+    let (start, end) = match byte_range(request_headers, len, etag) {
+        None => {
+            let body = axum::body::Body::from_stream(ReaderStream::new(handle));
+            return Ok(response.status(StatusCode::OK).header(header::CONTENT_LENGTH, len).body(body).unwrap());
+        }
+        Some(None) => {
+            return Ok(Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                .body(axum::body::Body::empty())
+                .unwrap())
+        }
+        Some(Some(range)) => range,
+    };
+    handle.seek(std::io::SeekFrom::Start(start)).await?;
+    let body = axum::body::Body::from_stream(ReaderStream::new(handle.take(end - start + 1)));
+    Ok(response
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+        .header(header::CONTENT_LENGTH, end - start + 1)
+        .body(body)
+        .unwrap())
+}
+
+// Serve original images from content/images
+async fn serve_image(headers: HeaderMap, Path(path): Path<String>) -> impl IntoResponse {
+    let Some(file) = resolve_local_image(&format!("/images/{}", path)) else {
+        return (StatusCode::NOT_FOUND, "Image not found").into_response();
+    };
+    // Validated from the file's metadata, so a 304 never reads the file
+    let Some(etag) = file_etag(&file) else {
+        return (StatusCode::NOT_FOUND, "Image not found").into_response();
+    };
+    if let Some(response) = check_not_modified(&headers, &etag, None, "public, max-age=86400") {
+        return response;
+    }
+    let response = Response::builder()
+        .header(header::CONTENT_TYPE, mime_for_path(&file))
+        .header(header::CACHE_CONTROL, "public, max-age=86400")
+        // Mirrored SVGs are third-party; never let one run script if opened directly
+        .header(header::CONTENT_SECURITY_POLICY, "default-src 'none'; style-src 'unsafe-inline'; sandbox");
+    file_response(&headers, &file, &etag, response)
+        .await
+        .unwrap_or_else(|_| (StatusCode::NOT_FOUND, "Image not found").into_response())
+}
+
+// ETag for a static file from its path, size and modification time
+fn file_etag(path: &std::path::Path) -> Option<String> {
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta.modified().ok()?.duration_since(SystemTime::UNIX_EPOCH).ok()?.as_nanos();
+    Some(etag_for(&[&path.to_string_lossy(), &meta.len().to_string(), &modified.to_string()]))
+}
+
+// The file a bundle asset URL names inside the bundle directory `dir`, if it's one that may be served
+fn bundle_file(dir: &std::path::Path, asset: &str) -> Option<PathBuf> {
+    let relative = PathBuf::from(asset);
+    // The post source itself is not an asset, and neither is the directory
+    if relative.as_os_str().is_empty()
+        || relative.components().any(|c| !matches!(c, Component::Normal(_)))
+        || relative == std::path::Path::new("index.md")
+    {
+        return None;
+    }
+    Some(dir.join(relative))
+}
+
+// Serve a file from a page bundle: /blog/{slug}/{asset}
+async fn serve_bundle_asset(
+    headers: HeaderMap,
+    Path((slug, asset)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let Some(file) = find_bundle_dir(&slug).and_then(|dir| bundle_file(&dir, &asset)) else {
+        return (StatusCode::NOT_FOUND, "File not found").into_response();
+    };
+    // Validated from the file's metadata, like serve_image, so neither a 304 nor a range reads it all
+    let Some(etag) = file_etag(&file) else {
+        return (StatusCode::NOT_FOUND, "File not found").into_response();
+    };
+    if let Some(response) = check_not_modified(&headers, &etag, None, "public, max-age=86400") {
+        return response;
+    }
+    let response = Response::builder()
+        .header(header::CONTENT_TYPE, mime_for_path(&file))
+        .header(header::CACHE_CONTROL, "public, max-age=86400");
+    file_response(&headers, &file, &etag, response)
+        .await
+        .unwrap_or_else(|_| (StatusCode::NOT_FOUND, "File not found").into_response())
+}
+
+// Serve a resized WebP variant: /media/{source-hash}-{width}.webp
+async fn serve_image_variant(Path(file): Path<String>) -> impl IntoResponse {
+    let parsed = file
+        .strip_suffix(".webp")
+        .and_then(|stem| stem.rsplit_once('-'))
+        .and_then(|(hash, width)| Some((hash.to_string(), width.parse::<u32>().ok()?)));
+    let (hash, width) = match parsed {
+        Some(p) => p,
+        None => return (StatusCode::NOT_FOUND, "Image not found").into_response(),
+    };
+
+    // Only hashes rendering has recorded are served, and the file must still have that hash
+    let Some(source) = IMAGE_SOURCES.lock().unwrap().get(&hash).cloned() else {
+        return (StatusCode::NOT_FOUND, "Image not found").into_response();
+    };
+    let variant = tokio::task::spawn_blocking(move || {
+        let image = local_image_info(&source).filter(|img| img.hash == hash && variant_widths(img).contains(&width))?;
+        Some(image_variant(&image, width))
+    })
+    .await;
+
+    match variant {
+        Ok(None) => (StatusCode::NOT_FOUND, "Image not found").into_response(),
+        Ok(Some(Ok(data))) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "image/webp")
+            .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
+            .body(axum::body::Body::from(data))
+            .unwrap(),
+        Ok(Some(Err(e))) => {
+            eprintln!("Failed to generate image variant: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to generate image").into_response()
+        }
+        Err(e) => {
+            eprintln!("Image variant task failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to generate image").into_response()
+        }
+    }
+}
+
+// Generate dynamic OG image for blog posts
+async fn og_image(headers: HeaderMap, Path(slug): Path<String>) -> impl IntoResponse {
+    // Validated from the post files and the drawing code, so a 304 renders no posts
+    let etag = etag_for(&[OG_IMAGE_VERSION, &content_etag(&format!("/blog/{}/og.png", slug))]);
+    let last_modified = post_sources().into_iter().find(|source| source.slug == slug).map(|source| source.updated);
+    let cache_control = "public, max-age=31536000, immutable";
+    if let Some(response) = check_not_modified(&headers, &etag, last_modified, cache_control) {
+        return response;
+    }
+
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    
+    // Find the post by slug
+    let post = posts.iter().find(|p| p.slug == slug);
+    
+    let (title, author, date, reading_time, tags) = match post {
+        Some(p) => (
+            p.title.clone(),
+            p.author.clone(),
+            p.date.clone(),
+            format!("{} min read", p.reading_time),
+            p.tags.join(" · "),
+        ),
+        None => (
+            "Post Not Found".to_string(),
+            site_config.author_full_name.clone(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+        ),
+    };
+
+    // Generate the OG image
+    match generate_og_image(&title, &author, &date, &reading_time, &tags, &site_config) {
+        Ok(png_data) => with_last_modified(
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "image/png")
+                .header(header::CACHE_CONTROL, cache_control)
+                .header(header::ETAG, etag)
+                .body(axum::body::Body::from(png_data))
+                .unwrap(),
+            last_modified,
+        ),
+        Err(e) => {
+            eprintln!("Failed to generate OG image: {}", e);
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(axum::body::Body::from("Failed to generate image"))
+                .unwrap()
+        }
+    }
+}
+
+// Generate default OG image for homepage
+async fn og_image_default(headers: HeaderMap) -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+
+    let etag = etag_for(&[OG_IMAGE_VERSION, &serde_json::to_string(&site_config).unwrap_or_default()]);
+    let cache_control = "public, max-age=31536000, immutable";
+    if let Some(response) = check_not_modified(&headers, &etag, None, cache_control) {
+        return response;
+    }
+    
+    match generate_og_image_home(&site_config) {
+        Ok(png_data) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "image/png")
+            .header(header::CACHE_CONTROL, cache_control)
+            .header(header::ETAG, etag)
+            .body(axum::body::Body::from(png_data))
+            .unwrap(),
+        Err(e) => {
+            eprintln!("Failed to generate OG image: {}", e);
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(axum::body::Body::from("Failed to generate image"))
+                .unwrap()
+        }
+    }
+}
+
+// Redirect /blog to home page
+async fn blog_redirect() -> impl IntoResponse {
+    Redirect::permanent("/")
+}
+
+// Generate recent posts OG image for /blog/recents.png
+async fn og_image_recents(headers: HeaderMap) -> impl IntoResponse {
+    // Validated like og_image, before any post is rendered
+    let etag = etag_for(&[OG_IMAGE_VERSION, &content_etag("/blog/recents.png")]);
+    let last_modified = post_sources().into_iter().map(|source| source.updated).max();
+    let cache_control = "public, max-age=3600"; // Cache for 1 hour since it updates
+    if let Some(response) = check_not_modified(&headers, &etag, last_modified, cache_control) {
+        return response;
+    }
+
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    
+    match generate_og_image_recents(&posts, &site_config) {
+        Ok(png_data) => with_last_modified(
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "image/png")
+                .header(header::CACHE_CONTROL, cache_control)
+                .header(header::ETAG, etag)
+                .body(axum::body::Body::from(png_data))
+                .unwrap(),
+            last_modified,
+        ),
+        Err(e) => {
+            eprintln!("Failed to generate recents OG image: {}", e);
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(axum::body::Body::from("Failed to generate image"))
+                .unwrap()
+        }
+    }
+}
+fn generate_og_image(
+    title: &str,
+    _author: &str,
+    date: &str,
+    reading_time: &str,
+    tags: &str,
+    _site_config: &SiteConfig,
+) -> Result<Vec<u8>, String> {
+    // Embed logo as base64
+    static LOGO_WEBP: &[u8] = include_bytes!("../logo-small.webp");
+    let logo_base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LOGO_WEBP);
+    
+    // Calculate how many lines we need based on title length
+    let title_char_count = title.len();
+    
+    // Dynamic sizing based on title length - allow up to 5 lines for very long titles
+    let (max_lines, mut title_font_size, title_y_start) = if title_char_count <= 40 {
+        // Short title - 1-2 lines, large font
+        (2, 72, 180)
+    } else if title_char_count <= 80 {
+        // Medium title - 2-3 lines
+        (3, 60, 150)
+    } else if title_char_count <= 120 {
+        // Long title - 3-4 lines
+        (4, 52, 120)
+    } else {
+        // Very long title - 4-5 lines, smaller font
+        (5, 44, 100)
+    };
+
+    let title_x = 100;
+    let title_max_width_px = (1200 - title_x - 100) as f32; // keep 100px right padding to avoid clipping
+
+    // Wrap based on an estimated pixel width (more reliable than char-count for large fonts).
+    // If it still doesn't fit within max_lines, step the font size down until it does.
+    let mut title_lines = wrap_text_to_width(title, title_max_width_px, title_font_size);
+    while title_lines.len() > max_lines && title_font_size > 32 {
+        title_font_size -= 4;
+        title_lines = wrap_text_to_width(title, title_max_width_px, title_font_size);
+    }
+
+    let line_height = ((title_font_size as f32) * 1.18).round() as i32;
+    let title_line_count = title_lines.len().min(max_lines);
+    
+    // Build title SVG elements with gradient text
+    let mut title_svg = String::new();
+    for (i, line) in title_lines.iter().take(max_lines).enumerate() {
+        let y = title_y_start + (i as i32 * line_height);
+        let escaped_line = html_escape::encode_text(line);
+        title_svg.push_str(&format!(
+            r##"<text x="100" y="{}" font-family="Geist" font-size="{}" font-weight="700" fill="url(#textGradient)">{}</text>"##,
+            y, title_font_size, escaped_line
+        ));
+    }
+    
+    // Meta info Y position - adjust based on actual lines used
+    let meta_y = title_y_start + (title_line_count as i32 * line_height) + 50;
+    
+    let escaped_date = html_escape::encode_text(date);
+    let escaped_reading = html_escape::encode_text(reading_time);
+    let escaped_tags = html_escape::encode_text(tags);
+    
+    let svg = format!(
+        r##"<svg width="1200" height="630" viewBox="0 0 1200 630" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+  <defs>
+    <!-- Grid pattern -->
+    <pattern id="grid" width="40" height="40" patternUnits="userSpaceOnUse">
+      <path d="M 40 0 L 0 0 0 40" fill="none" stroke="#1a1a1a" stroke-width="1"/>
+    </pattern>
+    <!-- Text gradient - white to gray like Glimpse -->
+    <linearGradient id="textGradient" x1="0%" y1="0%" x2="100%" y2="100%">
+      <stop offset="0%" style="stop-color:#ffffff"/>
+      <stop offset="50%" style="stop-color:#b0b0b0"/>
+      <stop offset="100%" style="stop-color:#606060"/>
+    </linearGradient>
+  </defs>
+  
+  <!-- Pure black background -->
+  <rect width="1200" height="630" fill="#000000"/>
+  <!-- Grid overlay -->
+  <rect width="1200" height="630" fill="url(#grid)"/>
+  
+  <!-- Title with gradient -->
+  {}
+  
+  <!-- Meta info line - date and reading time only -->
+  <text x="100" y="{}" font-family="Geist" font-size="24" font-weight="500" fill="#777777">{} · {}</text>
+  
+  <!-- Tags -->
+  <text x="100" y="{}" font-family="Geist" font-size="20" font-weight="400" fill="#555555">{}</text>
+  
+  <!-- Author at bottom left -->
+  <text x="100" y="560" font-family="Geist" font-size="22" font-weight="500" fill="#555555">By </text>
+  <text x="135" y="560" font-family="Geist" font-size="22" font-weight="600" fill="#c0c0c0">Aryan S Rao</text>
+  
+  <!-- Logo at bottom right - equal distance from bottom and right (50px) -->
+  <image x="1070" y="500" width="80" height="80" xlink:href="data:image/webp;base64,{}"/>
+</svg>"##,
+        title_svg,
+        meta_y,
+        escaped_date,
+        escaped_reading,
+        meta_y + 40,
+        escaped_tags,
+        logo_base64,
+    );
+    
+    svg_to_png(&svg)
+}
+
+fn generate_og_image_home(site_config: &SiteConfig) -> Result<Vec<u8>, String> {
+    // Embed logo as base64
+    static LOGO_WEBP: &[u8] = include_bytes!("../logo-small.webp");
+    let logo_base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LOGO_WEBP);
+    
+    let escaped_title = html_escape::encode_text(&site_config.title);
+    let escaped_tagline = html_escape::encode_text(&site_config.tagline);
+    let escaped_author = html_escape::encode_text(&site_config.author_full_name);
+    
+    let svg = format!(
+        r##"<svg width="1200" height="630" viewBox="0 0 1200 630" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+  <defs>
+    <!-- Background gradient - pure black to dark gray -->
+    <linearGradient id="bgGradient" x1="0%" y1="0%" x2="100%" y2="100%">
+      <stop offset="0%" style="stop-color:#000000"/>
+      <stop offset="50%" style="stop-color:#080808"/>
+      <stop offset="100%" style="stop-color:#0f0f0f"/>
+    </linearGradient>
+    <!-- Text gradient - white to gray like Glimpse -->
+    <linearGradient id="titleGradient" x1="0%" y1="0%" x2="100%" y2="100%">
+      <stop offset="0%" style="stop-color:#ffffff"/>
+      <stop offset="40%" style="stop-color:#c0c0c0"/>
+      <stop offset="100%" style="stop-color:#707070"/>
+    </linearGradient>
+  </defs>
+  
+  <!-- Pure black background with gradient -->
+  <rect width="1200" height="630" fill="url(#bgGradient)"/>
+  
+  <!-- Logo - top left -->
+  <image x="60" y="50" width="100" height="100" xlink:href="data:image/webp;base64,{}"/>
+  
+  <!-- Main title - centered, HUGE with gradient -->
+  <text x="600" y="320" font-family="Geist" font-size="110" font-weight="700" fill="url(#titleGradient)" text-anchor="middle">{}</text>
+  
+  <!-- Tagline - centered -->
+  <text x="600" y="400" font-family="Geist" font-size="28" font-weight="500" fill="#777777" text-anchor="middle">{}</text>
+  
+  <!-- Tech stack -->
+  <text x="600" y="455" font-family="Geist" font-size="20" font-weight="400" fill="#555555" text-anchor="middle">Rust · Web Development · Open Source</text>
+  
+  <!-- Author at bottom - centered -->
+  <text x="600" y="560" font-family="Geist" font-size="22" font-weight="500" fill="#666666" text-anchor="middle">{}</text>
+</svg>"##,
+        logo_base64,
+        escaped_title,
+        escaped_tagline,
+        escaped_author,
+    );
+    
+    svg_to_png(&svg)
+}
+
+fn estimate_text_width_px(text: &str, font_size_px: i32) -> f32 {
+    let mut units = 0.0f32;
+    for ch in text.chars() {
+        units += match ch {
+            ' ' => 0.28,
+            '-' | '–' | '—' | '·' | '|' => 0.35,
+            '.' | ',' | ':' | ';' | '!' | '?' => 0.28,
+            'A'..='Z' => 0.62,
+            'a'..='z' => 0.52,
+            '0'..='9' => 0.55,
+            _ => 0.60,
+        };
+    }
+    units * (font_size_px as f32)
+}
+
+fn hard_wrap_word_to_width(word: &str, max_width_px: f32, font_size_px: i32) -> Vec<String> {
+    // Worst-case fallback for a single unbreakable word that's wider than the allowed width.
+    let mut parts: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for ch in word.chars() {
+        let mut next = current.clone();
+        next.push(ch);
+        if !current.is_empty() && estimate_text_width_px(&next, font_size_px) > max_width_px {
+            parts.push(current);
+            current = ch.to_string();
+        } else {
+            current = next;
+        }
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    parts
+}
+
+fn wrap_text_to_width(text: &str, max_width_px: f32, font_size_px: i32) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in words {
+        // Keep a standalone dash attached to the previous word if possible.
+        if word == "-" && !current.is_empty() {
+            let candidate = format!("{} -", current);
+            if estimate_text_width_px(&candidate, font_size_px) <= max_width_px {
+                current = candidate;
+                continue;
+            }
+        }
+
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+
+        if estimate_text_width_px(&candidate, font_size_px) <= max_width_px {
+            current = candidate;
+            continue;
+        }
+
+        if current.is_empty() {
+            // A single word is too wide; hard-wrap it.
+            let wrapped = hard_wrap_word_to_width(word, max_width_px, font_size_px);
+            lines.extend(wrapped);
+        } else {
+            lines.push(current);
+            current = String::new();
+
+            // Re-try placing the word on a new line (or hard-wrap it).
+            if estimate_text_width_px(word, font_size_px) <= max_width_px {
+                current = word.to_string();
+            } else {
+                let wrapped = hard_wrap_word_to_width(word, max_width_px, font_size_px);
+                lines.extend(wrapped);
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+fn ellipsize_to_width(text: &str, max_width_px: f32, font_size_px: i32) -> String {
+    if estimate_text_width_px(text, font_size_px) <= max_width_px {
+        return text.to_string();
+    }
+
+    let ellipsis = "…";
+    let mut out = String::new();
+    for ch in text.chars() {
+        let mut candidate = out.clone();
+        candidate.push(ch);
+        let with_ellipsis = format!("{}{}", candidate, ellipsis);
+        if estimate_text_width_px(&with_ellipsis, font_size_px) > max_width_px {
+            break;
+        }
+        out = candidate;
+    }
+
+    if out.is_empty() {
+        ellipsis.to_string()
+    } else {
+        format!("{}{}", out, ellipsis)
+    }
+}
+
+fn generate_og_image_recents(posts: &[Post], _site_config: &SiteConfig) -> Result<Vec<u8>, String> {
+    // Embed logo as base64
+    static LOGO_WEBP: &[u8] = include_bytes!("../logo-small.webp");
+    let logo_base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LOGO_WEBP);
+    
+    // Take the latest post
+    let post = posts
+        .first()
+        .ok_or_else(|| "No posts available".to_string())?;
+    
+    // Wrap latest post title to fit in one line, truncate if needed
+    let title_font_size = 42;
+    let title_max_width_px = 880.0;
+    let title_lines = wrap_text_to_width(&post.title, title_max_width_px, title_font_size);
+    let mut title = title_lines.first().cloned().unwrap_or_default();
+    if title_lines.len() > 1 {
+        title = ellipsize_to_width(&title, title_max_width_px, title_font_size);
+    }
+    let title = html_escape::encode_text(&title).to_string();
+
+    let date_str = &post.date;
+    
+    let svg = format!(
+        r##"<svg width="1200" height="630" viewBox="0 0 1200 630" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+  <defs>
+    <!-- Text gradient -->
+    <linearGradient id="textGradient" x1="0%" y1="0%" x2="100%" y2="100%">
+      <stop offset="0%" style="stop-color:#ffffff"/>
+      <stop offset="50%" style="stop-color:#b0b0b0"/>
+      <stop offset="100%" style="stop-color:#606060"/>
+    </linearGradient>
+  </defs>
+  
+  <!-- Background -->
+  <rect width="1200" height="630" fill="#0a0a0a"/>
+  
+  <!-- Logo and BLOGS text centered together -->
+  <image x="220" y="120" width="100" height="100" xlink:href="data:image/webp;base64,{}"/>
+  <text x="350" y="230" font-family="Geist" font-size="160" font-weight="700" fill="url(#textGradient)">BLOGS</text>
+  
+  <!-- Divider line -->
+  <line x1="200" y1="315" x2="1000" y2="315" stroke="#1a1a1a" stroke-width="2"/>
+  
+  <!-- Latest Post section -->
+  <text x="160" y="385" font-family="Geist" font-size="20" font-weight="600" fill="#888888">LATEST POST</text>
+  
+  <!-- Post title (single line, centered) -->
+  <text x="600" y="465" font-family="Geist" font-size="42" font-weight="700" fill="#ffffff" text-anchor="middle">{}</text>
+  
+  <!-- Post date -->
+  <text x="600" y="515" font-family="Geist" font-size="20" font-weight="500" fill="#888888" text-anchor="middle">{}</text>
+</svg>"##,
+        logo_base64,
+        title,
+        date_str,
+    );
+    
+    svg_to_png(&svg)
+}
+
+fn svg_to_png(svg_str: &str) -> Result<Vec<u8>, String> {
+    use resvg::tiny_skia::Pixmap;
+    use resvg::usvg::{Options, Tree, fontdb};
+    
+    // Embed Geist fonts directly for deployment compatibility
+    static GEIST_MEDIUM: &[u8] = include_bytes!("../fonts/Geist-Medium.ttf");
+    static GEIST_SEMIBOLD: &[u8] = include_bytes!("../fonts/Geist-SemiBold.ttf");
+    static GEIST_BOLD: &[u8] = include_bytes!("../fonts/Geist-Bold.ttf");
+    
+    let mut fontdb = fontdb::Database::new();
+    fontdb.load_font_data(GEIST_MEDIUM.to_vec());
+    fontdb.load_font_data(GEIST_SEMIBOLD.to_vec());
+    fontdb.load_font_data(GEIST_BOLD.to_vec());
+    
+    let opt = Options {
+        fontdb: std::sync::Arc::new(fontdb),
+        ..Options::default()
+    };
+    
+    let tree = Tree::from_str(svg_str, &opt)
+        .map_err(|e| format!("Failed to parse SVG: {}", e))?;
+    
+    let size = tree.size();
+    let mut pixmap = Pixmap::new(size.width() as u32, size.height() as u32)
+        .ok_or("Failed to create pixmap")?;
+    
+    resvg::render(&tree, resvg::tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    
+    pixmap.encode_png()
+        .map_err(|e| format!("Failed to encode PNG: {}", e))
+}
+
+// ============================================================================
+// Feeds & Sitemaps - RSS 2.0, Atom 1.0 and sitemap documents over a streaming XML writer
+// ============================================================================
+
+type XmlWriter = quick_xml::Writer<Vec<u8>>;
+
+// A document that writes itself element by element; the writer does all escaping
+trait XmlDocument {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()>;
+
+    fn to_xml(&self) -> String {
+        let mut xml = quick_xml::Writer::new_with_indent(Vec::new(), b' ', 2);
+        xml.write_event(XmlEvent::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+            .and_then(|_| self.write(&mut xml))
+            .expect("writing XML to memory can't fail");
+        String::from_utf8(xml.into_inner()).expect("XML is written from UTF-8 strings")
+    }
+}
+
+// Drop characters XML 1.0 doesn't allow (most control characters), which escaping can't fix
+fn xml_chars(text: &str) -> String {
+    text.chars()
+        .filter(|&c| matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..))
+        .collect()
+}
+
+// <name>text</name>
+fn text_element(xml: &mut XmlWriter, name: &str, text: &str) -> std::io::Result<()> {
+    xml.create_element(name).write_text_content(BytesText::new(&xml_chars(text)))?;
+    Ok(())
+}
+
+// <name><![CDATA[text]]></name>, split into several sections around any "]]>"
+fn cdata_element(xml: &mut XmlWriter, name: &str, text: &str) -> std::io::Result<()> {
+    let text = xml_chars(text);
+    xml.create_element(name).write_inner_content(|xml| {
+        for section in BytesCData::escaped(&text) {
+            xml.write_event(XmlEvent::CData(section))?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+// Percent-encode a URL path segment, e.g. a tag with spaces
+fn url_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// RSS 2.0 (https://www.rssboard.org/rss-specification)
+struct RssChannel {
+    title: String,
+    link: String,
+    description: String,
+    language: String,
+    last_build_date: DateTime<Local>,
+    self_link: String,   // atom:link rel="self"
+    hub: Option<String>, // atom:link rel="hub", for WebSub
+    itunes: Option<ItunesChannel>,
+    items: Vec<RssItem>,
+}
+
+struct RssItem {
+    title: String,
+    link: String, // Also the guid
+    description: String,
+    content: Option<String>, // Full HTML, as content:encoded
+    creator: String,         // dc:creator; RSS <author> must be an email address
+    pub_date: DateTime<Local>,
+    categories: Vec<String>,
+    enclosure: Option<RssEnclosure>,
+    itunes: Option<ItunesItem>,
+}
+
+struct RssEnclosure {
+    url: String,
+    length: u64, // Bytes
+    mime: String,
+}
+
+// Apple Podcasts tags (https://podcasters.apple.com/support/823-podcast-requirements)
+struct ItunesChannel {
+    author: String,
+    summary: String,
+    image: Option<String>, // Square, 1400-3000 px; no generated image fits, so only PODCAST_IMAGE
+    category: String,
+    explicit: bool,
+    owner_name: String,
+    owner_email: Option<String>,
+}
+
+struct ItunesItem {
+    duration: Option<u64>, // Seconds
+    image: Option<String>,
+    explicit: bool,
+}
+
+impl XmlDocument for RssChannel {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        let mut rss = xml
+            .create_element("rss")
+            .with_attribute(("version", "2.0"))
+            .with_attribute(("xmlns:atom", "http://www.w3.org/2005/Atom"))
+            .with_attribute(("xmlns:content", "http://purl.org/rss/1.0/modules/content/"))
+            .with_attribute(("xmlns:dc", "http://purl.org/dc/elements/1.1/"));
+        if self.itunes.is_some() || self.items.iter().any(|item| item.itunes.is_some()) {
+            rss = rss.with_attribute(("xmlns:itunes", "http://www.itunes.com/dtds/podcast-1.0.dtd"));
+        }
+        rss.write_inner_content(|xml| {
+            xml.create_element("channel").write_inner_content(|xml| {
+                text_element(xml, "title", &self.title)?;
+                text_element(xml, "link", &self.link)?;
+                text_element(xml, "description", &self.description)?;
+                text_element(xml, "language", &self.language)?;
+                text_element(xml, "lastBuildDate", &self.last_build_date.to_rfc2822())?;
+                xml.create_element("atom:link")
+                    .with_attribute(("href", xml_chars(&self.self_link).as_str()))
+                    .with_attribute(("rel", "self"))
+                    .with_attribute(("type", "application/rss+xml"))
+                    .write_empty()?;
+                if let Some(hub) = &self.hub {
+                    xml.create_element("atom:link")
+                        .with_attribute(("href", xml_chars(hub).as_str()))
+                        .with_attribute(("rel", "hub"))
+                        .write_empty()?;
+                }
+                text_element(xml, "generator", "Axum Blog Engine")?;
+                if let Some(itunes) = &self.itunes {
+                    itunes.write(xml)?;
+                }
+                for item in &self.items {
+                    item.write(xml)?;
+                }
+                Ok(())
+            })?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+impl RssItem {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        xml.create_element("item").write_inner_content(|xml| {
+            text_element(xml, "title", &self.title)?;
+            text_element(xml, "link", &self.link)?;
+            xml.create_element("guid")
+                .with_attribute(("isPermaLink", "true"))
+                .write_text_content(BytesText::new(&xml_chars(&self.link)))?;
+            text_element(xml, "pubDate", &self.pub_date.to_rfc2822())?;
+            text_element(xml, "description", &self.description)?;
+            if let Some(content) = &self.content {
+                cdata_element(xml, "content:encoded", content)?;
+            }
+            text_element(xml, "dc:creator", &self.creator)?;
+            if let Some(enclosure) = &self.enclosure {
+                xml.create_element("enclosure")
+                    .with_attribute(("url", xml_chars(&enclosure.url).as_str()))
+                    .with_attribute(("length", enclosure.length.to_string().as_str()))
+                    .with_attribute(("type", xml_chars(&enclosure.mime).as_str()))
+                    .write_empty()?;
+            }
+            if let Some(itunes) = &self.itunes {
+                if let Some(duration) = itunes.duration {
+                    text_element(xml, "itunes:duration", &duration.to_string())?;
+                }
+                if let Some(image) = &itunes.image {
+                    xml.create_element("itunes:image")
+                        .with_attribute(("href", xml_chars(image).as_str()))
+                        .write_empty()?;
+                }
+                text_element(xml, "itunes:explicit", if itunes.explicit { "true" } else { "false" })?;
+                text_element(xml, "itunes:episodeType", "full")?;
+            }
+            for category in &self.categories {
+                text_element(xml, "category", category)?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+impl ItunesChannel {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        text_element(xml, "itunes:author", &self.author)?;
+        text_element(xml, "itunes:summary", &self.summary)?;
+        if let Some(image) = &self.image {
+            xml.create_element("itunes:image")
+                .with_attribute(("href", xml_chars(image).as_str()))
+                .write_empty()?;
+        }
+        xml.create_element("itunes:category")
+            .with_attribute(("text", xml_chars(&self.category).as_str()))
+            .write_empty()?;
+        text_element(xml, "itunes:explicit", if self.explicit { "true" } else { "false" })?;
+        xml.create_element("itunes:owner").write_inner_content(|xml| {
+            text_element(xml, "itunes:name", &self.owner_name)?;
+            if let Some(email) = &self.owner_email {
+                text_element(xml, "itunes:email", email)?;
+            }
+            Ok(())
+        })?;
+        text_element(xml, "itunes:type", "episodic")?;
+        Ok(())
+    }
+}
+
+// Atom 1.0 (RFC 4287)
+struct AtomFeed {
+    lang: String,
+    title: String,
+    subtitle: String,
+    id: String,
+    updated: DateTime<Local>,
+    links: Vec<AtomLink>,
+    author: AtomPerson,
+    icon: String,
+    logo: String,
+    rights: String,
+    entries: Vec<AtomEntry>,
+}
+
+struct AtomEntry {
+    title: String,
+    id: String,
+    published: DateTime<Local>,
+    updated: DateTime<Local>,
+    links: Vec<AtomLink>,
+    author: AtomPerson,
+    summary: String,
+    content: Option<String>, // HTML
+    categories: Vec<String>,
+}
+
+struct AtomLink {
+    href: String,
+    rel: &'static str,
+    mime: String,
+    title: Option<String>,
+    length: Option<u64>,
+}
+
+struct AtomPerson {
+    name: String,
+    uri: Option<String>,
+}
+
+impl XmlDocument for AtomFeed {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        xml.create_element("feed")
+            .with_attribute(("xmlns", "http://www.w3.org/2005/Atom"))
+            .with_attribute(("xml:lang", xml_chars(&self.lang).as_str()))
+            .write_inner_content(|xml| {
+                text_element(xml, "title", &self.title)?;
+                text_element(xml, "subtitle", &self.subtitle)?;
+                for link in &self.links {
+                    link.write(xml)?;
+                }
+                text_element(xml, "id", &self.id)?;
+                text_element(xml, "updated", &self.updated.to_rfc3339_opts(chrono::SecondsFormat::Secs, false))?;
+                self.author.write(xml)?;
+                xml.create_element("generator")
+                    .with_attribute(("uri", "https://github.com/aryansrao/aryansrao-blogs"))
+                    .write_text_content(BytesText::new("Axum Blog Engine"))?;
+                text_element(xml, "icon", &self.icon)?;
+                text_element(xml, "logo", &self.logo)?;
+                text_element(xml, "rights", &self.rights)?;
+                for entry in &self.entries {
+                    entry.write(xml)?;
+                }
+                Ok(())
+            })?;
+        Ok(())
+    }
+}
+
+impl AtomEntry {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        xml.create_element("entry").write_inner_content(|xml| {
+            text_element(xml, "title", &self.title)?;
+            for link in &self.links {
+                link.write(xml)?;
+            }
+            text_element(xml, "id", &self.id)?;
+            text_element(xml, "published", &self.published.to_rfc3339_opts(chrono::SecondsFormat::Secs, false))?;
+            text_element(xml, "updated", &self.updated.to_rfc3339_opts(chrono::SecondsFormat::Secs, false))?;
+            self.author.write(xml)?;
+            xml.create_element("summary")
+                .with_attribute(("type", "text"))
+                .write_text_content(BytesText::new(&xml_chars(&self.summary)))?;
+            if let Some(content) = &self.content {
+                xml.create_element("content")
+                    .with_attribute(("type", "html"))
+                    .write_text_content(BytesText::new(&xml_chars(content)))?;
+            }
+            for category in &self.categories {
+                xml.create_element("category")
+                    .with_attribute(("term", xml_chars(category).as_str()))
+                    .write_empty()?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+impl AtomLink {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        let length = self.length.map(|l| l.to_string());
+        let mut element = xml
+            .create_element("link")
+            .with_attribute(("href", xml_chars(&self.href).as_str()))
+            .with_attribute(("rel", self.rel));
+        if !self.mime.is_empty() {
+            element = element.with_attribute(("type", self.mime.as_str()));
+        }
+        if let Some(title) = &self.title {
+            element = element.with_attribute(("title", xml_chars(title).as_str()));
+        }
+        if let Some(length) = &length {
+            element = element.with_attribute(("length", length.as_str()));
+        }
+        element.write_empty()?;
+        Ok(())
+    }
+}
+
+impl AtomPerson {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        xml.create_element("author").write_inner_content(|xml| {
+            text_element(xml, "name", &self.name)?;
+            if let Some(uri) = &self.uri {
+                text_element(xml, "uri", uri)?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+// Sitemap protocol 0.9 (https://www.sitemaps.org/protocol.html) with Google's image and news extensions
+struct SitemapIndex {
+    sitemaps: Vec<SitemapRef>,
+}
+
+struct SitemapRef {
+    loc: String,
+    lastmod: Option<DateTime<Local>>,
+}
+
+struct UrlSet {
+    urls: Vec<SitemapUrl>,
+}
+
+struct SitemapUrl {
+    loc: String,
+    lastmod: Option<DateTime<Local>>,
+    changefreq: Option<&'static str>,
+    priority: Option<f32>,
+    images: Vec<SitemapImage>,
+    news: Option<SitemapNews>,
+}
+
+struct SitemapImage {
+    loc: String,
+    title: String,
+    caption: Option<String>,
+}
+
+// Google News entry (https://developers.google.com/search/docs/crawling-indexing/sitemaps/news-sitemap)
+struct SitemapNews {
+    publication: String,
+    language: String, // ISO 639, e.g. "en"
+    publication_date: DateTime<Local>,
+    title: String,
+}
+
+impl XmlDocument for SitemapIndex {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        xml.create_element("sitemapindex")
+            .with_attribute(("xmlns", "http://www.sitemaps.org/schemas/sitemap/0.9"))
+            .write_inner_content(|xml| {
+                for sitemap in &self.sitemaps {
+                    xml.create_element("sitemap").write_inner_content(|xml| {
+                        text_element(xml, "loc", &sitemap.loc)?;
+                        if let Some(lastmod) = &sitemap.lastmod {
+                            text_element(xml, "lastmod", &lastmod.to_rfc3339_opts(chrono::SecondsFormat::Secs, false))?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                Ok(())
+            })?;
+        Ok(())
+    }
+}
+
+impl XmlDocument for UrlSet {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        let mut urlset = xml
+            .create_element("urlset")
+            .with_attribute(("xmlns", "http://www.sitemaps.org/schemas/sitemap/0.9"))
+            .with_attribute(("xmlns:image", "http://www.google.com/schemas/sitemap-image/1.1"));
+        if self.urls.iter().any(|url| url.news.is_some()) {
+            urlset = urlset.with_attribute(("xmlns:news", "http://www.google.com/schemas/sitemap-news/0.9"));
+        }
+        urlset.write_inner_content(|xml| {
+            for url in &self.urls {
+                url.write(xml)?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+impl SitemapUrl {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        xml.create_element("url").write_inner_content(|xml| {
+            text_element(xml, "loc", &self.loc)?;
+            if let Some(lastmod) = &self.lastmod {
+                text_element(xml, "lastmod", &lastmod.to_rfc3339_opts(chrono::SecondsFormat::Secs, false))?;
+            }
+            if let Some(changefreq) = self.changefreq {
+                text_element(xml, "changefreq", changefreq)?;
+            }
+            if let Some(priority) = self.priority {
+                text_element(xml, "priority", &format!("{:.1}", priority))?;
+            }
+            for image in &self.images {
+                xml.create_element("image:image").write_inner_content(|xml| {
+                    text_element(xml, "image:loc", &image.loc)?;
+                    text_element(xml, "image:title", &image.title)?;
+                    if let Some(caption) = &image.caption {
+                        text_element(xml, "image:caption", caption)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            if let Some(news) = &self.news {
+                xml.create_element("news:news").write_inner_content(|xml| {
+                    xml.create_element("news:publication").write_inner_content(|xml| {
+                        text_element(xml, "news:name", &news.publication)?;
+                        text_element(xml, "news:language", &news.language)?;
+                        Ok(())
+                    })?;
+                    text_element(
+                        xml,
+                        "news:publication_date",
+                        &news.publication_date.to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+                    )?;
+                    text_element(xml, "news:title", &news.title)?;
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+// OPML 2.0 subscription list (http://opml.org/spec2.opml)
+struct Opml {
+    title: String,
+    date_modified: Option<DateTime<Local>>,
+    owner_name: String,
+    outlines: Vec<OpmlOutline>,
+}
+
+// A feed (type="rss", which OPML uses for any feed format) or a folder of feeds
+struct OpmlOutline {
+    text: String,
+    xml_url: Option<String>,
+    html_url: Option<String>,
+    description: Option<String>,
+    children: Vec<OpmlOutline>,
+}
+
+impl OpmlOutline {
+    fn feed(text: String, xml_url: String, html_url: String, description: Option<String>) -> Self {
+        Self { text, xml_url: Some(xml_url), html_url: Some(html_url), description, children: Vec::new() }
+    }
+
+    fn folder(text: &str, children: Vec<OpmlOutline>) -> Self {
+        Self { text: text.to_string(), xml_url: None, html_url: None, description: None, children }
+    }
+
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        let text = xml_chars(&self.text);
+        let mut outline = xml.create_element("outline").with_attribute(("text", text.as_str()));
+        if let Some(xml_url) = &self.xml_url {
+            outline = outline
+                .with_attribute(("type", "rss"))
+                .with_attribute(("title", text.as_str()))
+                .with_attribute(("xmlUrl", xml_url.as_str()));
+        }
+        if let Some(html_url) = &self.html_url {
+            outline = outline.with_attribute(("htmlUrl", html_url.as_str()));
+        }
+        let description = self.description.as_deref().map(xml_chars);
+        if let Some(description) = &description {
+            outline = outline.with_attribute(("description", description.as_str()));
+        }
+        if self.children.is_empty() {
+            outline.write_empty()?;
+        } else {
+            outline.write_inner_content(|xml| {
+                for child in &self.children {
+                    child.write(xml)?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl XmlDocument for Opml {
+    fn write(&self, xml: &mut XmlWriter) -> std::io::Result<()> {
+        xml.create_element("opml").with_attribute(("version", "2.0")).write_inner_content(|xml| {
+            xml.create_element("head").write_inner_content(|xml| {
+                text_element(xml, "title", &self.title)?;
+                if let Some(date_modified) = &self.date_modified {
+                    text_element(xml, "dateModified", &date_modified.to_rfc2822())?;
+                }
+                text_element(xml, "ownerName", &self.owner_name)?;
+                Ok(())
+            })?;
+            xml.create_element("body").write_inner_content(|xml| {
+                for outline in &self.outlines {
+                    outline.write(xml)?;
+                }
+                Ok(())
+            })?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+// What a feed covers: the whole site, or the posts with one tag or by one author
+struct FeedScope {
+    title: String,
+    description: String,
+    home: String, // The HTML page the feed mirrors
+    base: String, // Feed URLs are {base}/rss.xml, {base}/atom.xml and {base}/feed.json
+    content: FeedContent,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FeedContent {
+    Full,
+    Summary,
+}
+
+impl FeedContent {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "full" => Some(Self::Full),
+            "summary" => Some(Self::Summary),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FeedQuery {
+    content: Option<String>,
+}
+
+impl FeedScope {
+    // A ?content= override from the request
+    fn with_query(mut self, query: &FeedQuery) -> Self {
+        if let Some(content) = query.content.as_deref().and_then(FeedContent::parse) {
+            self.content = content;
+        }
+        self
+    }
+
+    // Self URL of one of the scope's feeds, keeping a content mode other than the default
+    fn feed_url(&self, file: &str) -> String {
+        match (self.content, *FEED_CONTENT) {
+            (FeedContent::Full, FeedContent::Summary) => format!("{}/{}?content=full", self.base, file),
+            (FeedContent::Summary, FeedContent::Full) => format!("{}/{}?content=summary", self.base, file),
+            _ => format!("{}/{}", self.base, file),
+        }
+    }
+
+    fn site(site_config: &SiteConfig) -> Self {
+        Self {
+            title: site_config.title.clone(),
+            description: site_config.description.clone(),
+            home: format!("{}/", site_config.url),
+            base: site_config.url.clone(),
+            content: *FEED_CONTENT,
+        }
+    }
+
+    fn tag(site_config: &SiteConfig, tag: &str) -> Self {
+        Self {
+            title: format!("{} - {}", site_config.title, tag),
+            description: format!("Posts tagged with '{}'.", tag),
+            home: format!("{}/tags/{}", site_config.url, url_segment(tag)),
+            base: format!("{}/tags/{}", site_config.url, url_segment(tag)),
+            content: *FEED_CONTENT,
+        }
+    }
+
+    fn author(site_config: &SiteConfig, author: &str) -> Self {
+        Self {
+            title: format!("{} - {}", site_config.title, author),
+            description: format!("Posts by {}.", author),
+            home: format!("{}/", site_config.url),
+            base: format!("{}/authors/{}", site_config.url, url_segment(&title_slug(author))),
+            content: *FEED_CONTENT,
+        }
+    }
+}
+
+// Which posts a scoped feed takes: a tag (case-insensitive) or an author's slug
+enum FeedFilter<'a> {
+    Tag(&'a str),
+    Author(&'a str),
+}
+
+// The posts in a tag or author feed, with the scope; None when nothing matches
+fn scoped_feed_posts(posts: Vec<Post>, site_config: &SiteConfig, filter: FeedFilter) -> Option<(Vec<Post>, FeedScope)> {
+    let (posts, scope): (Vec<Post>, FeedScope) = match filter {
+        FeedFilter::Tag(name) => {
+            let tag = name.to_lowercase();
+            let posts: Vec<Post> =
+                posts.into_iter().filter(|p| p.tags.iter().any(|t| t.to_lowercase() == tag)).collect();
+            (posts, FeedScope::tag(site_config, name))
+        }
+        FeedFilter::Author(slug) => {
+            let posts: Vec<Post> = posts.into_iter().filter(|p| title_slug(&p.author) == slug).collect();
+            let author = posts.first()?.author.clone();
+            (posts, FeedScope::author(site_config, &author))
+        }
+    };
+    if posts.is_empty() {
+        None
+    } else {
+        Some((posts, scope))
+    }
+}
+
+// The 20 posts a feed includes
+fn feed_posts(posts: &[Post]) -> &[Post] {
+    &posts[..posts.len().min(20)]
+}
+
+// Last-Modified is the newest included post, so pollers get a 304 until something changes
+fn feed_response(body: String, content_type: &str, posts: &[Post]) -> Response {
+    with_last_modified(
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, format!("{}; charset=utf-8", content_type))
+            .header(header::CACHE_CONTROL, "public, max-age=3600")
+            .body(body.into())
+            .unwrap(),
+        newest_update(feed_posts(posts)),
+    )
+}
+
+// One post as an RSS item; RSS allows a single enclosure, so the post's media wins over its OG image
+fn rss_item(post: &Post, site_config: &SiteConfig, scope: &FeedScope) -> RssItem {
+    let link = format!("{}/blog/{}", site_config.url, post.slug);
+    RssItem {
+        title: post.title.clone(),
+        enclosure: Some(match &post.enclosure {
+            Some(enclosure) => RssEnclosure {
+                url: enclosure.url.clone(),
+                length: enclosure.length,
+                mime: enclosure.mime.clone(),
+            },
+            None => RssEnclosure {
+                url: format!("{}/og.png", link),
+                length: 0, // Rendered on request, so the size isn't known up front
+                mime: "image/png".to_string(),
+            },
+        }),
+        itunes: post.enclosure.as_ref().map(|enclosure| ItunesItem {
+            duration: enclosure.duration_seconds,
+            image: PODCAST_IMAGE.clone(),
+            explicit: *PODCAST_EXPLICIT,
+        }),
+        link,
+        description: post.summary.clone(),
+        content: (scope.content == FeedContent::Full).then(|| post.content.clone()),
+        creator: post.author.clone(),
+        pub_date: post.published,
+        categories: post.tags.clone(),
+    }
+}
+
+// RSS 2.0 document for the 20 newest of `posts`
+fn build_rss(posts: &[Post], site_config: &SiteConfig, scope: &FeedScope) -> String {
+    RssChannel {
+        title: scope.title.clone(),
+        link: scope.home.clone(),
+        description: scope.description.clone(),
+        language: site_config.language.clone(),
+        last_build_date: newest_update(feed_posts(posts)).unwrap_or_else(Local::now),
+        self_link: scope.feed_url("rss.xml"),
+        hub: websub_hub_url(site_config),
+        itunes: None,
+        items: feed_posts(posts).iter().map(|post| rss_item(post, site_config, scope)).collect(),
+    }
+    .to_xml()
+}
+
+// Podcast feed: every post with an audio enclosure, with the channel tags Apple Podcasts requires
+fn build_podcast(episodes: &[Post], site_config: &SiteConfig, scope: &FeedScope) -> String {
+    RssChannel {
+        title: scope.title.clone(),
+        link: scope.home.clone(),
+        description: scope.description.clone(),
+        language: site_config.language.clone(),
+        last_build_date: newest_update(episodes).unwrap_or_else(Local::now),
+        self_link: format!("{}/podcast.xml", site_config.url),
+        hub: websub_hub_url(site_config),
+        itunes: Some(ItunesChannel {
+            author: site_config.author_full_name.clone(),
+            summary: scope.description.clone(),
+            image: PODCAST_IMAGE.clone(),
+            category: PODCAST_CATEGORY.clone(),
+            explicit: *PODCAST_EXPLICIT,
+            owner_name: site_config.author_full_name.clone(),
+            owner_email: PODCAST_EMAIL.clone(),
+        }),
+        items: episodes.iter().map(|post| rss_item(post, site_config, scope)).collect(),
+    }
+    .to_xml()
+}
+
+// Atom 1.0 document for the 20 newest of `posts`
+fn build_atom(posts: &[Post], site_config: &SiteConfig, scope: &FeedScope) -> String {
+    AtomFeed {
+        lang: site_config.language.clone(),
+        title: scope.title.clone(),
+        subtitle: scope.description.clone(),
+        id: scope.home.clone(),
+        updated: newest_update(feed_posts(posts)).unwrap_or_else(Local::now),
+        links: vec![
+            AtomLink {
+                href: scope.feed_url("atom.xml"),
+                rel: "self",
+                mime: "application/atom+xml".to_string(),
+                title: None,
+                length: None,
+            },
+            AtomLink {
+                href: scope.home.clone(),
+                rel: "alternate",
+                mime: "text/html".to_string(),
+                title: None,
+                length: None,
+            },
+        ]
+        .into_iter()
+        .chain(websub_hub_url(site_config).map(|hub| AtomLink {
+            href: hub,
+            rel: "hub",
+            mime: String::new(),
+            title: None,
+            length: None,
+        }))
+        .collect(),
+        author: AtomPerson {
+            name: site_config.author_full_name.clone(),
+            uri: Some(site_config.url.clone()),
+        },
+        icon: format!("{}{}", site_config.url, site_config.logo),
+        logo: format!("{}{}", site_config.url, site_config.logo),
+        rights: format!("© {} {}", site_config.copyright_year, site_config.author_full_name),
+        entries: feed_posts(posts)
+            .iter()
+            .map(|post| {
+                let link = format!("{}/blog/{}", site_config.url, post.slug);
+                AtomEntry {
+                    title: post.title.clone(),
+                    links: vec![
+                        AtomLink {
+                            href: link.clone(),
+                            rel: "alternate",
+                            mime: "text/html".to_string(),
+                            title: None,
+                            length: None,
+                        },
+                        AtomLink {
+                            href: format!("{}/og.png", link),
+                            rel: "enclosure",
+                            mime: "image/png".to_string(),
+                            title: Some("OG Image".to_string()),
+                            length: None,
+                        },
+                    ]
+                    .into_iter()
+                    .chain(post.enclosure.as_ref().map(|enclosure| AtomLink {
+                        href: enclosure.url.clone(),
+                        rel: "enclosure",
+                        mime: enclosure.mime.clone(),
+                        title: None,
+                        length: (enclosure.length > 0).then_some(enclosure.length),
+                    }))
+                    .collect(),
+                    id: link,
+                    published: post.published,
+                    updated: post.updated,
+                    author: AtomPerson { name: post.author.clone(), uri: None },
+                    summary: post.summary.clone(),
+                    content: (scope.content == FeedContent::Full).then(|| post.content.clone()),
+                    categories: post.tags.clone(),
+                }
+            })
+            .collect(),
+    }
+    .to_xml()
+}
+
+// JSON Feed 1.1 (https://www.jsonfeed.org/version/1.1/)
+#[derive(Serialize)]
+struct JsonFeed {
+    version: &'static str,
+    title: String,
+    home_page_url: String,
+    feed_url: String,
+    description: String,
+    icon: String,
+    favicon: String,
+    language: String,
+    authors: Vec<JsonFeedAuthor>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hubs: Vec<JsonFeedHub>,
+    items: Vec<JsonFeedItem>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedHub {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    url: String,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem {
+    id: String,
+    url: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_text: Option<String>, // Summary-only feeds
+    summary: String,
+    image: String,
+    date_published: String,
+    date_modified: String,
+    tags: Vec<String>,
+    authors: Vec<JsonFeedAuthor>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attachments: Vec<JsonFeedAttachment>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAttachment {
+    url: String,
+    mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size_in_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_in_seconds: Option<u64>,
+}
+
+impl JsonFeedItem {
+    fn from_post(post: &Post, site_config: &SiteConfig, content: FeedContent) -> Self {
+        let url = format!("{}/blog/{}", site_config.url, post.slug);
+        let full = content == FeedContent::Full;
+        Self {
+            id: url.clone(),
+            image: format!("{}/og.png", url),
+            url,
+            title: post.title.clone(),
+            content_html: full.then(|| post.content.clone()),
+            content_text: (!full).then(|| post.summary.clone()),
+            summary: post.summary.clone(),
+            date_published: post.published.to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            date_modified: post.updated.to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            tags: post.tags.clone(),
+            authors: vec![JsonFeedAuthor { name: post.author.clone(), url: None }],
+            attachments: post
+                .enclosure
+                .iter()
+                .map(|enclosure| JsonFeedAttachment {
+                    url: enclosure.url.clone(),
+                    mime_type: enclosure.mime.clone(),
+                    size_in_bytes: (enclosure.length > 0).then_some(enclosure.length),
+                    duration_in_seconds: enclosure.duration_seconds,
+                })
+                .collect(),
+        }
+    }
+}
+
+// JSON Feed document for the 20 newest of `posts`
+fn build_json_feed(posts: &[Post], site_config: &SiteConfig, scope: &FeedScope) -> String {
+    let feed = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: scope.title.clone(),
+        home_page_url: scope.home.clone(),
+        feed_url: scope.feed_url("feed.json"),
+        description: scope.description.clone(),
+        icon: format!("{}{}", site_config.url, site_config.logo),
+        favicon: format!("{}{}", site_config.url, site_config.favicon),
+        language: site_config.language.clone(),
+        authors: vec![JsonFeedAuthor {
+            name: site_config.author_full_name.clone(),
+            url: Some(site_config.url.clone()),
+        }],
+        hubs: websub_hub_url(site_config).map(|url| JsonFeedHub { kind: "WebSub", url }).into_iter().collect(),
+        items: feed_posts(posts).iter().map(|post| JsonFeedItem::from_post(post, site_config, scope.content)).collect(),
+    };
+    serde_json::to_string_pretty(&feed).unwrap()
+}
+
+// Sitemap files hold at most 50,000 URLs, so posts are split across sitemap-posts.xml, sitemap-posts-2.xml, ...
+const SITEMAP_CHUNK: usize = 50_000;
+
+// Each post's source hash and when it was first seen, which dates sitemap entries
+const CONTENT_MODIFIED_CACHE: &str = ".cache/content-modified.json";
+
+fn sitemap_response(xml: String, last_modified: Option<DateTime<Local>>) -> Response {
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=3600")
+        .header("X-Robots-Tag", "noindex")
+        .body(xml.into())
+        .unwrap();
+    with_last_modified(response, last_modified)
+}
+
+// URL of the nth (1-based) posts sitemap
+fn posts_sitemap_url(site_config: &SiteConfig, page: usize) -> String {
+    if page == 1 {
+        format!("{}/sitemap-posts.xml", site_config.url)
+    } else {
+        format!("{}/sitemap-posts-{}.xml", site_config.url, page)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SourceChange {
+    hash: String,
+    modified: i64, // Unix seconds
+}
+
+// When each post's source file last changed, by slug: the time its hash was first seen to differ,
+// starting from the file's modification time. Sitemaps' lastmod; feeds keep `updated`
+fn content_modified() -> HashMap<String, DateTime<Local>> {
+    static CHANGES: Lazy<Mutex<HashMap<String, SourceChange>>> = Lazy::new(|| {
+        let changes = fs::read_to_string(CONTENT_MODIFIED_CACHE)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Mutex::new(changes)
+    });
+    let now = Local::now().timestamp();
+    let mut changes = CHANGES.lock().unwrap();
+    let mut changed = false;
+    let mut modified = HashMap::new();
+    for source in post_sources() {
+        let hash = etag_for(&[&source.content]).trim_matches('"').to_string();
+        let time = match changes.get(&source.slug) {
+            Some(change) if change.hash == hash => change.modified,
+            known => {
+                // A post seen for the first time dates from its file; a known one changed just now
+                let time = match known {
+                    Some(_) => now,
+                    None => source.modified.unwrap_or(source.updated).timestamp(),
+                };
+                changes.insert(source.slug.clone(), SourceChange { hash, modified: time });
+                changed = true;
+                time
+            }
+        };
+        let time = Local.timestamp_opt(time, 0).single().unwrap_or(source.updated);
+        // An explicit `updated` later than any change seen still counts
+        modified.insert(source.slug, time.max(source.updated));
+    }
+    if changed {
+        let _ = fs::create_dir_all(".cache");
+        if let Ok(json) = serde_json::to_string_pretty(&*changes) {
+            let _ = fs::write(CONTENT_MODIFIED_CACHE, json);
+        }
+    }
+    modified
+}
+
+// When a post last changed according to `modified`, else its `updated`
+fn post_modified(post: &Post, modified: &HashMap<String, DateTime<Local>>) -> DateTime<Local> {
+    modified.get(&post.slug).copied().unwrap_or(post.updated)
+}
+
+// Newest change among `posts` according to `modified`
+fn newest_modified(posts: &[Post], modified: &HashMap<String, DateTime<Local>>) -> Option<DateTime<Local>> {
+    posts.iter().map(|post| post_modified(post, modified)).max()
+}
+
+// Each tag (first spelling seen) with its newest post's modification time
+fn sitemap_tags(posts: &[Post], modified: &HashMap<String, DateTime<Local>>) -> Vec<(String, DateTime<Local>)> {
+    let mut tags: Vec<(String, DateTime<Local>)> = Vec::new();
+    for post in posts {
+        let post_modified = post_modified(post, modified);
+        for tag in &post.tags {
+            match tags.iter_mut().find(|(t, _)| t.to_lowercase() == tag.to_lowercase()) {
+                Some((_, updated)) => *updated = (*updated).max(post_modified),
+                None => tags.push((tag.clone(), post_modified)),
+            }
+        }
+    }
+    tags.sort_by_key(|(tag, _)| tag.to_lowercase());
+    tags
+}
+
+// Sitemap index: the posts sitemaps, tag pages and other pages, each with its newest change
+async fn sitemap() -> Response {
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    let modified = content_modified();
+
+    let mut sitemaps: Vec<SitemapRef> = posts
+        .chunks(SITEMAP_CHUNK)
+        .enumerate()
+        .map(|(i, chunk)| SitemapRef {
+            loc: posts_sitemap_url(&site_config, i + 1),
+            lastmod: newest_modified(chunk, &modified),
+        })
+        .collect();
+    if sitemaps.is_empty() {
+        sitemaps.push(SitemapRef { loc: posts_sitemap_url(&site_config, 1), lastmod: None });
+    }
+    sitemaps.push(SitemapRef {
+        loc: format!("{}/sitemap-tags.xml", site_config.url),
+        lastmod: sitemap_tags(&posts, &modified).iter().map(|(_, updated)| *updated).max(),
+    });
+    sitemaps.push(SitemapRef {
+        loc: format!("{}/sitemap-pages.xml", site_config.url),
+        lastmod: newest_modified(&posts, &modified).max(blogroll_modified()),
+    });
+
+    let last_modified = sitemaps.iter().filter_map(|s| s.lastmod).max();
+    sitemap_response(SitemapIndex { sitemaps }.to_xml(), last_modified)
+}
+
+// Generate robots.txt - Enhanced with comprehensive directives
+async fn robots_txt() -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let content = format!(
+        r#"# Robots.txt for {}
+# Generated automatically - Optimized for SEO
+
+# Allow all search engines
+User-agent: *
+Allow: /
+Allow: /blog/
+Allow: /rss.xml
+Allow: /atom.xml
+Allow: /sitemap.xml
+
+# Optimize crawl budget
+Disallow: /api/
+Disallow: /admin/
+Disallow: /*.json$
+Disallow: /*?*
+
+# Crawl-delay for politeness (1 second between requests)
+Crawl-delay: 1
+
+# Google-specific directives
+User-agent: Googlebot
+Allow: /
+Crawl-delay: 0
+
+# Google Image Bot
+User-agent: Googlebot-Image
+Allow: /
+Allow: /*.png$
+Allow: /*.jpg$
+Allow: /*.jpeg$
+Allow: /*.webp$
+Allow: /*.gif$
+
+# Bing
+User-agent: Bingbot
+Allow: /
+Crawl-delay: 1
+
+# DuckDuckGo
+User-agent: DuckDuckBot
+Allow: /
+
+# Yandex
+User-agent: Yandex
+Allow: /
+Crawl-delay: 2
+
+# Baidu
+User-agent: Baiduspider
+Allow: /
+Crawl-delay: 2
+
+# AI and Specialized Bots
+User-agent: GPTBot
+Allow: /
+
+User-agent: ChatGPT-User
+Allow: /
+
+User-agent: CCBot
+Allow: /
+
+User-agent: anthropic-ai
+Allow: /
+
+User-agent: Claude-Web
+Allow: /
+
+# Sitemaps
+Sitemap: {}/sitemap.xml
+
+# Host directive
+Host: {}
+"#,
+        site_config.title,
+        site_config.url,
+        site_config.url.replace("https://", "").replace("http://", "")
+    );
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=86400")
+        .body(content)
+        .unwrap()
+}
+
+// Generate RSS Feed
+async fn rss_feed(Query(query): Query<FeedQuery>) -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let posts = get_posts_for(&site_config, RenderProfile::Feed);
+    let scope = FeedScope::site(&site_config).with_query(&query);
+    feed_response(build_rss(&posts, &site_config, &scope), "application/rss+xml", &posts)
+}
+
+// Generate Atom Feed (alternative to RSS, preferred by some readers)
+async fn atom_feed(Query(query): Query<FeedQuery>) -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let posts = get_posts_for(&site_config, RenderProfile::Feed);
+    let scope = FeedScope::site(&site_config).with_query(&query);
+    feed_response(build_atom(&posts, &site_config, &scope), "application/atom+xml", &posts)
+}
+
+// Generate JSON Feed
+async fn json_feed(Query(query): Query<FeedQuery>) -> impl IntoResponse {
+    let site_config = SiteConfig::default();
+    let posts = get_posts_for(&site_config, RenderProfile::Feed);
+    let scope = FeedScope::site(&site_config).with_query(&query);
+    feed_response(build_json_feed(&posts, &site_config, &scope), "application/feed+json", &posts)
+}
+
+// Whether any post has audio, which makes /podcast.xml exist
+fn has_podcast(posts: &[Post]) -> bool {
+    posts.iter().any(|p| p.enclosure.as_ref().is_some_and(|e| e.mime.starts_with("audio/")))
+}
+
+// Podcast episodes: the posts with audio
+fn podcast_episodes(posts: Vec<Post>) -> Vec<Post> {
+    posts
+        .into_iter()
+        .filter(|post| post.enclosure.as_ref().is_some_and(|e| e.mime.starts_with("audio/")))
+        .collect()
+}
+
+// Podcast feed of the posts with audio
+async fn podcast_feed(Query(query): Query<FeedQuery>) -> Response {
+    let site_config = SiteConfig::default();
+    let episodes = podcast_episodes(get_posts_for(&site_config, RenderProfile::Feed));
+    if episodes.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let scope = FeedScope::site(&site_config).with_query(&query);
+    feed_response(build_podcast(&episodes, &site_config, &scope), "application/rss+xml", &episodes)
+}
+
+// Every tag and author feed the posts produce, each sorted by name; tags compare case-insensitively
+fn scoped_feed_scopes(posts: &[Post], site_config: &SiteConfig) -> (Vec<FeedScope>, Vec<FeedScope>) {
+    let mut tags: Vec<&str> = Vec::new();
+    let mut authors: Vec<&str> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for post in posts {
+        for tag in &post.tags {
+            if seen.insert(format!("tag:{}", tag.to_lowercase())) {
+                tags.push(tag);
+            }
+        }
+        if seen.insert(format!("author:{}", title_slug(&post.author))) {
+            authors.push(&post.author);
+        }
+    }
+    tags.sort_by_key(|tag| tag.to_lowercase());
+    authors.sort_by_key(|author| author.to_lowercase());
+    (
+        tags.into_iter().map(|tag| FeedScope::tag(site_config, tag)).collect(),
+        authors.into_iter().map(|author| FeedScope::author(site_config, author)).collect(),
+    )
+}
+
+// Feeds for one tag (/tags/{tag}/rss.xml) or author (/authors/{author}/rss.xml), in any format
+fn scoped_feed(filter: FeedFilter, format: &str, query: &FeedQuery) -> Response {
+    let site_config = SiteConfig::default();
+    let posts = get_posts_for(&site_config, RenderProfile::Feed);
+    let Some((posts, scope)) = scoped_feed_posts(posts, &site_config, filter) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match build_feed(format, &posts, &site_config, &scope.with_query(query)) {
+        Some((body, content_type)) => feed_response(body, content_type, &posts),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+// A feed document and its content type, by file name: rss.xml, atom.xml or feed.json
+fn build_feed(format: &str, posts: &[Post], site_config: &SiteConfig, scope: &FeedScope) -> Option<(String, &'static str)> {
+    match format {
+        "rss.xml" => Some((build_rss(posts, site_config, scope), "application/rss+xml")),
+        "atom.xml" => Some((build_atom(posts, site_config, scope), "application/atom+xml")),
+        "feed.json" => Some((build_json_feed(posts, site_config, scope), "application/feed+json")),
+        _ => None,
+    }
+}
+
+// The feed served at an absolute URL on this site, as WebSub topics name them
+fn render_feed(topic: &str, posts: &[Post], site_config: &SiteConfig) -> Option<(String, &'static str)> {
+    let path = topic.strip_prefix(site_config.url.as_str())?;
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["feed.xml"] | ["feed"] => build_feed("rss.xml", posts, site_config, &FeedScope::site(site_config)),
+        ["podcast.xml"] => {
+            let episodes = podcast_episodes(posts.to_vec());
+            let podcast = build_podcast(&episodes, site_config, &FeedScope::site(site_config));
+            (!episodes.is_empty()).then_some((podcast, "application/rss+xml"))
+        }
+        [format] => build_feed(format, posts, site_config, &FeedScope::site(site_config)),
+        [kind @ ("tags" | "authors"), name, format] => {
+            let name = percent_decode(name);
+            let filter = if *kind == "tags" { FeedFilter::Tag(&name) } else { FeedFilter::Author(&name) };
+            let (posts, scope) = scoped_feed_posts(posts.to_vec(), site_config, filter)?;
+            build_feed(format, &posts, site_config, &scope)
+        }
+        _ => None,
+    }
+}
+
+async fn tag_feed(Path((tag, format)): Path<(String, String)>, Query(query): Query<FeedQuery>) -> Response {
+    scoped_feed(FeedFilter::Tag(&tag), &format, &query)
+}
+
+async fn author_feed(Path((author, format)): Path<(String, String)>, Query(query): Query<FeedQuery>) -> Response {
+    scoped_feed(FeedFilter::Author(&author), &format, &query)
+}
+
+// Google News wants the bare ISO 639 code, except for Chinese, which is zh-cn or zh-tw
+fn news_language(language: &str) -> String {
+    let language = language.to_lowercase().replace('_', "-");
+    let mut parts = language.split('-');
+    match (parts.next().unwrap_or(""), parts.next()) {
+        ("zh", Some("tw" | "hk" | "mo" | "hant")) => "zh-tw".to_string(),
+        ("zh", Some("cn" | "sg" | "hans")) => "zh-cn".to_string(),
+        ("", _) => "en".to_string(),
+        (code, _) => code.to_string(),
+    }
+}
+
+// One chunk of posts, with OG images; posts published in the last 48 hours also get a Google News entry
+fn posts_sitemap(posts: &[Post], site_config: &SiteConfig, modified: &HashMap<String, DateTime<Local>>) -> Response {
+    let news_cutoff = Local::now() - chrono::Duration::hours(48);
+    let language = news_language(&site_config.language);
+    let default_image = format!("{}/og-default.png", site_config.url);
+
+    let urls = posts
+        .iter()
+        .map(|post| {
+            let loc = format!("{}/blog/{}", site_config.url, url_segment(&post.slug));
+            let mut images = vec![SitemapImage {
+                loc: format!("{}/og.png", loc),
+                title: post.title.clone(),
+                caption: Some(post.summary.clone()),
+            }];
+            // A cover image set in front matter
+            if post.image != default_image {
+                images.push(SitemapImage {
+                    loc: if post.image.starts_with('/') {
+                        format!("{}{}", site_config.url, post.image)
+                    } else {
+                        post.image.clone()
+                    },
+                    title: post.title.clone(),
+                    caption: None,
+                });
+            }
+            SitemapUrl {
+                news: (post.published >= news_cutoff).then(|| SitemapNews {
+                    publication: site_config.title.clone(),
+                    language: language.clone(),
+                    publication_date: post.published,
+                    title: post.title.clone(),
+                }),
+                loc,
+                lastmod: Some(post_modified(post, modified)),
+                changefreq: Some("weekly"),
+                priority: Some(0.8),
+                images,
+            }
+        })
+        .collect();
+
+    sitemap_response(UrlSet { urls }.to_xml(), newest_modified(posts, modified))
+}
+
+// First posts sitemap
+async fn sitemap_posts() -> Response {
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    posts_sitemap(&posts[..posts.len().min(SITEMAP_CHUNK)], &site_config, &content_modified())
+}
+
+// Further posts sitemaps: /sitemap-posts-2.xml, /sitemap-posts-3.xml, ...
+async fn sitemap_posts_page(Path(page): Path<String>) -> Response {
+    let Some(page) = page.strip_suffix(".xml").and_then(|p| p.parse::<usize>().ok()).filter(|p| *p >= 2) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    match posts.chunks(SITEMAP_CHUNK).nth(page - 1) {
+        Some(chunk) => posts_sitemap(chunk, &site_config, &content_modified()),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+// Tag pages, each last modified with its newest post
+async fn sitemap_tags_xml() -> Response {
+    let site_config = SiteConfig::default();
+    tags_sitemap(&get_posts(&site_config), &site_config, &content_modified())
+}
+
+fn tags_sitemap(posts: &[Post], site_config: &SiteConfig, modified: &HashMap<String, DateTime<Local>>) -> Response {
+    let tags = sitemap_tags(posts, modified);
+    let last_modified = tags.iter().map(|(_, updated)| *updated).max();
+
+    let urls = tags
+        .into_iter()
+        .map(|(tag, updated)| SitemapUrl {
+            loc: format!("{}/tags/{}", site_config.url, url_segment(&tag)),
+            lastmod: Some(updated),
+            changefreq: Some("weekly"),
+            priority: Some(0.6),
+            images: Vec::new(),
+            news: None,
+        })
+        .collect();
+    sitemap_response(UrlSet { urls }.to_xml(), last_modified)
+}
+
+// When content/blogroll.json last changed; None without a blogroll
+fn blogroll_modified() -> Option<DateTime<Local>> {
+    if BLOGROLL.lock().unwrap().is_empty() {
+        return None;
+    }
+    fs::metadata(BLOGROLL_FILE).and_then(|m| m.modified()).ok().map(DateTime::<Local>::from)
+}
+
+// Pages other than posts and tags: the home page, which changes with the newest post, and the blogroll
+async fn sitemap_pages() -> Response {
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    let blogroll_modified = blogroll_modified();
+    let last_modified = newest_modified(&posts, &content_modified()).max(blogroll_modified);
+
+    let mut urls = vec![SitemapUrl {
+        loc: format!("{}/", site_config.url),
+        lastmod: last_modified,
+        changefreq: Some("daily"),
+        priority: Some(1.0),
+        images: vec![SitemapImage {
+            loc: format!("{}/og.png", site_config.url),
+            title: site_config.title.clone(),
+            caption: None,
+        }],
+        news: None,
+    }];
+    if site_config.has_blogroll {
+        urls.push(SitemapUrl {
+            loc: format!("{}/blogroll", site_config.url),
+            lastmod: blogroll_modified,
+            changefreq: Some("monthly"),
+            priority: Some(0.5),
+            images: Vec::new(),
+            news: None,
+        });
+    }
+    sitemap_response(UrlSet { urls }.to_xml(), last_modified)
+}
+
+// ============================================================================
+// Blogroll & OPML - the blogs we follow and subscription lists for them and for our own feeds
+// ============================================================================
+
+fn opml_response(xml: String, last_modified: Option<DateTime<Local>>) -> Response {
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/x-opml; charset=utf-8")
+        .header(header::CACHE_CONTROL, "public, max-age=3600")
+        .body(xml.into())
+        .unwrap();
+    with_last_modified(response, last_modified)
+}
+
+// Blogroll page; reuses the index template with the list in place of posts, under its own URL and title
+async fn blogroll_page(Extension(hb): Extension<Arc<Handlebars<'_>>>) -> Response {
+    let blogroll = BLOGROLL.lock().unwrap().clone();
+    if blogroll.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let site_config = SiteConfig::default();
+
+    let mut data = HashMap::new();
+    data.insert("blogroll", serde_json::to_value(&*blogroll).unwrap());
+    data.insert("blogroll_count", serde_json::to_value(blogroll.len()).unwrap());
+    data.insert("posts_count", serde_json::to_value(0).unwrap());
+    let mut site_override = site_config.clone();
+    site_override.description = "Blogs we read and recommend.".to_string();
+    data.insert("site", serde_json::to_value(&site_override).unwrap());
+    data.insert("current_year", serde_json::to_value(Local::now().format("%Y").to_string()).unwrap());
+
+    match hb.render("index.html", &data) {
+        Ok(rendered) => Html(rendered).into_response(),
+        Err(e) => {
+            eprintln!("Failed to render blogroll template: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Template rendering error").into_response()
+        }
+    }
+}
+
+// The blogroll as OPML, for importing into a feed reader
+async fn blogroll_opml() -> Response {
+    let blogroll = BLOGROLL.lock().unwrap().clone();
+    if blogroll.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let site_config = SiteConfig::default();
+    let opml = Opml {
+        title: format!("{} - Blogroll", site_config.title),
+        date_modified: None,
+        owner_name: site_config.author_full_name.clone(),
+        outlines: blogroll
+            .iter()
+            .map(|entry| OpmlOutline::feed(entry.title.clone(), entry.feed.clone(), entry.url.clone(), entry.description.clone()))
+            .collect(),
+    };
+    opml_response(opml.to_xml(), None)
+}
+
+// All of our own feeds as OPML: the site, the podcast, then one per tag and per author
+async fn feeds_opml() -> Response {
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    let last_modified = newest_update(&posts);
+    opml_response(build_feeds_opml(&posts, &site_config).to_xml(), last_modified)
+}
+
+// The feeds list for `posts`
+fn build_feeds_opml(posts: &[Post], site_config: &SiteConfig) -> Opml {
+    let scope_outline = |scope: FeedScope| {
+        let xml_url = scope.feed_url("rss.xml");
+        OpmlOutline::feed(scope.title, xml_url, scope.home, Some(scope.description))
+    };
+
+    let mut outlines = vec![scope_outline(FeedScope::site(site_config))];
+    if has_podcast(posts) {
+        outlines.push(OpmlOutline::feed(
+            format!("{} - Podcast", site_config.title),
+            format!("{}/podcast.xml", site_config.url),
+            format!("{}/", site_config.url),
+            None,
+        ));
+    }
+    let (tags, authors) = scoped_feed_scopes(posts, site_config);
+    if !tags.is_empty() {
+        outlines.push(OpmlOutline::folder("Tags", tags.into_iter().map(scope_outline).collect()));
+    }
+    if !authors.is_empty() {
+        outlines.push(OpmlOutline::folder("Authors", authors.into_iter().map(scope_outline).collect()));
+    }
+
+    Opml {
+        title: format!("{} - Feeds", site_config.title),
+        date_modified: newest_update(posts),
+        owner_name: site_config.author_full_name.clone(),
+        outlines,
+    }
+}
+
+// ============================================================================
+// WebSub - hub discovery in feeds, publish pings and an optional built-in hub
+// ============================================================================
+
+// Where feeds send subscribers: an external hub, or the one this server runs at /websub
+enum WebSubHub {
+    External(String),
+    Builtin,
+}
+
+impl WebSubHub {
+    fn from_env() -> Option<Self> {
+        match env::var("WEBSUB_HUB").ok()?.trim() {
+            "" => None,
+            "builtin" => Some(Self::Builtin),
+            url => Some(Self::External(url.to_string())),
+        }
+    }
+}
+
+// A callback verified for one topic, until its lease runs out
+#[derive(Clone, Serialize, Deserialize)]
+struct WebSubSubscription {
+    topic: String,
+    callback: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secret: Option<String>, // Signs distributed content (X-Hub-Signature)
+    expires: i64,           // Unix seconds
+}
+
+// The hub URL feeds advertise with rel="hub", when WebSub is enabled
+fn websub_hub_url(site_config: &SiteConfig) -> Option<String> {
+    match WEBSUB_HUB.as_ref()? {
+        WebSubHub::External(url) => Some(url.clone()),
+        WebSubHub::Builtin => Some(format!("{}/websub", site_config.url)),
+    }
+}
+
+// Every feed on the site, by the self URL it advertises: site feeds, then each tag's and each author's
+fn websub_topics(posts: &[Post], site_config: &SiteConfig) -> Vec<String> {
+    let (tags, authors) = scoped_feed_scopes(posts, site_config);
+    let mut topics: Vec<String> = std::iter::once(FeedScope::site(site_config))
+        .chain(tags)
+        .chain(authors)
+        .flat_map(|scope| ["rss.xml", "atom.xml", "feed.json"].map(|format| format!("{}/{}", scope.base, format)))
+        .collect();
+    if has_podcast(posts) {
+        topics.push(format!("{}/podcast.xml", site_config.url));
+    }
+    topics
+}
+
+fn websub_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent("axum-blog")
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// Whether an address is on the public internet: not loopback, private, link-local, CGNAT,
+// documentation, multicast or unspecified
+fn is_public_ip(ip: std::net::IpAddr) -> bool {
+    match ip {
+        std::net::IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        std::net::IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(ip.into()),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || first & 0xfe00 == 0xfc00 // Unique local
+                    || first & 0xffc0 == 0xfe80) // Link-local
+            }
+        },
+    }
+}
+
+// Resolves subscriber hosts, failing for any name with a non-public address, so a callback
+// can't point the hub at services inside its network
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs: Vec<std::net::SocketAddr> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+                return Err(format!("{} resolves to a private address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+// Client for requests to subscriber callbacks: public addresses only, and no redirects to elsewhere
+fn websub_callback_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent("axum-blog")
+        .timeout(Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// A subscriber's hub.callback: an http(s) URL whose host isn't a private address or localhost.
+// Names are checked again, when resolved, by websub_callback_client
+fn websub_callback(callback: &str) -> Result<reqwest::Url, &'static str> {
+    let url = reqwest::Url::parse(callback).map_err(|_| "hub.callback must be an http(s) URL")?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("hub.callback must be an http(s) URL");
+    }
+    let host = url.host_str().unwrap_or_default();
+    let public = match host.trim_start_matches('[').trim_end_matches(']').parse::<std::net::IpAddr>() {
+        Ok(ip) => is_public_ip(ip),
+        Err(_) => {
+            let domain = host.trim_end_matches('.').to_ascii_lowercase();
+            !domain.is_empty() && domain != "localhost" && !domain.ends_with(".localhost")
+        }
+    };
+    if public {
+        Ok(url)
+    } else {
+        Err("hub.callback must be on a public host")
+    }
+}
+
+fn save_websub_subscriptions(subscriptions: &[WebSubSubscription]) {
+    let _ = fs::create_dir_all(".cache");
+    if let Ok(json) = serde_json::to_string_pretty(subscriptions) {
+        let _ = fs::write(WEBSUB_SUBSCRIPTIONS_FILE, json);
+    }
+}
+
+// After a content change: tell the hub about each feed whose document differs from the last one published
+async fn websub_publish_changes() {
+    let Some(hub) = WEBSUB_HUB.as_ref() else {
+        return;
+    };
+    let site_config = SiteConfig::default();
+    let Ok((previous, mut current, changed)) = tokio::task::spawn_blocking(move || {
+        let posts = get_posts_for(&site_config, RenderProfile::Feed);
+        let previous: HashMap<String, String> = fs::read_to_string(WEBSUB_TOPICS_CACHE)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let mut current = HashMap::new();
+        let mut changed = Vec::new();
+        let topics = websub_topics(&posts, &site_config);
+        *WEBSUB_TOPICS.lock().unwrap() = topics.iter().cloned().collect();
+        // Each feed is rendered once; the built-in hub distributes the same documents
+        for topic in topics {
+            let Some((body, content_type)) = render_feed(&topic, &posts, &site_config) else {
+                continue;
+            };
+            let hash = etag_for(&[&body]);
+            if previous.get(&topic) != Some(&hash) {
+                changed.push((topic.clone(), body, content_type));
+            }
+            current.insert(topic, hash);
+        }
+        (previous, current, changed)
+    })
+    .await
+    else {
+        return;
+    };
+    if changed.is_empty() {
+        return;
+    }
+
+    let client = match hub {
+        WebSubHub::External(_) => websub_client(),
+        WebSubHub::Builtin => websub_callback_client(),
+    };
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => return eprintln!("WebSub: {}", e),
+    };
+    for (topic, body, content_type) in changed {
+        let result = match hub {
+            WebSubHub::External(url) => websub_ping(&client, url, &topic).await,
+            WebSubHub::Builtin => {
+                // Retries wait, so one slow subscriber doesn't hold up the other feeds
+                tokio::spawn(websub_distribute(client.clone(), topic.clone(), body, content_type));
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("WebSub: {}", e);
+            // Keep the old hash so the next change retries this feed
+            match previous.get(&topic) {
+                Some(hash) => current.insert(topic.clone(), hash.clone()),
+                None => current.remove(&topic),
+            };
+        }
+    }
+    let _ = fs::create_dir_all(".cache");
+    if let Ok(json) = serde_json::to_string_pretty(&current) {
+        let _ = fs::write(WEBSUB_TOPICS_CACHE, json);
+    }
+}
+
+// Publish ping to an external hub, which then fetches the topic itself
+async fn websub_ping(client: &reqwest::Client, hub: &str, topic: &str) -> Result<(), String> {
+    let response = client
+        .post(hub)
+        .form(&[("hub.mode", "publish"), ("hub.url", topic)])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to ping {} for {}: {}", hub, topic, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} rejected the ping for {}: {}", hub, topic, response.status()));
+    }
+    Ok(())
+}
+
+// HMAC-SHA256 (RFC 2104), for X-Hub-Signature
+fn hmac_sha256(key: &[u8], message: &[u8]) -> String {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let inner = Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x36))
+        .chain_update(message)
+        .finalize();
+    let outer = Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x5c))
+        .chain_update(inner)
+        .finalize();
+    format!("{:x}", outer)
+}
+
+// Built-in hub: POST the topic's new document to each subscriber whose lease is still valid
+async fn websub_distribute(client: reqwest::Client, topic: String, body: String, content_type: &'static str) {
+    let now = Local::now().timestamp();
+    let subscribers: Vec<WebSubSubscription> = {
+        let mut subscriptions = WEBSUB_SUBSCRIPTIONS.lock().unwrap();
+        let count = subscriptions.len();
+        subscriptions.retain(|s| s.expires > now);
+        if subscriptions.len() != count {
+            save_websub_subscriptions(&subscriptions);
+        }
+        subscriptions.iter().filter(|s| s.topic == topic).cloned().collect()
+    };
+    if subscribers.is_empty() {
+        return;
+    }
+
+    let hub = format!("{}/websub", SiteConfig::default().url);
+    let gone = websub_deliver(&client, &hub, &topic, &body, content_type, &subscribers, Duration::from_secs(2)).await;
+    if !gone.is_empty() {
+        let mut subscriptions = WEBSUB_SUBSCRIPTIONS.lock().unwrap();
+        subscriptions.retain(|s| !(s.topic == topic && gone.contains(&s.callback)));
+        save_websub_subscriptions(&subscriptions);
+    }
+}
+
+// POST a topic's document to its subscribers; returns the callbacks that answered 410 Gone.
+// Network errors, 429 and 5xx are retried with exponential backoff, starting at `backoff`
+async fn websub_deliver(
+    client: &reqwest::Client,
+    hub: &str,
+    topic: &str,
+    body: &str,
+    content_type: &str,
+    subscribers: &[WebSubSubscription],
+    backoff: Duration,
+) -> Vec<String> {
+    let mut gone = Vec::new();
+    let mut pending: Vec<&WebSubSubscription> = subscribers.iter().collect();
+    for attempt in 1..=WEBSUB_DELIVERY_ATTEMPTS {
+        if attempt > 1 {
+            tokio::time::sleep(backoff * (1 << (attempt - 2))).await;
+        }
+        let mut failed = Vec::new();
+        for subscription in pending {
+            let mut request = client
+                .post(&subscription.callback)
+                .header(header::CONTENT_TYPE, format!("{}; charset=utf-8", content_type))
+                .header(header::LINK, format!("<{}>; rel=\"hub\", <{}>; rel=\"self\"", hub, topic))
+                .body(body.to_string());
+            if let Some(secret) = &subscription.secret {
+                request = request.header("X-Hub-Signature", format!("sha256={}", hmac_sha256(secret.as_bytes(), body.as_bytes())));
+            }
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => continue,
+                // 410 Gone: the subscriber is no longer interested
+                Ok(response) if response.status() == StatusCode::GONE => {
+                    gone.push(subscription.callback.clone());
+                    continue;
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    if status != StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+                        eprintln!("WebSub: {} rejected {}: {}", subscription.callback, topic, status);
+                        continue;
+                    }
+                    status.to_string()
+                }
+                Err(e) => e.to_string(),
+            };
+            if attempt == WEBSUB_DELIVERY_ATTEMPTS {
+                eprintln!("WebSub: Failed to deliver {} to {}: {}", topic, subscription.callback, error);
+            }
+            failed.push(subscription);
+        }
+        if failed.is_empty() {
+            break;
+        }
+        pending = failed;
+    }
+    gone
+}
+
+// Verification of intent: the callback must echo hub.challenge to a GET naming the mode and topic
+async fn websub_confirm(
+    client: &reqwest::Client,
+    mode: &str,
+    subscription: &WebSubSubscription,
+    lease_seconds: i64,
+) -> Result<(), String> {
+    let challenge = Uuid::new_v4().to_string();
+    let mut url = reqwest::Url::parse(&subscription.callback).map_err(|e| e.to_string())?;
+    url.query_pairs_mut()
+        .append_pair("hub.mode", mode)
+        .append_pair("hub.topic", &subscription.topic)
+        .append_pair("hub.challenge", &challenge);
+    if mode == "subscribe" {
+        url.query_pairs_mut().append_pair("hub.lease_seconds", &lease_seconds.to_string());
+    }
+    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("verification returned {}", response.status()));
+    }
+    let echoed = response.text().await.map_err(|e| e.to_string())?;
+    if echoed.trim() != challenge {
+        return Err("verification did not echo the challenge".to_string());
+    }
+    Ok(())
+}
+
+// A (un)subscription takes effect once the callback confirms it
+async fn websub_verify(mode: &'static str, subscription: WebSubSubscription, lease_seconds: i64) {
+    let result = match websub_callback_client() {
+        Ok(client) => websub_confirm(&client, mode, &subscription, lease_seconds).await,
+        Err(e) => Err(e),
+    };
+    WEBSUB_PENDING.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+    if let Err(e) = result {
+        return eprintln!("WebSub: {} of {} to {} not confirmed: {}", mode, subscription.callback, subscription.topic, e);
+    }
+
+    let mut subscriptions = WEBSUB_SUBSCRIPTIONS.lock().unwrap();
+    subscriptions.retain(|s| !(s.topic == subscription.topic && s.callback == subscription.callback));
+    if mode == "subscribe" {
+        if subscriptions.len() >= WEBSUB_MAX_SUBSCRIPTIONS {
+            return eprintln!("WebSub: dropping subscription of {} to {}: the hub is full", subscription.callback, subscription.topic);
+        }
+        subscriptions.push(subscription);
+    }
+    save_websub_subscriptions(&subscriptions);
+}
+
+fn websub_error(message: &str) -> Response {
+    (StatusCode::BAD_REQUEST, message.to_string()).into_response()
+}
+
+// Built-in hub endpoint (WEBSUB_HUB=builtin): subscribe and unsubscribe requests as form posts.
+// Publishing is this server's own job after content changes, so publish requests are refused
+async fn websub_hub(Form(form): Form<HashMap<String, String>>) -> Response {
+    if !matches!(*WEBSUB_HUB, Some(WebSubHub::Builtin)) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let field = |name: &str| form.get(name).map(|v| v.trim()).filter(|v| !v.is_empty());
+    let Some(mode) = field("hub.mode") else {
+        return websub_error("hub.mode is required");
+    };
+    let mode = match mode {
+        "subscribe" => "subscribe",
+        "unsubscribe" => "unsubscribe",
+        "publish" => return (StatusCode::FORBIDDEN, "This hub publishes its own feeds when they change").into_response(),
+        _ => return websub_error("hub.mode must be subscribe or unsubscribe"),
+    };
+    let Some(topic) = field("hub.topic").map(str::to_string) else {
+        return websub_error("hub.topic is required");
+    };
+    if !WEBSUB_TOPICS.lock().unwrap().contains(&topic) {
+        return websub_error("hub.topic is not a feed on this site");
+    }
+    let callback = match field("hub.callback").map(websub_callback) {
+        Some(Ok(callback)) => callback,
+        Some(Err(message)) => return websub_error(message),
+        None => return websub_error("hub.callback is required"),
+    };
+    let secret = field("hub.secret").map(str::to_string);
+    if secret.as_ref().is_some_and(|s| s.len() >= 200) {
+        return websub_error("hub.secret must be under 200 bytes");
+    }
+    let lease_seconds = field("hub.lease_seconds")
+        .and_then(|l| l.parse::<i64>().ok())
+        .filter(|l| *l > 0)
+        .map_or(*WEBSUB_LEASE_SECONDS, |l| l.min(*WEBSUB_LEASE_SECONDS));
+
+    let full = {
+        let subscriptions = WEBSUB_SUBSCRIPTIONS.lock().unwrap();
+        let renewal = subscriptions.iter().any(|s| s.topic == topic && s.callback == callback.as_str());
+        mode == "subscribe" && !renewal && subscriptions.len() >= WEBSUB_MAX_SUBSCRIPTIONS
+    };
+    if full {
+        return (StatusCode::SERVICE_UNAVAILABLE, "This hub has no room for more subscriptions").into_response();
+    }
+    // Each request makes the hub send one; bound how many can be in flight
+    if WEBSUB_PENDING.fetch_add(1, std::sync::atomic::Ordering::SeqCst) >= WEBSUB_MAX_PENDING {
+        WEBSUB_PENDING.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+        return (StatusCode::TOO_MANY_REQUESTS, "Too many verifications in progress").into_response();
+    }
+
+    let subscription = WebSubSubscription {
+        topic,
+        callback: callback.to_string(),
+        secret,
+        expires: Local::now().timestamp() + lease_seconds,
+    };
+    tokio::spawn(websub_verify(mode, subscription, lease_seconds));
+    StatusCode::ACCEPTED.into_response()
+}
+
+// ============================================================================
+// IndexNow - tell search engines about changed pages without waiting for a crawl
+// ============================================================================
+
+// Most URLs one request may carry (https://www.indexnow.org/documentation)
+const INDEXNOW_BATCH: usize = 10_000;
+// Tries per batch; waits 2s, 4s, 8s, ... between them
+const INDEXNOW_ATTEMPTS: u32 = 4;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexNowRequest<'a> {
+    host: &'a str,
+    key: &'a str,
+    key_location: &'a str,
+    url_list: &'a [String],
+}
+
+// Where submissions go and what they carry
+struct IndexNowOptions {
+    endpoint: String,
+    host: String,
+    key: String,
+    key_location: String,
+    backoff: Duration, // Wait before the first retry; doubles after each one
+}
+
+impl IndexNowOptions {
+    fn from_env(key: &str, site_config: &SiteConfig) -> Result<Self, String> {
+        let host = reqwest::Url::parse(&site_config.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .ok_or_else(|| format!("no host in the site URL {}", site_config.url))?;
+        Ok(Self {
+            endpoint: INDEXNOW_ENDPOINT.clone(),
+            host,
+            key: key.to_string(),
+            key_location: format!("{}/{}.txt", site_config.url, key),
+            backoff: Duration::from_secs(2),
+        })
+    }
+}
+
+// One batch as sent, for the admin log
+#[derive(Clone, Serialize, Deserialize)]
+struct IndexNowSubmission {
+    time: i64, // Unix seconds
+    urls: Vec<String>,
+    attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<u16>, // HTTP status of the last attempt, None when it never got a response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// Hash of every indexable page: each post, each tag page and the home page, which change with their
+// posts. Only what the pages show counts, so a post is resubmitted when its text changes, not its file
+fn indexnow_pages(posts: &[Post], site_config: &SiteConfig) -> HashMap<String, String> {
+    let mut pages = HashMap::new();
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    let mut all = Vec::new();
+    for post in posts {
+        let hash = etag_for(&[&post.title, &post.summary, &post.content]);
+        for tag in &post.tags {
+            tags.entry(tag.clone()).or_default().push(hash.clone());
+        }
+        all.push(hash.clone());
+        pages.insert(format!("{}/blog/{}", site_config.url, post.slug), hash);
+    }
+    for (tag, hashes) in tags {
+        let hashes: Vec<&str> = hashes.iter().map(String::as_str).collect();
+        pages.insert(format!("{}/tags/{}", site_config.url, url_segment(&tag)), etag_for(&hashes));
+    }
+    let all: Vec<&str> = all.iter().map(String::as_str).collect();
+    pages.insert(format!("{}/", site_config.url), etag_for(&all));
+    pages
+}
+
+// Pages added or changed since `previous`, and pages removed since, so engines recrawl them and drop the 404s
+fn indexnow_changes(previous: &HashMap<String, String>, current: &HashMap<String, String>) -> Vec<String> {
+    let mut changed: Vec<String> = current
+        .iter()
+        .filter(|(url, hash)| previous.get(*url) != Some(*hash))
+        .map(|(url, _)| url.clone())
+        .chain(previous.keys().filter(|url| !current.contains_key(*url)).cloned())
+        .collect();
+    changed.sort();
+    changed
+}
+
+fn save_indexnow_log(log: &[IndexNowSubmission]) {
+    let _ = fs::create_dir_all(".cache");
+    if let Ok(json) = serde_json::to_string_pretty(log) {
+        let _ = fs::write(INDEXNOW_LOG_FILE, json);
+    }
+}
+
+fn indexnow_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent("axum-blog")
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// After a content change: submit every page added, changed or removed since the last submission.
+// Without `submit`, only record the current pages as the baseline later changes are measured against
+async fn indexnow_submit_changes(submit: bool) {
+    let Some(key) = INDEXNOW_KEY.as_deref() else {
+        return;
+    };
+    let _running = INDEXNOW_RUNNING.lock().await;
+    let site_config = SiteConfig::default();
+    let options = match IndexNowOptions::from_env(key, &site_config) {
+        Ok(options) => options,
+        Err(e) => return eprintln!("IndexNow: {}", e),
+    };
+    let Ok((previous, mut current)) = tokio::task::spawn_blocking(move || {
+        let previous: HashMap<String, String> = fs::read_to_string(INDEXNOW_URLS_CACHE)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        (previous, indexnow_pages(&get_posts(&site_config), &site_config))
+    })
+    .await
+    else {
+        return;
+    };
+
+    let changed = indexnow_changes(&previous, &current);
+    if changed.is_empty() {
+        return;
+    }
+    if submit {
+        let client = match indexnow_client() {
+            Ok(client) => client,
+            Err(e) => return eprintln!("IndexNow: {}", e),
+        };
+        for submission in indexnow_submit(&client, &options, &changed).await {
+            if let Some(e) = &submission.error {
+                eprintln!("IndexNow: {} URLs not submitted: {}", submission.urls.len(), e);
+                // Keep the old hashes so the next change retries these pages
+                for url in &submission.urls {
+                    match previous.get(url) {
+                        Some(hash) => current.insert(url.clone(), hash.clone()),
+                        None => current.remove(url),
+                    };
+                }
+            } else {
+                println!("IndexNow: submitted {} URLs", submission.urls.len());
+            }
+            let mut log = INDEXNOW_LOG.lock().unwrap();
+            log.insert(0, submission);
+            log.truncate(INDEXNOW_LOG_LIMIT);
+            save_indexnow_log(&log);
+        }
+    }
+    let _ = fs::create_dir_all(".cache");
+    if let Ok(json) = serde_json::to_string_pretty(&current) {
+        let _ = fs::write(INDEXNOW_URLS_CACHE, json);
+    }
+}
+
+// Submit `urls` in batches of INDEXNOW_BATCH, one submission per batch
+async fn indexnow_submit(client: &reqwest::Client, options: &IndexNowOptions, urls: &[String]) -> Vec<IndexNowSubmission> {
+    let mut submissions = Vec::new();
+    for batch in urls.chunks(INDEXNOW_BATCH) {
+        submissions.push(indexnow_send(client, options, batch).await);
+    }
+    submissions
+}
+
+// POST one batch, retrying with exponential backoff on network errors, 429 and 5xx; other errors are final
+async fn indexnow_send(client: &reqwest::Client, options: &IndexNowOptions, urls: &[String]) -> IndexNowSubmission {
+    let request = IndexNowRequest { host: &options.host, key: &options.key, key_location: &options.key_location, url_list: urls };
+    let mut submission = IndexNowSubmission {
+        time: Local::now().timestamp(),
+        urls: urls.to_vec(),
+        attempts: 0,
+        status: None,
+        error: None,
+    };
+    for attempt in 1..=INDEXNOW_ATTEMPTS {
+        if attempt > 1 {
+            tokio::time::sleep(options.backoff * (1 << (attempt - 2))).await;
+        }
+        submission.attempts = attempt;
+        let retry = match client.post(&options.endpoint).json(&request).send().await {
+            Ok(response) => {
+                let status = response.status();
+                submission.status = Some(status.as_u16());
+                if status.is_success() {
+                    submission.error = None;
+                    return submission;
+                }
+                submission.error = Some(match status {
+                    StatusCode::BAD_REQUEST => "400 Bad Request: invalid format".to_string(),
+                    StatusCode::FORBIDDEN => "403 Forbidden: key not valid or key file not found".to_string(),
+                    StatusCode::UNPROCESSABLE_ENTITY => "422 Unprocessable Entity: URLs don't belong to the host".to_string(),
+                    status => status.to_string(),
+                });
+                status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+            Err(e) => {
+                submission.status = None;
+                submission.error = Some(e.to_string());
+                true
+            }
+        };
+        if !retry {
+            break;
+        }
+    }
+    submission
+}
+
+// The key file engines fetch to check that submissions come from the site's owner
+async fn indexnow_key_file() -> Response {
+    match INDEXNOW_KEY.as_deref() {
+        Some(key) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(key.to_string().into())
+            .unwrap(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+// ============================================================================
+// Link Checker - verify internal and external links in rendered posts
+// ============================================================================
+
+// A link that doesn't resolve, with where it was found
+#[derive(Debug, Clone, Serialize)]
+struct BrokenLink {
+    post: String,
+    file: String,
+    line: Option<usize>, // 1-based line in the source file, when the URL can be found there
+    url: String,
+    reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LinkReport {
+    posts: usize,
+    links: usize,
+    external: usize,
+    broken: Vec<BrokenLink>,
+}
+
+// State of the admin link check; `report` is the last finished run's, kept while a new one runs
+#[derive(Debug, Default, Clone, Serialize)]
+struct LinkCheckJob {
+    running: bool,
+    external: bool,
+    checked: usize, // Posts checked so far
+    total: usize,
+    started_at: i64, // Unix seconds
+    finished_at: Option<i64>,
+    report: Option<LinkReport>,
+}
+
+// Cached outcome of an external check: None means the URL resolved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExternalCheck {
+    error: Option<String>,
+    checked_at: u64, // Unix seconds
+}
+
+// Links (a[href]), images (img[src]) and element ids in rendered HTML, entity-decoded
+fn extract_links(html: &str) -> (Vec<String>, Vec<String>, std::collections::HashSet<String>) {
+    let mut links = Vec::new();
+    let mut images = Vec::new();
+    let mut ids = std::collections::HashSet::new();
+    let mut rest = html;
+    while let Some(lt) = rest.find('<') {
+        rest = &rest[lt..];
+        let end = match find_tag_end(rest) {
+            Some(end) => end + 1,
+            None => break,
+        };
+        let tag = parse_tag(&rest[..end]);
+        rest = &rest[end..];
+        if tag.closing {
+            continue;
+        }
+        let name = tag.name.to_lowercase();
+        for (key, value) in &tag.attrs {
+            let Some(value) = value else { continue };
+            let value = html_escape::decode_html_entities(value).into_owned();
+            match (name.as_str(), key.to_lowercase().as_str()) {
+                (_, "id") => {
+                    ids.insert(value);
+                }
+                ("a", "href") if !value.is_empty() && !links.contains(&value) => links.push(value),
+                ("img", "src") if !value.is_empty() => images.push(value),
+                _ => {}
+            }
+        }
+    }
+    (links, images, ids)
+}
+
+// Decode %XX escapes in a URL path segment
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// Line of the source file where a link appears; rewritten URLs are looked up by their last segment
+fn source_line(source: &str, url: &str) -> Option<usize> {
+    let body_start = source
+        .strip_prefix("---")
+        .and_then(|rest| rest.find("---").map(|end| end + 6))
+        .unwrap_or(0)
+        .min(source.len());
+    let last_segment = url.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+    [url, last_segment]
+        .iter()
+        .filter(|needle| !needle.is_empty())
+        .find_map(|needle| source[body_start..].find(needle))
+        .map(|pos| source[..body_start + pos].lines().count().max(1))
+}
+
+// Why an internal link doesn't resolve, or None if it does
+fn check_internal_link(
+    url: &str,
+    page_ids: &std::collections::HashSet<String>,
+    posts: &[Post],
+    post_ids: &HashMap<String, std::collections::HashSet<String>>,
+) -> Option<String> {
+    let (path, anchor) = match url.split_once('#') {
+        Some((path, anchor)) => (path, Some(percent_decode(anchor))),
+        None => (url, None),
+    };
+    let path = path.split('?').next().unwrap_or("");
+
+    // Same-page anchor
+    if path.is_empty() {
+        let anchor = anchor.unwrap_or_default();
+        return (!anchor.is_empty() && !page_ids.contains(&anchor))
+            .then(|| format!("no element with id '{}' on this page", anchor));
+    }
+
+    let segments: Vec<String> = path.trim_matches('/').split('/').map(percent_decode).collect();
+    match segments.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["blog"] | [""] => None,
+        ["blog", slug] => match post_ids.get(*slug) {
+            None => Some(format!("no post with slug '{}'", slug)),
+            Some(ids) => anchor
+                .filter(|a| !a.is_empty() && !ids.contains(a))
+                .map(|a| format!("post '{}' has no element with id '{}'", slug, a)),
+        },
+        ["blog", slug, "og.png"] => (!post_ids.contains_key(*slug)).then(|| format!("no post with slug '{}'", slug)),
+        ["blog", slug, ..] => {
+            let asset = segments[2..].join("/");
+            match find_bundle_dir(slug) {
+                Some(dir) if dir.join(&asset).is_file() => None,
+                Some(_) => Some(format!("bundle '{}' has no file '{}'", slug, asset)),
+                None => Some(format!("no page bundle for '{}'", slug)),
+            }
+        }
+        ["tags", tag] => {
+            let tag = tag.to_lowercase();
+            let used = posts.iter().any(|p| p.tags.iter().any(|t| t.to_lowercase() == tag));
+            (!used).then(|| format!("no posts tagged '{}'", tag))
+        }
+        ["images", ..] => resolve_local_image(path).is_none().then(|| "image not found".to_string()),
+        // Other site routes (feeds, sitemap, generated media) aren't checked
+        _ => None,
+    }
+}
+
+enum ExternalOutcome {
+    Ok,
+    Broken(String),       // Definitive (an HTTP error status); cached
+    Unreachable(String),  // Timeout or connection failure; reported but retried next run
+    Inconclusive(String), // Rate limited; neither reported nor cached
+}
+
+// HEAD an external URL, falling back to GET for servers that reject HEAD
+async fn check_external_link(client: &reqwest::Client, url: &str) -> ExternalOutcome {
+    let mut response = client.head(url).send().await;
+    if let Ok(r) = &response {
+        if matches!(r.status().as_u16(), 403 | 405 | 501) {
+            response = client.get(url).send().await;
+        }
+    }
+    match response {
+        Ok(r) if r.status().as_u16() == 429 => ExternalOutcome::Inconclusive(format!("rate limited by {}", url)),
+        Ok(r) if r.status().is_client_error() || r.status().is_server_error() => {
+            ExternalOutcome::Broken(format!("HTTP {}", r.status()))
+        }
+        Ok(_) => ExternalOutcome::Ok,
+        Err(e) if e.is_timeout() => ExternalOutcome::Unreachable("timed out".to_string()),
+        Err(e) => ExternalOutcome::Unreachable(format!("request failed: {}", e)),
+    }
+}
+
+// How check_links treats external URLs
+struct LinkCheckOptions {
+    external: bool,
+    interval: Duration, // Between two requests to the same host
+    cache_file: PathBuf,
+    cache_ttl: u64, // Seconds
+}
+
+impl LinkCheckOptions {
+    fn from_env(external: bool) -> Self {
+        LinkCheckOptions {
+            external,
+            interval: Duration::from_millis(*LINK_CHECK_INTERVAL_MS),
+            cache_file: PathBuf::from(LINK_CHECK_CACHE),
+            cache_ttl: *LINK_CHECK_CACHE_HOURS * 3600,
+        }
+    }
+}
+
+// Check every link in every post, calling `progress` with the number of posts done after each.
+// External URLs are checked one at a time, at most one request per host every `interval`, and
+// results are cached on disk for `cache_ttl`
+async fn check_links(posts: &[Post], site_url: &str, options: &LinkCheckOptions, progress: impl Fn(usize)) -> LinkReport {
+    let external = options.external;
+    let post_ids: HashMap<String, std::collections::HashSet<String>> =
+        posts.iter().map(|p| (p.slug.clone(), p.ids.clone())).collect();
+
+    let mut cache: HashMap<String, ExternalCheck> = fs::read_to_string(&options.cache_file)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let ttl = options.cache_ttl;
+    let client = reqwest::Client::builder()
+        .user_agent("axum-blog-link-checker")
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+    let mut last_request: HashMap<String, Instant> = HashMap::new();
+
+    let mut report = LinkReport { posts: posts.len(), links: 0, external: 0, broken: Vec::new() };
+    for (done, post) in posts.iter().enumerate() {
+        progress(done);
+        let source = fs::read_to_string(PathBuf::from("content").join(&post.filename)).unwrap_or_default();
+        let mut images = post.images.clone();
+        images.dedup();
+        for link in post.links.iter().chain(&images) {
+            report.links += 1;
+            // Absolute links to this site are checked as internal ones
+            let url = match link.strip_prefix(site_url.trim_end_matches('/')) {
+                Some(path) if path.is_empty() || path.starts_with(['/', '#', '?']) => path,
+                _ => link.as_str(),
+            };
+
+            let reason = if url.starts_with('/') || url.starts_with('#') || url.is_empty() {
+                check_internal_link(url, &post.ids, posts, &post_ids)
+            } else if url.starts_with("http://") || url.starts_with("https://") {
+                if !external {
+                    continue;
+                }
+                report.external += 1;
+                match cache.get(url).filter(|c| now.saturating_sub(c.checked_at) < ttl) {
+                    Some(cached) => cached.error.clone(),
+                    None => {
+                        let host = reqwest::Url::parse(url)
+                            .ok()
+                            .and_then(|u| u.host_str().map(str::to_string))
+                            .unwrap_or_default();
+                        if let Some(last) = last_request.get(&host) {
+                            let wait = options.interval.saturating_sub(last.elapsed());
+                            tokio::time::sleep(wait).await;
+                        }
+                        let outcome = check_external_link(&client, url).await;
+                        last_request.insert(host, Instant::now());
+                        match outcome {
+                            ExternalOutcome::Ok => {
+                                cache.insert(url.to_string(), ExternalCheck { error: None, checked_at: now });
+                                None
+                            }
+                            ExternalOutcome::Broken(reason) => {
+                                cache.insert(url.to_string(), ExternalCheck { error: Some(reason.clone()), checked_at: now });
+                                Some(reason)
+                            }
+                            ExternalOutcome::Unreachable(reason) => Some(reason),
+                            ExternalOutcome::Inconclusive(reason) => {
+                                eprintln!("Link check: {}", reason);
+                                None
+                            }
+                        }
+                    }
+                }
+            } else {
+                // mailto:, tel:, data: and other schemes
+                None
+            };
+
+            if let Some(reason) = reason {
+                report.broken.push(BrokenLink {
+                    post: post.title.clone(),
+                    file: post.filename.clone(),
+                    line: source_line(&source, link).or_else(|| source_line(&source, url)),
+                    url: link.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    progress(posts.len());
+
+    if external {
+        if let Some(dir) = options.cache_file.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        match serde_json::to_string(&cache) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&options.cache_file, json) {
+                    eprintln!("Failed to write {}: {}", options.cache_file.display(), e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize link check cache: {}", e),
+        }
+    }
+    report
+}
+
+// ============================================================================
+// GitHub Integration - Fetch READMEs from repositories
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct GitHubRepo {
+    name: String,
+    full_name: String,
+    description: Option<String>,
+    html_url: String,
+    pushed_at: Option<String>,
+    updated_at: Option<String>,
+    language: Option<String>,
+    stargazers_count: u32,
+    fork: bool,
+    archived: bool,
+    topics: Option<Vec<String>>,
+    homepage: Option<String>,
+    default_branch: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum GitHubImages {
+    Remote, // Link relative images to raw.githubusercontent.com
+    Bundle, // Download relative images into the post's page bundle
+    Mirror, // Download every image into content/images/github, deduplicated by content hash
+}
+
+impl GitHubImages {
+    fn from_env() -> Self {
+        match env::var("GITHUB_IMAGES").map(|v| v.trim().to_lowercase()).as_deref() {
+            Ok("remote") | Err(_) => Self::Remote,
+            Ok("bundle") => Self::Bundle,
+            Ok("mirror") => Self::Mirror,
+            Ok(other) => {
+                eprintln!("Unknown GITHUB_IMAGES value '{}', using remote", other);
+                Self::Remote
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubReadme {
+    content: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SyncResult {
+    success: bool,
+    message: String,
+    repos_synced: Vec<String>,
+    errors: Vec<String>,
+}
+
+// Fetch all public repos for the configured GitHub user
+async fn fetch_github_repos() -> Result<Vec<GitHubRepo>, String> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/users/{}/repos?sort=updated&per_page=100", GITHUB_API_BASE, GITHUB_USERNAME.as_str());
+    
+    let mut request = client
+        .get(&url)
+        .header("User-Agent", "axum-blog")
+        .header("Accept", "application/vnd.github.v3+json");
+    
+    if let Some(token) = GITHUB_TOKEN.as_ref() {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch repos: {}", e))?;
+    
+    if !response.status().is_success() {
+        return Err(format!("GitHub API error: {}", response.status()));
+    }
+    
+    let repos: Vec<GitHubRepo> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse repos: {}", e))?;
+    
+    // Filter out forks and archived repos
+    Ok(repos.into_iter().filter(|r| !r.fork && !r.archived).collect())
+}
+
+// Fetch one repository by owner/name
+async fn fetch_github_repo(full_name: &str) -> Result<GitHubRepo, String> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/repos/{}", GITHUB_API_BASE, full_name);
+    
+    let mut request = client
+        .get(&url)
+        .header("User-Agent", "axum-blog")
+        .header("Accept", "application/vnd.github.v3+json");
+    
+    if let Some(token) = GITHUB_TOKEN.as_ref() {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch repo: {}", e))?;
+    
+    if !response.status().is_success() {
+        return Err(format!("GitHub API error: {}", response.status()));
+    }
+    
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse repo: {}", e))
+}
+
+// Fetch README content for a specific repo
+async fn fetch_readme(repo_name: &str) -> Result<String, String> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/repos/{}/{}/readme", GITHUB_API_BASE, GITHUB_USERNAME.as_str(), repo_name);
+    
+    let mut request = client
+        .get(&url)
+        .header("User-Agent", "axum-blog")
+        .header("Accept", "application/vnd.github.v3+json");
+    
+    if let Some(token) = GITHUB_TOKEN.as_ref() {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch README: {}", e))?;
+    
+    if !response.status().is_success() {
+        return Err(format!("No README found for {}", repo_name));
+    }
+    
+    let readme: GitHubReadme = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse README: {}", e))?;
+    
+    if let Some(content) = readme.content {
+        // GitHub returns base64 encoded content
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(content.replace('\n', ""))
+            .map_err(|e| format!("Failed to decode README: {}", e))?;
+        
+        String::from_utf8(decoded)
+            .map_err(|e| format!("Invalid UTF-8 in README: {}", e))
+    } else {
+        Err("README content is empty".to_string())
+    }
+}
+
+// Create a blog post from a GitHub repo README
+fn create_post_from_readme(repo: &GitHubRepo, readme_content: &str) -> String {
+    let date = repo.pushed_at.as_ref()
+        .or(repo.updated_at.as_ref())
+        .map(|d| d.split('T').next().unwrap_or("2025-01-01"))
+        .unwrap_or("2025-01-01");
+    
+    let description = repo.description.as_deref().unwrap_or("A GitHub project");
+    
+    let mut tags = vec!["github".to_string(), "project".to_string()];
+    if let Some(lang) = &repo.language {
+        tags.push(lang.to_lowercase());
+    }
+    if let Some(topics) = &repo.topics {
+        for topic in topics.iter().take(3) {
+            tags.push(topic.clone());
+        }
+    }
+
+    let homepage = repo
+        .homepage
+        .as_deref()
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .unwrap_or("");
+    let website_line = if homepage.is_empty() {
+        String::new()
+    } else {
+        format!("\nwebsite: \"{}\"", homepage)
+    };
+    
+    let tags_str = tags.iter()
+        .map(|t| format!("\"{}\"", t))
+        .collect::<Vec<_>>()
+        .join(", ");
+    
+    format!(
+        r#"---
+title: "{}"
+date: "{}"
+tags: [{}]
+summary: "{}"
+author: "{}"
+keywords: "{}, github, open source"
+github_repo: "{}"{website}
+---
+
+{}
+"#,
+        repo.name,
+        date,
+        tags_str,
+        description.replace('"', "'"),
+        GITHUB_USERNAME.as_str(),
+        repo.name,
+        repo.full_name,
+        readme_content,
+        website = website_line
+    )
+}
+
+// Rewrite every link and image URL in Markdown, including <a>, <img> and <source> in raw HTML.
+// `rewrite(url, is_image)` returns the replacement, or None to leave the URL as written
+fn rewrite_markdown_urls(markdown: &str, mut rewrite: impl FnMut(&str, bool) -> Option<String>) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_GFM;
+    let parser = Parser::new_ext(markdown, options);
+    // Reference definitions are rewritten once all uses are known, so images can be told from links
+    let definitions: Vec<(String, String, std::ops::Range<usize>)> = parser
+        .reference_definitions()
+        .iter()
+        .map(|(label, def)| (label.to_lowercase(), def.dest.to_string(), def.span.clone()))
+        .collect();
+    let (events, ranges): (Vec<Event>, Vec<std::ops::Range<usize>>) = parser.into_offset_iter().unzip();
+    let mut image_labels = Vec::new();
+    let mut edits: Vec<(std::ops::Range<usize>, String)> = Vec::new();
+
+    let mut i = 0;
+    while i < events.len() {
+        let range = ranges[i].clone();
+        match &events[i] {
+            Event::Start(tag @ (Tag::Link { link_type, dest_url, id, .. } | Tag::Image { link_type, dest_url, id, .. })) => {
+                let is_image = matches!(tag, Tag::Image { .. });
+                match link_type {
+                    pulldown_cmark::LinkType::Inline => {
+                        // The destination follows the `](` that closes the text, which ends where the last event
+                        // inside the link does (the text may itself hold brackets, code or a nested image)
+                        let end = matching_end(&events, i);
+                        let text_end = if end > i + 1 { ranges[end - 1].end } else { range.start + 1 };
+                        let dest = markdown[text_end..range.end]
+                            .find("](")
+                            .and_then(|pos| link_destination(markdown, text_end + pos + 2));
+                        if let (Some(dest), Some(url)) = (dest, rewrite(dest_url, is_image)) {
+                            edits.push(destination_edit(dest, url));
+                        }
+                    }
+                    pulldown_cmark::LinkType::Reference
+                    | pulldown_cmark::LinkType::Collapsed
+                    | pulldown_cmark::LinkType::Shortcut
+                        if is_image =>
+                    {
+                        image_labels.push(id.to_lowercase());
+                    }
+                    _ => {}
+                }
+            }
+            // A block is rewritten whole, since one tag may span several of its lines
+            Event::Start(Tag::HtmlBlock) => {
+                if let Some(html) = rewrite_html_urls(&markdown[range.clone()], &mut rewrite) {
+                    edits.push((range, html));
+                }
+                i = matching_end(&events, i);
+            }
+            Event::InlineHtml(_) => {
+                if let Some(html) = rewrite_html_urls(&markdown[range.clone()], &mut rewrite) {
+                    edits.push((range, html));
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    for (label, dest, span) in definitions {
+        let is_image = image_labels.contains(&label);
+        // `[label]:` - labels can't hold unescaped brackets, so the first `]:` ends it
+        let destination = markdown[span.clone()]
+            .find("]:")
+            .and_then(|pos| link_destination(markdown, span.start + pos + 2));
+        if let (Some(destination), Some(url)) = (destination, rewrite(&dest, is_image)) {
+            edits.push(destination_edit(destination, url));
+        }
+    }
+
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut out = String::with_capacity(markdown.len());
+    let mut last = 0;
+    for (range, replacement) in edits {
+        if range.start < last {
+            continue;
+        }
+        out.push_str(&markdown[last..range.start]);
+        out.push_str(&replacement);
+        last = range.end;
+    }
+    out.push_str(&markdown[last..]);
+    out
+}
+
+// A link destination as written in the source (CommonMark 6.3): `<...>`, or a run of non-space characters
+// with balanced parentheses and backslash escapes
+struct LinkDestination {
+    range: std::ops::Range<usize>, // Without the angle brackets
+    angle: bool,
+}
+
+// The destination starting at `start` (just after `(` or `]:`), skipping the whitespace before it
+fn link_destination(markdown: &str, start: usize) -> Option<LinkDestination> {
+    let rest = &markdown[start..];
+    let begin = start + (rest.len() - rest.trim_start().len());
+    let source = &markdown[begin..];
+
+    if let Some(inner) = source.strip_prefix('<') {
+        let mut escaped = false;
+        for (i, c) in inner.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '>' => return Some(LinkDestination { range: begin + 1..begin + 1 + i, angle: true }),
+                '<' | '\n' => return None,
+                _ => {}
+            }
+        }
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut escaped = false;
+    let mut end = source.len();
+    for (i, c) in source.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                end = i;
+                break;
+            }
+            ')' => depth -= 1,
+            c if c.is_whitespace() || c.is_control() => {
+                end = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+    (end > 0).then_some(LinkDestination { range: begin..begin + end, angle: false })
+}
+
+// Replace a destination with a URL, in angle brackets when it has characters a bare destination can't
+fn destination_edit(destination: LinkDestination, url: String) -> (std::ops::Range<usize>, String) {
+    let needs_angle = url.chars().any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '<' | '>'));
+    let url = if needs_angle { url.replace('<', "%3C").replace('>', "%3E") } else { url };
+    match (destination.angle, needs_angle) {
+        (false, true) => (destination.range, format!("<{}>", url)),
+        _ => (destination.range, url),
+    }
+}
+
+// Rewrite href/src/srcset URLs of <a>, <img> and <source> tags in an HTML fragment; None if nothing changed
+fn rewrite_html_urls(html: &str, rewrite: &mut impl FnMut(&str, bool) -> Option<String>) -> Option<String> {
+    let mut out = String::with_capacity(html.len());
+    let mut changed = false;
+    let mut rest = html;
+    while let Some(lt) = rest.find('<') {
+        out.push_str(&rest[..lt]);
+        rest = &rest[lt..];
+        let end = match find_tag_end(rest) {
+            Some(end) => end + 1,
+            None => break,
+        };
+        let mut tag = parse_tag(&rest[..end]);
+        let name = tag.name.to_lowercase();
+        let mut tag_changed = false;
+        for (key, value) in tag.attrs.iter_mut() {
+            let Some(v) = value.as_mut() else { continue };
+            let new_value = match (name.as_str(), key.to_lowercase().as_str()) {
+                ("a", "href") => rewrite(v, false),
+                ("img", "src") | ("source", "src") => rewrite(v, true),
+                ("img", "srcset") | ("source", "srcset") => {
+                    let candidates: Vec<String> = v
+                        .split(',')
+                        .map(|candidate| {
+                            let candidate = candidate.trim();
+                            let (url, descriptor) = candidate.split_once(' ').unwrap_or((candidate, ""));
+                            let url = rewrite(url, true).unwrap_or_else(|| url.to_string());
+                            format!("{} {}", url, descriptor).trim_end().to_string()
+                        })
+                        .collect();
+                    Some(candidates.join(", ")).filter(|joined| joined != v)
+                }
+                _ => None,
+            };
+            if let Some(new_value) = new_value {
+                *v = new_value;
+                tag_changed = true;
+            }
+        }
+        if tag_changed {
+            out.push_str(&render_tag(&tag));
+            changed = true;
+        } else {
+            out.push_str(&rest[..end]);
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    changed.then_some(out)
+}
+
+// Path of a README-relative URL within the repo (query and fragment kept); None for absolute URLs and anchors
+fn repo_relative_path(url: &str) -> Option<&str> {
+    if url.is_empty() || url.starts_with('#') || url.starts_with("//") {
+        return None;
+    }
+    let end = url.find(['?', '#']).unwrap_or(url.len());
+    // Anything with a scheme (https:, mailto:, data:, ...) is already absolute
+    if url[..end].split('/').next().unwrap_or("").contains(':') {
+        return None;
+    }
+    let path = url.trim_start_matches("./").trim_start_matches('/');
+    (!path.is_empty()).then_some(path)
+}
+
+// Where a README-relative URL points on GitHub: raw file for images, blob page for links
+fn github_url(url: &str, repo: &GitHubRepo, is_image: bool) -> Option<String> {
+    let path = repo_relative_path(url)?;
+    let branch = repo.default_branch.as_deref().unwrap_or("HEAD");
+    Some(if is_image {
+        format!("https://raw.githubusercontent.com/{}/{}/{}", repo.full_name, branch, path)
+    } else {
+        format!("https://github.com/{}/blob/{}/{}", repo.full_name, branch, path)
+    })
+}
+
+// A relative image path that can be stored inside a bundle (no `..`, query or fragment)
+fn bundle_image_path(url: &str) -> Option<String> {
+    let path = repo_relative_path(url)?;
+    let path = &path[..path.find(['?', '#']).unwrap_or(path.len())];
+    let valid = !path.is_empty()
+        && PathBuf::from(path).components().all(|c| matches!(c, Component::Normal(_)));
+    valid.then(|| path.to_string())
+}
+
+// Download a file of at most MAX_IMAGE_DOWNLOAD bytes, authenticating to GitHub when a token is configured
+async fn download_bytes(url: &str) -> Result<Vec<u8>, String> {
+    let client = reqwest::Client::new();
+    let mut request = client.get(url).header("User-Agent", "axum-blog");
+    if url.starts_with("https://raw.githubusercontent.com/") {
+        if let Some(token) = GITHUB_TOKEN.as_ref() {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch {}: {}", url, response.status()));
+    }
+    let too_large = || format!("{} is larger than {} bytes", url, MAX_IMAGE_DOWNLOAD);
+    if response.content_length().is_some_and(|len| len > MAX_IMAGE_DOWNLOAD as u64) {
+        return Err(too_large());
+    }
+
+    // Content-Length may be missing or wrong, so enforce the limit on the body as it arrives
+    let mut response = response;
+    let mut data = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", url, e))?
+    {
+        if data.len() + chunk.len() > MAX_IMAGE_DOWNLOAD {
+            return Err(too_large());
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+// One mirrored image in the manifest: every URL it was found at, and which repos use it
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MirroredImage {
+    origins: Vec<String>,
+    repos: Vec<String>,
+    size: usize,
+    fetched_at: String,
+}
+
+// Absolute URL to fetch for an image in a README: relative paths resolve to the raw file on GitHub
+fn mirror_source_url(url: &str, repo: &GitHubRepo) -> Option<String> {
+    // URLs from raw HTML attributes are still entity-encoded
+    let url = html_escape::decode_html_entities(url);
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Some(url.into_owned())
+    } else {
+        github_url(&url, repo, true)
+    }
+}
+
+// File extension for downloaded image data, from its content or, failing that, its URL
+fn image_extension(data: &[u8], url: &str) -> Option<String> {
+    if let Ok(format) = image::guess_format(data) {
+        return format.extensions_str().first().map(|e| e.to_string());
+    }
+    let head = String::from_utf8_lossy(&data[..data.len().min(512)]).to_lowercase();
+    if head.contains("<svg") {
+        return Some("svg".to_string());
+    }
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    let ext = path.rsplit('/').next()?.rsplit_once('.')?.1.to_lowercase();
+    ["png", "jpg", "jpeg", "gif", "webp", "avif", "svg", "ico"]
+        .contains(&ext.as_str())
+        .then_some(ext)
+}
+
+// Download an image into MIRROR_DIR (once per distinct content) and return its local URL
+async fn mirror_image(url: &str, repo: &GitHubRepo) -> Result<String, String> {
+    let data = download_bytes(url).await?;
+    let ext = image_extension(&data, url).ok_or_else(|| format!("{} is not an image", url))?;
+    let hash = format!("{:x}", Sha256::digest(&data));
+    let file = format!("{}.{}", &hash[..16], ext);
+
+    let path = PathBuf::from(MIRROR_DIR).join(&file);
+    if !path.is_file() {
+        fs::create_dir_all(MIRROR_DIR).map_err(|e| format!("Failed to create {}: {}", MIRROR_DIR, e))?;
+        fs::write(&path, &data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    record_mirrored_image(&file, url, &repo.full_name, data.len());
+    Ok(format!("/images/github/{}", file))
+}
+
+// Add an origin URL and repo to a mirrored image's manifest entry
+fn record_mirrored_image(file: &str, origin: &str, repo: &str, size: usize) {
+    let _guard = MIRROR_MANIFEST_LOCK.lock().unwrap();
+    let mut manifest: std::collections::BTreeMap<String, MirroredImage> = fs::read_to_string(MIRROR_MANIFEST)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let entry = manifest.entry(file.to_string()).or_default();
+    if !entry.origins.iter().any(|o| o == origin) {
+        entry.origins.push(origin.to_string());
+    }
+    if !entry.repos.iter().any(|r| r == repo) {
+        entry.repos.push(repo.to_string());
+    }
+    entry.size = size;
+    if entry.fetched_at.is_empty() {
+        entry.fetched_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    }
+
+    match serde_json::to_string_pretty(&manifest) {
+        Ok(json) => {
+            let _ = fs::create_dir_all(".cache");
+            if let Err(e) = fs::write(MIRROR_MANIFEST, json) {
+                eprintln!("Failed to write {}: {}", MIRROR_MANIFEST, e);
+            }
+        }
+        Err(e) => eprintln!("Failed to serialize {}: {}", MIRROR_MANIFEST, e),
+    }
+}
+
+// Write an imported README post with its relative URLs made absolute: content/{slug}.md, or
+// content/{slug}/index.md with the images downloaded alongside when GITHUB_IMAGES=bundle.
+// With GITHUB_IMAGES=mirror every image, relative or not, is served from MIRROR_DIR instead
+async fn write_readme_post(repo: &GitHubRepo, readme: &str, slug: &str) -> Result<PathBuf, String> {
+    let flat_path = PathBuf::from("content").join(format!("{}.md", slug));
+    let bundle_dir = PathBuf::from("content").join(slug);
+
+    let (path, readme) = match *GITHUB_IMAGES {
+        GitHubImages::Remote => {
+            let readme = rewrite_markdown_urls(readme, |url, is_image| github_url(url, repo, is_image));
+            // Switching modes would otherwise leave the bundle copy behind as a duplicate post
+            let _ = fs::remove_file(bundle_dir.join("index.md"));
+            (flat_path, readme)
+        }
+        GitHubImages::Bundle => {
+            let mut images = Vec::new();
+            rewrite_markdown_urls(readme, |url, is_image| {
+                if let Some(path) = bundle_image_path(url).filter(|_| is_image) {
+                    if !images.contains(&path) {
+                        images.push(path);
+                    }
+                }
+                None
+            });
+
+            fs::create_dir_all(&bundle_dir).map_err(|e| format!("Failed to create {}: {}", bundle_dir.display(), e))?;
+            let mut downloaded = Vec::new();
+            for image in images {
+                let url = github_url(&image, repo, true).unwrap_or_default();
+                let target = bundle_dir.join(&image);
+                let result = match download_bytes(&url).await {
+                    Ok(data) => target
+                        .parent()
+                        .map_or(Ok(()), fs::create_dir_all)
+                        .and_then(|_| fs::write(&target, data))
+                        .map_err(|e| format!("Failed to write {}: {}", target.display(), e)),
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(()) => downloaded.push(image),
+                    // Fall back to hotlinking this one image
+                    Err(e) => eprintln!("Warning: {}: {}", repo.name, e),
+                }
+            }
+
+            let readme = rewrite_markdown_urls(readme, |url, is_image| {
+                match bundle_image_path(url).filter(|path| is_image && downloaded.contains(path)) {
+                    Some(path) => Some(path),
+                    None => github_url(url, repo, is_image),
+                }
+            });
+            let _ = fs::remove_file(&flat_path);
+            (bundle_dir.join("index.md"), readme)
+        }
+        GitHubImages::Mirror => {
+            let mut sources = Vec::new();
+            rewrite_markdown_urls(readme, |url, is_image| {
+                if let Some(source) = mirror_source_url(url, repo).filter(|_| is_image) {
+                    if !sources.contains(&source) {
+                        sources.push(source);
+                    }
+                }
+                None
+            });
+
+            let mut mirrored = HashMap::new();
+            for source in sources {
+                match mirror_image(&source, repo).await {
+                    Ok(local) => {
+                        mirrored.insert(source, local);
+                    }
+                    // Fall back to hotlinking this one image
+                    Err(e) => eprintln!("Warning: {}: {}", repo.name, e),
+                }
+            }
+
+            let readme = rewrite_markdown_urls(readme, |url, is_image| {
+                let local = mirror_source_url(url, repo)
+                    .filter(|_| is_image)
+                    .and_then(|source| mirrored.get(&source).cloned());
+                local.or_else(|| github_url(url, repo, is_image))
+            });
+            let _ = fs::remove_file(bundle_dir.join("index.md"));
+            (flat_path, readme)
+        }
+    };
+
+    fs::write(&path, create_post_from_readme(repo, &readme))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+// Sync all GitHub repos - creates/updates markdown files
+async fn sync_github_repos() -> impl IntoResponse {
+    let mut result = SyncResult {
+        success: true,
+        message: String::new(),
+        repos_synced: Vec::new(),
+        errors: Vec::new(),
+    };
+    
+    // Ensure content directory exists
+    let content_dir = PathBuf::from("content");
+    if !content_dir.exists() {
+        if let Err(e) = fs::create_dir_all(&content_dir) {
+            result.success = false;
+            result.message = format!("Failed to create content directory: {}", e);
+            return Json(result);
+        }
+    }
+    
+    // Fetch repos
+    let repos = match fetch_github_repos().await {
+        Ok(r) => r,
+        Err(e) => {
+            result.success = false;
+            result.message = e;
+            return Json(result);
+        }
+    };
+    
+    println!("📡 Found {} repositories for {}", repos.len(), GITHUB_USERNAME.as_str());
+    
+    for repo in repos {
+        println!("  → Fetching README for {}...", repo.name);
+        
+        match fetch_readme(&repo.name).await {
+            Ok(readme) => {
+                let slug = format!("github-{}", repo.name.to_lowercase().replace(' ', "-"));
+                
+                match write_readme_post(&repo, &readme, &slug).await {
+                    Ok(path) => {
+                        println!("    ✅ Created {}", path.display());
+                        result.repos_synced.push(repo.name);
+                    }
+                    Err(err) => {
+                        println!("    ❌ {}", err);
+                        result.errors.push(err);
+                    }
+                }
+            }
+            Err(e) => {
+                println!("    ⚠️  {}", e);
+                result.errors.push(e);
+            }
+        }
+    }
+    
+    result.message = format!(
+        "Synced {} repos, {} errors",
+        result.repos_synced.len(),
+        result.errors.len()
+    );
+    
+    println!("✨ GitHub sync complete: {}", result.message);
+    if !result.repos_synced.is_empty() {
+        on_content_changed();
+    }
+    
+    Json(result)
+}
+
+// List all GitHub repos (without syncing)
+async fn list_github_repos() -> impl IntoResponse {
+    match fetch_github_repos().await {
+        Ok(repos) => {
+            let repo_list: Vec<serde_json::Value> = repos.iter().map(|r| {
+                serde_json::json!({
+                    "name": r.name,
+                    "description": r.description,
+                    "url": r.html_url,
+                    "language": r.language,
+                    "stars": r.stargazers_count,
+                    "updated": r.pushed_at
+                })
+            }).collect();
+            
+            Json(serde_json::json!({
+                "success": true,
+                "username": GITHUB_USERNAME.as_str(),
+                "count": repo_list.len(),
+                "repos": repo_list
+            }))
+        }
+        Err(e) => {
+            Json(serde_json::json!({
+                "success": false,
+                "error": e
+            }))
+        }
+    }
+}
+
+// Search posts by title, summary, tags, and content
+async fn search_posts(Query(params): Query<SearchParams>) -> impl IntoResponse {
+    let query = params.q.unwrap_or_default().trim().to_lowercase();
+    if query.is_empty() {
+        return Json(serde_json::json!({ "results": [] }));
+    }
+
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    let mut results: Vec<SearchResult> = Vec::new();
+
+    for post in posts {
+        let haystack = format!(
+            "{} {} {} {}",
+            post.title,
+            post.summary,
+            post.tags.join(" "),
+            post.text
+        )
+        .to_lowercase();
+
+        if haystack.contains(&query) {
+            results.push(SearchResult {
+                title: post.title,
+                slug: post.slug,
+                summary: post.summary,
+                date: post.date.clone(),
+                date_iso: post.date_iso,
+                tags: post.tags,
+                reading_time: post.reading_time,
+            });
+        }
+    }
+
+    Json(serde_json::json!({ "results": results }))
+}
+
+// ============================================================================
+// Admin Panel - Routes & Handlers
+// ============================================================================
+
+// Helper to check if request has valid session
+async fn is_authenticated(headers: &HeaderMap, state: &AdminState) -> bool {
+    if let Some(cookie) = headers.get("cookie") {
+        if let Ok(cookie_str) = cookie.to_str() {
+            for part in cookie_str.split(';') {
+                let part = part.trim();
+                if let Some(token) = part.strip_prefix("session=") {
+                    let sessions = state.sessions.read().await;
+                    if let Some(session) = sessions.get(token) {
+                        let now = Instant::now();
+                        if now.duration_since(session.created_at) < Duration::from_secs(SESSION_DURATION_SECS) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+// Add secure headers to response
+fn add_security_headers(mut response: Response) -> Response {
+    let headers = response.headers_mut();
+    headers.insert(
+        header::X_FRAME_OPTIONS,
+        "DENY".parse().unwrap(),
+    );
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        "nosniff".parse().unwrap(),
+    );
+    headers.insert(
+        "X-XSS-Protection",
+        "1; mode=block".parse().unwrap(),
+    );
+    headers.insert(
+        header::STRICT_TRANSPORT_SECURITY,
+        "max-age=31536000; includeSubDomains".parse().unwrap(),
+    );
+    headers.insert(
+        header::CONTENT_SECURITY_POLICY,
+        "default-src 'self'; script-src 'self' 'unsafe-inline' fonts.googleapis.com; style-src 'self' 'unsafe-inline' fonts.googleapis.com; font-src 'self' fonts.gstatic.com; img-src 'self' data: https:; media-src 'self' https:".parse().unwrap(),
+    );
+    response
+}
+
+// Get client IP for rate limiting
+fn get_client_ip(headers: &HeaderMap) -> String {
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.split(',').next().unwrap_or("unknown").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// Admin login page
+async fn admin_login_page(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+) -> impl IntoResponse {
+    // If already logged in, redirect to dashboard
+    if is_authenticated(&headers, &state).await {
+        return Redirect::to("/admin/dashboard").into_response();
+    }
+    
+    add_security_headers(Html(ADMIN_LOGIN_HTML.to_string()).into_response())
+}
+
+// Admin login submit
+async fn admin_login_submit(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+    Form(form): Form<LoginForm>,
+) -> impl IntoResponse {
+    let client_ip = get_client_ip(&headers);
+    
+    // Check rate limiting
+    {
+        let mut attempts = state.login_attempts.write().await;
+        if let Some(data) = attempts.get_mut(&client_ip) {
+            // Check if locked
+            if let Some(locked_until) = data.locked_until {
+                if Instant::now() < locked_until {
+                    let remaining = locked_until.duration_since(Instant::now()).as_secs();
+                    return Html(format!(
+                        r#"<!DOCTYPE html><html><head><meta charset="UTF-8"><title>Locked</title></head>
+                        <body style="background:#000;color:#fff;font-family:system-ui;display:flex;justify-content:center;align-items:center;height:100vh;margin:0">
+                        <div style="text-align:center"><h1>Too Many Attempts</h1><p>Try again in {} seconds</p><a href="/admin" style="color:#666">Back</a></div>
+                        </body></html>"#, remaining
+                    )).into_response();
+                } else {
+                    // Reset after lockout expires
+                    data.attempts = 0;
+                    data.locked_until = None;
+                }
+            }
+            
+            // Reset if first attempt was more than lockout duration ago
+            if Instant::now().duration_since(data.first_attempt) > Duration::from_secs(LOCKOUT_DURATION_SECS) {
+                data.attempts = 0;
+                data.first_attempt = Instant::now();
+            }
+        }
+    }
+    
+    // Verify password
+    if form.password == ADMIN_PASSWORD.as_str() {
+        // Clear login attempts
+        {
+            let mut attempts = state.login_attempts.write().await;
+            attempts.remove(&client_ip);
+        }
+        
+        // Create session
+        let token = Uuid::new_v4().to_string();
+        {
+            let mut sessions = state.sessions.write().await;
+            sessions.insert(token.clone(), SessionData {
+                created_at: Instant::now(),
+                last_activity: Instant::now(),
+            });
+        }
+        
+        // Set cookie and redirect
+        Response::builder()
+            .status(StatusCode::SEE_OTHER)
+            .header("Location", "/admin/dashboard")
+            .header("Set-Cookie", format!("session={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}", token, SESSION_DURATION_SECS))
+            .body("".to_string())
+            .unwrap()
+            .into_response()
+    } else {
+        // Record failed attempt
+        {
+            let mut attempts = state.login_attempts.write().await;
+            let data = attempts.entry(client_ip.clone()).or_insert(LoginAttemptData {
+                attempts: 0,
+                first_attempt: Instant::now(),
+                locked_until: None,
+            });
+            data.attempts += 1;
+            
+            if data.attempts >= MAX_LOGIN_ATTEMPTS {
+                data.locked_until = Some(Instant::now() + Duration::from_secs(LOCKOUT_DURATION_SECS));
+            }
+        }
+        
+        Html(
+            r#"<!DOCTYPE html><html><head><meta charset="UTF-8"><title>Login Failed</title></head>
+            <body style="background:#000;color:#fff;font-family:system-ui;display:flex;justify-content:center;align-items:center;height:100vh;margin:0">
+            <div style="text-align:center"><h1>Invalid Password</h1><a href="/admin" style="color:#666">Try Again</a></div>
+            </body></html>"#
+        ).into_response()
+    }
+}
+
+// Admin logout
+async fn admin_logout(State(state): State<AdminState>, headers: HeaderMap) -> impl IntoResponse {
+    // Remove session
+    if let Some(cookie) = headers.get("cookie") {
+        if let Ok(cookie_str) = cookie.to_str() {
+            for part in cookie_str.split(';') {
+                let part = part.trim();
+                if let Some(token) = part.strip_prefix("session=") {
+                    let mut sessions = state.sessions.write().await;
+                    sessions.remove(token);
+                }
+            }
+        }
+    }
+    
+    Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header("Location", "/admin")
+        .header("Set-Cookie", "session=; Path=/; HttpOnly; Max-Age=0")
+        .body("".to_string())
+        .unwrap()
+}
+
+// Admin dashboard
+async fn admin_dashboard(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Redirect::to("/admin").into_response();
+    }
+    
+    let site_config = SiteConfig::default();
+    let posts = get_posts(&site_config);
+    let github_links = state.github_links.read().await;
+    
+    let mut posts_html = String::from("<table class=\"posts-table\"><thead><tr><th>Title</th><th>Date</th><th>Read Time</th><th style=\"text-align:right\">Actions</th></tr></thead><tbody>");
+    
+    if posts.is_empty() {
+        posts_html.push_str("<tr><td colspan=\"4\" style=\"text-align:center;padding:3rem\"><div class=\"empty-state\"><div class=\"empty-state-icon\">📝</div><div>No posts yet</div></div></td></tr>");
+    } else {
+        for post in &posts {
+            let is_github_linked = github_links.contains_key(&post.slug);
+            let github_badge = if is_github_linked {
+                " <span style=\"background:#1a1a1a;padding:2px 6px;border-radius:3px;font-size:11px;margin-left:8px\">GitHub</span>"
+            } else {
+                ""
+            };
+            
+            posts_html.push_str(&format!(
+                "<tr><td><div class=\"post-title\">{}{}</div></td><td class=\"post-meta\">{}</td><td class=\"post-meta\">{} min</td><td style=\"text-align:right\"><div class=\"table-actions\"><a href=\"/admin/edit/{}\" class=\"btn-sm\">Edit</a><button onclick=\"deletePost('{}')\" class=\"btn-sm btn-danger\">Delete</button></div></td></tr>",
+                html_escape::encode_text(&post.title),
+                github_badge,
+                post.date,
+                post.reading_time,
+                post.slug,
+                post.slug
+            ));
+        }
+    }
+    
+    posts_html.push_str("</tbody></table>");
+    
+    let dashboard_html = ADMIN_DASHBOARD_HTML
+        .replace("{{posts_count}}", &posts.len().to_string())
+        .replace("{{posts_list}}", &posts_html);
+    
+    Html(dashboard_html).into_response()
+}
+
+// Admin new post page
+async fn admin_new_post(headers: HeaderMap, State(state): State<AdminState>) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Redirect::to("/admin").into_response();
+    }
+    Html(ADMIN_EDITOR_HTML.replace("{{mode}}", "new").replace("{{title}}", "").replace("{{content}}", "").replace("{{tags}}", "").replace("{{summary}}", "").replace("{{slug}}", "")).into_response()
+}
+
+// Admin edit post page
+async fn admin_edit_post(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Redirect::to("/admin").into_response();
+    }
+    
+    // Find the post file
+    let content_path = PathBuf::from("content");
+    let mut post_content = String::new();
+    let mut post_title = String::new();
+    let mut post_tags = String::new();
+    let mut post_summary = String::new();
+    let mut found_file_slug = String::new();
+    
+    for path in post_files() {
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Some(meta) = parse_metadata(&content) {
+                // Bundles keep their directory, so saving writes back to <name>/index.md
+                let file_slug = path
+                    .strip_prefix(&content_path)
+                    .unwrap_or(&path)
+                    .with_extension("")
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                
+                if title_slug(&meta.title) == slug {
+                    post_title = meta.title;
+                    post_tags = meta.tags.join(", ");
+                    post_summary = meta.summary;
+                    found_file_slug = file_slug;
+                    // Get content after frontmatter
+                    let parts: Vec<&str> = content.splitn(3, "---").collect();
+                    if parts.len() >= 3 {
+                        post_content = parts[2].trim().to_string();
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    
+    Html(ADMIN_EDITOR_HTML
+        .replace("{{mode}}", "edit")
+        .replace("{{title}}", &html_escape::encode_text(&post_title))
+        .replace("{{content}}", &html_escape::encode_text(&post_content))
+        .replace("{{tags}}", &html_escape::encode_text(&post_tags))
+        .replace("{{summary}}", &html_escape::encode_text(&post_summary))
+        .replace("{{slug}}", &found_file_slug)
+    ).into_response()
+}
+
+// Front matter keys the editor writes itself
+const EDITOR_FRONT_MATTER: [&str; 5] = ["title", "date", "updated", "tags", "summary"];
+
+// The post file for an editor submission. Other front matter lines of the `existing` file are
+// kept as they are, and `updated` becomes `today` when the title, tags, summary or text changed
+fn post_file_from_form(existing: Option<&str>, form: &PostForm, today: &str) -> String {
+    let tags: Vec<&str> = form.tags.split(',').map(str::trim).filter(|t| !t.is_empty()).collect();
+    let old = existing.and_then(|content| {
+        let (front_matter, body) = content.strip_prefix("---")?.split_once("---")?;
+        Some((parse_metadata(content)?, front_matter, body))
+    });
+
+    let mut date = today.to_string();
+    let mut updated = None;
+    let mut kept = Vec::new();
+    if let Some((meta, front_matter, body)) = &old {
+        if !meta.date.is_empty() {
+            date = meta.date.clone();
+        }
+        let changed = meta.title != form.title
+            || meta.tags != tags
+            || meta.summary != form.summary
+            || body.trim() != form.content.trim();
+        updated = if changed { Some(today.to_string()) } else { meta.updated.clone() };
+
+        // Lines without a key belong to the field above them
+        let mut keep = false;
+        for line in front_matter.lines().filter(|line| !line.trim().is_empty()) {
+            if let Some((key, _)) = line.split_once(':').filter(|(key, _)| !key.starts_with(char::is_whitespace)) {
+                keep = !EDITOR_FRONT_MATTER.contains(&key.trim());
+            }
+            if keep {
+                kept.push(line);
+            }
+        }
+    }
+
+    let mut front_matter = format!("title: \"{}\"\ndate: \"{}\"\n", form.title, date);
+    if let Some(updated) = updated {
+        front_matter.push_str(&format!("updated: \"{}\"\n", updated));
+    }
+    front_matter.push_str(&format!(
+        "tags: [{}]\nsummary: \"{}\"\n",
+        tags.iter().map(|t| format!("\"{}\"", t)).collect::<Vec<_>>().join(", "),
+        form.summary
+    ));
+    for line in kept {
+        front_matter.push_str(line);
+        front_matter.push('\n');
+    }
+    format!("---\n{}---\n\n{}", front_matter, form.content)
+}
+
+// Save post (create or update)
+async fn admin_save_post(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+    Form(form): Form<PostForm>,
+) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Json(serde_json::json!({"success": false, "error": "Not authenticated"})).into_response();
+    }
+    
+    let slug = form.slug.clone().unwrap_or_else(|| {
+        form.title.to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect::<String>()
+            .split('-')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    });
+    
+    // Editing an existing post keeps its date and every front matter field the editor doesn't show
+    let file_path = format!("content/{}.md", slug);
+    let existing = fs::read_to_string(&file_path).ok();
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let markdown_content = post_file_from_form(existing.as_deref(), &form, &today);
+    
+    if let Err(e) = fs::write(&file_path, markdown_content) {
+        return Json(serde_json::json!({"success": false, "error": e.to_string()})).into_response();
+    }
+    on_content_changed();
+    
+    Json(serde_json::json!({"success": true, "slug": slug})).into_response()
+}
+
+// Delete post
+async fn admin_delete_post(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+    }
+    
+    let file_path = format!("content/{}.md", slug);
+    
+    // Also try github- prefix
+    let github_file_path = format!("content/github-{}.md", slug);
+    
+    let result = fs::remove_file(&file_path).or_else(|_| fs::remove_file(&github_file_path));
+    
+    match result {
+        Ok(_) => {
+            // Remove from GitHub links if exists
+            let mut links = state.github_links.write().await;
+            links.remove(&slug);
+            state.save_github_links(&links);
+            on_content_changed();
+            
+            Json(serde_json::json!({"success": true}))
+        }
+        Err(e) => Json(serde_json::json!({"success": false, "error": e.to_string()}))
+    }
+}
+
+// Admin GitHub import page
+async fn admin_github_page(headers: HeaderMap, State(state): State<AdminState>) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Redirect::to("/admin").into_response();
+    }
+    Html(ADMIN_GITHUB_HTML.to_string()).into_response()
+}
+
+// Link checker page (results are loaded from /admin/api/links)
+async fn admin_links_page(headers: HeaderMap, State(state): State<AdminState>) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Redirect::to("/admin").into_response();
+    }
+    Html(ADMIN_LINKS_HTML.to_string()).into_response()
+}
+
+// IndexNow page: whether submissions are on, and the recent ones
+async fn admin_indexnow_page(headers: HeaderMap, State(state): State<AdminState>) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Redirect::to("/admin").into_response();
+    }
+
+    let status = match INDEXNOW_KEY.as_deref() {
+        Some(key) => format!(
+            "Submitting changed pages to {} with key file <a href=\"/{}.txt\">/{}.txt</a> after every save, GitHub sync and webhook sync.",
+            html_escape::encode_text(INDEXNOW_ENDPOINT.as_str()),
+            key,
+            key
+        ),
+        None => "IndexNow is off. Set INDEXNOW_KEY to submit changed pages to search engines.".to_string(),
+    };
+
+    let log = INDEXNOW_LOG.lock().unwrap().clone();
+    let mut submissions_html = String::new();
+    if log.is_empty() {
+        submissions_html.push_str("<div class=\"loading\">No submissions yet</div>");
+    }
+    for submission in &log {
+        let result = match (&submission.error, submission.status) {
+            (None, Some(status)) => format!("<span class=\"ok\">{}</span>", status),
+            (Some(error), _) => format!("<span class=\"failed\">{}</span>", html_escape::encode_text(error)),
+            (None, None) => String::new(),
+        };
+        let urls: String = submission
+            .urls
+            .iter()
+            .map(|url| format!("<li>{}</li>", html_escape::encode_text(url)))
+            .collect();
+        submissions_html.push_str(&format!(
+            "<details class=\"submission\"><summary>{} &middot; {} URLs &middot; {} attempt{} &middot; {}</summary><ul class=\"urls\">{}</ul></details>",
+            DateTime::from_timestamp(submission.time, 0)
+                .map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default(),
+            submission.urls.len(),
+            submission.attempts,
+            if submission.attempts == 1 { "" } else { "s" },
+            result,
+            urls
+        ));
+    }
+
+    Html(ADMIN_INDEXNOW_HTML.replace("{{status}}", &status).replace("{{submissions}}", &submissions_html)).into_response()
+}
+
+// The link check job: its progress while running, then the report of the last run
+async fn admin_link_check_status(headers: HeaderMap, State(state): State<AdminState>) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+    }
+    let job = LINK_CHECK_JOB.lock().unwrap().clone();
+    Json(serde_json::json!({"success": true, "job": job}))
+}
+
+// Start the link checker in the background unless it's already running; `?external=false` skips
+// external URLs. The crawl waits between requests to each host, so it can take minutes
+async fn admin_check_links(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+    }
+    let external = params.get("external").map(|v| v != "false" && v != "0").unwrap_or(true);
+
+    let job = {
+        let mut job = LINK_CHECK_JOB.lock().unwrap();
+        if !job.running {
+            *job = LinkCheckJob {
+                running: true,
+                external,
+                started_at: Local::now().timestamp(),
+                report: job.report.take(),
+                ..Default::default()
+            };
+            tokio::spawn(run_link_check(external));
+        }
+        job.clone()
+    };
+    Json(serde_json::json!({"success": true, "job": job}))
+}
+
+async fn run_link_check(external: bool) {
+    let site_config = SiteConfig::default();
+    let posts = tokio::task::spawn_blocking(move || get_posts(&site_config)).await.unwrap_or_default();
+    LINK_CHECK_JOB.lock().unwrap().total = posts.len();
+
+    let report = check_links(&posts, &SiteConfig::default().url, &LinkCheckOptions::from_env(external), |done| {
+        LINK_CHECK_JOB.lock().unwrap().checked = done;
+    })
+    .await;
+
+    let mut job = LINK_CHECK_JOB.lock().unwrap();
+    job.running = false;
+    job.finished_at = Some(Local::now().timestamp());
+    job.report = Some(report);
+}
+
+// Render warnings for the editor's Markdown, with lines counted from the top of the textarea
+async fn admin_render_warnings(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+    Form(form): Form<WarningsForm>,
+) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+    }
+    // Runs on every pause in typing, so only front matter is read and only the lint stages run
+    let warnings = tokio::task::spawn_blocking(move || {
+        let site_config = SiteConfig::default();
+        let index = post_index(&post_sources());
+        let file_slug = form.slug.unwrap_or_default();
+        let bundle = file_slug
+            .strip_suffix("/index")
+            .map(|dir| PathBuf::from("content").join(dir))
+            .filter(|dir| dir.is_dir());
+        let slug = bundle
+            .as_ref()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file_slug.clone());
+        let ctx = RenderContext {
+            posts: &index,
+            sanitize: *SANITIZE_POSTS,
+            slug: &slug,
+            bundle: bundle.as_deref(),
+            disabled_stages: &[],
+            line_offset: 0,
+            profile: RenderProfile::Page,
+            site_url: &site_config.url,
+        };
+        RenderPipeline::for_lint().render(&form.content, &ctx).warnings
+    })
+    .await
+    .unwrap_or_default();
+    Json(serde_json::json!({"success": true, "warnings": warnings}))
+}
+
+// List GitHub repos as JSON (for admin)
+async fn admin_list_repos(headers: HeaderMap, State(state): State<AdminState>) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+    }
+    
+    match fetch_github_repos().await {
+        Ok(repos) => {
+            let github_links = state.github_links.read().await;
+            let repo_list: Vec<serde_json::Value> = repos.iter().map(|r| {
+                let is_linked = github_links.values().any(|link| link.repo_name == r.name);
+                serde_json::json!({
+                    "name": r.name,
+                    "full_name": r.full_name,
+                    "description": r.description,
+                    "url": r.html_url,
+                    "language": r.language,
+                    "stars": r.stargazers_count,
+                    "updated": r.pushed_at,
+                    "is_linked": is_linked
+                })
+            }).collect();
+            
+            Json(serde_json::json!({
+                "success": true,
+                "repos": repo_list
+            }))
+        }
+        Err(e) => Json(serde_json::json!({"success": false, "error": e}))
+    }
+}
+
+// Import a single GitHub repo
+async fn admin_import_repo(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+    Form(form): Form<GitHubImportForm>,
+) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+    }
+    
+    // Fetch repo info
+    let repos = match fetch_github_repos().await {
+        Ok(r) => r,
+        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
+    };
+    
+    let repo = match repos.iter().find(|r| r.name == form.repo_name) {
+        Some(r) => r,
+        None => return Json(serde_json::json!({"success": false, "error": "Repo not found"})),
+    };
+    
+    // Fetch README
+    let readme_content = match fetch_readme(&form.repo_name).await {
+        Ok(c) => c,
+        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
+    };
+    
+    // Create post
+    let slug = format!("github-{}", form.repo_name.to_lowercase());
+    
+    if let Err(e) = write_readme_post(repo, &readme_content, &slug).await {
+        return Json(serde_json::json!({"success": false, "error": e}));
+    }
+    on_content_changed();
+    
+    // Save link
+    let auto_sync = form.auto_sync.as_deref() == Some("on");
+    {
+        let mut links = state.github_links.write().await;
+        links.insert(slug.clone(), GitHubLink {
+            repo_name: form.repo_name.clone(),
+            repo_full_name: repo.full_name.clone(),
+            last_synced: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            auto_sync,
+        });
+        state.save_github_links(&links);
+    }
+    
+    Json(serde_json::json!({
+        "success": true,
+        "slug": slug,
+        "message": format!("Imported {} successfully", form.repo_name)
+    }))
+}
+
+// Sync a specific linked repo
+async fn admin_sync_repo(
+    headers: HeaderMap,
+    State(state): State<AdminState>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    if !is_authenticated(&headers, &state).await {
+        return Json(serde_json::json!({"success": false, "error": "Not authenticated"}));
+    }
+    
+    let link = {
+        let links = state.github_links.read().await;
+        links.get(&slug).cloned()
+    };
+    
+    let link = match link {
+        Some(l) => l,
+        None => return Json(serde_json::json!({"success": false, "error": "Not a GitHub linked post"})),
+    };
+    
+    // Fetch repo info
+    let repos = match fetch_github_repos().await {
+        Ok(r) => r,
+        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
+    };
+    
+    let repo = match repos.iter().find(|r| r.name == link.repo_name) {
+        Some(r) => r,
+        None => return Json(serde_json::json!({"success": false, "error": "Repo not found"})),
+    };
+    
+    // Fetch README
+    let readme_content = match fetch_readme(&link.repo_name).await {
+        Ok(c) => c,
+        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
+    };
+    
+    // Update post
+    if let Err(e) = write_readme_post(repo, &readme_content, &slug).await {
+        return Json(serde_json::json!({"success": false, "error": e}));
+    }
+    on_content_changed();
+    
+    // Update sync time
+    {
+        let mut links = state.github_links.write().await;
+        if let Some(link) = links.get_mut(&slug) {
+            link.last_synced = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        }
+        state.save_github_links(&links);
+    }
+    
+    Json(serde_json::json!({"success": true, "message": "Synced successfully"}))
+}
+
+// GitHub webhook handler for auto-sync
+async fn github_webhook(
+    State(state): State<AdminState>,
+    Json(payload): Json<WebhookPayload>,
+) -> impl IntoResponse {
+    // Check if this is a push to main/master
+    let is_main_push = payload.git_ref
+        .as_ref()
+        .map(|r| r == "refs/heads/main" || r == "refs/heads/master")
+        .unwrap_or(false);
+    
+    if !is_main_push {
+        return Json(serde_json::json!({"success": true, "message": "Ignoring non-main branch push"}));
+    }
+    
+    let repo_name = match payload.repository {
+        Some(r) => r.name,
+        None => return Json(serde_json::json!({"success": false, "error": "No repository in payload"})),
+    };
+    
+    // Find linked post with auto_sync enabled
+    let slug = {
+        let links = state.github_links.read().await;
+        links.iter()
+            .find(|(_, link)| link.repo_name == repo_name && link.auto_sync)
+            .map(|(slug, _)| slug.clone())
+    };
+    
+    let slug = match slug {
+        Some(s) => s,
+        None => return Json(serde_json::json!({"success": true, "message": "No auto-sync configured for this repo"})),
+    };
+    
+    // Fetch and update
+    let repos = match fetch_github_repos().await {
+        Ok(r) => r,
+        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
+    };
+    
+    let repo = match repos.iter().find(|r| r.name == repo_name) {
+        Some(r) => r,
+        None => return Json(serde_json::json!({"success": false, "error": "Repo not found"})),
+    };
+    
+    let readme_content = match fetch_readme(&repo_name).await {
+        Ok(c) => c,
+        Err(e) => return Json(serde_json::json!({"success": false, "error": e})),
+    };
+    
+    if let Err(e) = write_readme_post(repo, &readme_content, &slug).await {
+        return Json(serde_json::json!({"success": false, "error": e}));
+    }
+    on_content_changed();
+    
+    // Update sync time
+    {
+        let mut links = state.github_links.write().await;
+        if let Some(link) = links.get_mut(&slug) {
+            link.last_synced = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        }
+        state.save_github_links(&links);
+    }
+    
+    println!("Webhook: Auto-synced {} from GitHub", slug);
+    
+    Json(serde_json::json!({"success": true, "message": format!("Auto-synced {}", slug)}))
+}
+
+// ============================================================================
+// Admin Panel - HTML Templates (embedded for simplicity)
+// ============================================================================
+
+const ADMIN_LOGIN_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Admin Login</title>
+    <link rel="preconnect" href="https://fonts.googleapis.com">
+    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
+    <link href="https://fonts.googleapis.com/css2?family=Inter:wght@400;500;600;700&display=swap" rel="stylesheet">
+    <style>
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        body {
+            background: #000;
+            color: #fff;
+            font-family: 'Inter', -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            min-height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+            padding: 1rem;
+        }
+        .login-container {
+            width: 100%;
+            max-width: 400px;
+        }
+        .login-header {
+            text-align: center;
+            margin-bottom: 2rem;
+        }
+        .login-header h1 {
+            font-size: 1.75rem;
+            font-weight: 600;
+            letter-spacing: -0.01em;
+            margin-bottom: 0.5rem;
+        }
+        .login-header p {
+            font-size: 0.875rem;
+            color: #a0a0a0;
+        }
+        .login-form {
+            display: flex;
+            flex-direction: column;
+            gap: 1rem;
+        }
+        .form-group {
+            display: flex;
+            flex-direction: column;
+            gap: 0.5rem;
+        }
+        .form-group label {
+            font-size: 0.875rem;
+            font-weight: 500;
+            color: #fff;
+        }
+        input[type="password"] {
+            padding: 0.75rem;
+            background: #0a0a0a;
+            border: 1px solid #1a1a1a;
+            color: #fff;
+            font-size: 0.9375rem;
+            border-radius: 6px;
+            transition: all 0.15s ease;
+            font-family: inherit;
+        }
+        input[type="password"]:focus {
+            outline: none;
+            border-color: #444;
+            background: #0f0f0f;
+        }
+        .submit-btn {
+            padding: 0.75rem;
+            background: #fff;
+            color: #000;
+            border: none;
+            font-size: 0.9375rem;
+            font-weight: 600;
+            border-radius: 6px;
+            cursor: pointer;
+            transition: background 0.15s ease;
+            font-family: inherit;
+            margin-top: 0.5rem;
+        }
+        .submit-btn:hover {
+            background: #f0f0f0;
+        }
+        .login-footer {
+            text-align: center;
+            margin-top: 1.5rem;
+        }
+        .login-footer a {
+            color: #666;
+            text-decoration: none;
+            font-size: 0.8125rem;
+            transition: color 0.15s ease;
+        }
+        .login-footer a:hover {
+            color: #999;
+        }
+    </style>
+</head>
+<body>
+    <div class="login-container">
+        <div class="login-header">
+            <h1>Admin</h1>
+            <p>Secure access required</p>
+        </div>
+        <form method="POST" action="/admin/login" class="login-form">
+            <div class="form-group">
+                <label for="password">Password</label>
+                <input type="password" id="password" name="password" required autofocus placeholder="Enter admin password">
+            </div>
+            <button type="submit" class="submit-btn">Sign In</button>
+        </form>
+        <div class="login-footer">
+            <a href="/">← Back to Blog</a>
+        </div>
+    </div>
+</body>
+</html>"#;
+
+const ADMIN_DASHBOARD_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Admin Dashboard</title>
+    <link rel="preconnect" href="https://fonts.googleapis.com">
+    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
+    <link href="https://fonts.googleapis.com/css2?family=Inter:wght@400;500;600;700&display=swap" rel="stylesheet">
+    <style>
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        body {
+            background: #000;
+            color: #fff;
+            font-family: 'Inter', -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            min-height: 100vh;
+            display: flex;
+            flex-direction: column;
+        }
+        
+        .topbar {
+            border-bottom: 1px solid #1a1a1a;
+            padding: 1.25rem 1.5rem;
+            display: flex;
+            justify-content: space-between;
+            align-items: center;
+            position: sticky;
+            top: 0;
+            background: rgba(0,0,0,0.95);
+            backdrop-filter: blur(10px);
+            z-index: 100;
+        }
+        .topbar-left { display: flex; align-items: center; gap: 1rem; }
+        .topbar-title { font-size: 1rem; font-weight: 600; letter-spacing: -0.01em; }
+        .topbar-right { display: flex; gap: 0.75rem; }
+        .topbar-link {
+            color: #a0a0a0;
+            text-decoration: none;
+            font-size: 0.8125rem;
+            font-weight: 500;
+            padding: 0.5rem 0.75rem;
+            border-radius: 4px;
+            transition: all 0.15s ease;
+        }
+        .topbar-link:hover { color: #fff; background: #1a1a1a; }
+        
+        .main {
+            flex: 1;
+            max-width: 1000px;
+            width: 100%;
+            margin: 0 auto;
+            padding: 2rem 1.5rem;
+        }
+        
+        .stats-grid {
+            display: grid;
+            grid-template-columns: repeat(auto-fit, minmax(200px, 1fr));
+            gap: 1rem;
+            margin-bottom: 2rem;
+        }
+        .stat-card {
+            background: #0a0a0a;
+            border: 1px solid #1a1a1a;
+            border-radius: 8px;
+            padding: 1.5rem;
+        }
+        .stat-value { font-size: 2rem; font-weight: 700; letter-spacing: -0.02em; }
+        .stat-label { font-size: 0.75rem; color: #666; margin-top: 0.5rem; text-transform: uppercase; letter-spacing: 0.05em; }
+        
+        .section {
+            margin-bottom: 2rem;
+        }
+        .section-header {
+            display: flex;
+            justify-content: space-between;
+            align-items: center;
+            margin-bottom: 1rem;
+            padding-bottom: 0.75rem;
+            border-bottom: 1px solid #1a1a1a;
+        }
+        .section-title { font-size: 0.875rem; font-weight: 600; color: #fff; }
+        .btn-group { display: flex; gap: 0.5rem; }
+        
+        .btn {
+            padding: 0.625rem 1rem;
+            font-size: 0.8125rem;
+            font-weight: 500;
+            border: none;
+            border-radius: 6px;
+            cursor: pointer;
+            transition: all 0.15s ease;
+            font-family: inherit;
+            display: inline-flex;
+            align-items: center;
+            gap: 0.375rem;
+            text-decoration: none;
+        }
+        .btn-primary {
+            background: #fff;
+            color: #000;
+        }
+        .btn-primary:hover { background: #f0f0f0; }
+        .btn-secondary {
+            background: #1a1a1a;
+            color: #a0a0a0;
+            border: 1px solid #333;
+        }
+        .btn-secondary:hover { background: #222; color: #fff; }
+        
+        .posts-table {
+            width: 100%;
+            border-collapse: collapse;
+            background: #0a0a0a;
+            border: 1px solid #1a1a1a;
+            border-radius: 8px;
+            overflow: hidden;
+        }
+        .posts-table thead {
+            background: #0f0f0f;
+            border-bottom: 1px solid #1a1a1a;
+        }
+        .posts-table th {
+            padding: 0.875rem;
+            text-align: left;
+            font-size: 0.75rem;
+            font-weight: 600;
+            text-transform: uppercase;
+            letter-spacing: 0.05em;
+            color: #666;
+        }
+        .posts-table td {
+            padding: 0.875rem;
+            border-top: 1px solid #1a1a1a;
+            font-size: 0.9375rem;
+        }
+        .post-title { font-weight: 600; color: #fff; }
+        .post-meta { font-size: 0.8125rem; color: #666; }
+        .table-actions { display: flex; gap: 0.5rem; }
+        .btn-sm {
+            padding: 0.375rem 0.75rem;
+            font-size: 0.75rem;
+            background: #1a1a1a;
+            color: #a0a0a0;
+            border: none;
+            border-radius: 4px;
+            cursor: pointer;
+            transition: all 0.15s ease;
+        }
+        .btn-sm:hover { background: #2a2a2a; color: #fff; }
+        .btn-danger { color: #ff6b6b; }
+        .btn-danger:hover { background: #330000; }
+        
+        .empty-state {
+            text-align: center;
+            padding: 3rem 1rem;
+            color: #666;
+        }
+        .empty-state-icon {
+            font-size: 2.5rem;
+            margin-bottom: 0.5rem;
+            opacity: 0.3;
+        }
+        
+        @media (max-width: 640px) {
+            .main { padding: 1rem; }
+            .stats-grid { grid-template-columns: 1fr; }
+            .section-header { flex-direction: column; align-items: flex-start; gap: 0.75rem; }
+            .btn-group { width: 100%; flex-wrap: wrap; }
+        }
+    </style>
+</head>
+<body>
+    <div class="topbar">
+        <div class="topbar-left">
+            <span class="topbar-title">Admin Dashboard</span>
+        </div>
+        <div class="topbar-right">
+            <a href="/" class="topbar-link">← View Blog</a>
+            <a href="/admin/logout" class="topbar-link">Logout</a>
+        </div>
+    </div>
+    
+    <div class="main">
+        <div class="stats-grid">
+            <div class="stat-card">
+                <div class="stat-value">{{posts_count}}</div>
+                <div class="stat-label">Total Posts</div>
+            </div>
+        </div>
+        
+        <div class="section">
+            <div class="section-header">
+                <span class="section-title">Posts</span>
+                <div class="btn-group">
+                    <a href="/admin/github" class="btn btn-secondary">GitHub</a>
+                    <a href="/admin/links" class="btn btn-secondary">Links</a>
+                    <a href="/admin/indexnow" class="btn btn-secondary">IndexNow</a>
+                    <a href="/admin/new" class="btn btn-primary">+ New Post</a>
+                </div>
+            </div>
+            <div>{{posts_list}}</div>
+        </div>
+    </div>
+    
+    <script>
+        async function deletePost(slug) {
+            if (!confirm('Delete this post?')) return;
+            const res = await fetch('/admin/delete/' + slug, { method: 'DELETE' });
+            const data = await res.json();
+            if (data.success) location.reload();
+            else alert('Error: ' + data.error);
+        }
+    </script>
+</body>
+</html>"#;
+
+const ADMIN_EDITOR_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Edit Post</title>
+    <style>
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        body {
+            background: #000;
+            color: #fff;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            min-height: 100vh;
+        }
+        .header {
+            border-bottom: 1px solid #1a1a1a;
+            padding: 16px 24px;
+            display: flex;
+            justify-content: space-between;
+            align-items: center;
+            position: sticky;
+            top: 0;
+            background: #000;
+            z-index: 100;
+        }
+        .header h1 {
+            font-size: 16px;
+            font-weight: 600;
+        }
+        .header-actions {
+            display: flex;
+            gap: 12px;
+        }
+        .btn {
+            background: #fff;
+            color: #000;
+            padding: 8px 16px;
+            font-size: 13px;
+            font-weight: 500;
+            border-radius: 4px;
+            text-decoration: none;
+            border: none;
+            cursor: pointer;
+        }
+        .btn:hover {
+            background: #e0e0e0;
+        }
+        .btn-secondary {
+            background: transparent;
+            color: #666;
+            border: 1px solid #333;
+        }
+        .btn-secondary:hover {
+            color: #fff;
+            border-color: #555;
+        }
+        .container {
+            max-width: 800px;
+            margin: 0 auto;
+            padding: 32px 24px;
+        }
+        .form-group {
+            margin-bottom: 24px;
+        }
+        label {
+            display: block;
+            font-size: 11px;
+            font-weight: 500;
+            text-transform: uppercase;
+            letter-spacing: 0.05em;
+            color: #666;
+            margin-bottom: 8px;
+        }
+        input, textarea {
+            width: 100%;
+            background: #0a0a0a;
+            border: 1px solid #222;
+            color: #fff;
+            padding: 12px;
+            font-size: 14px;
+            border-radius: 4px;
+            outline: none;
+            font-family: inherit;
+        }
+        input:focus, textarea:focus {
+            border-color: #444;
+        }
+        textarea {
+            min-height: 400px;
+            resize: vertical;
+            font-family: 'SF Mono', 'Fira Code', monospace;
+            font-size: 13px;
+            line-height: 1.6;
+        }
+        .hint {
+            font-size: 12px;
+            color: #666;
+            margin-top: 6px;
+        }
+        .toast {
+            position: fixed;
+            bottom: 24px;
+            right: 24px;
+            background: #1a1a1a;
+            color: #fff;
+            padding: 12px 20px;
+            border-radius: 4px;
+            font-size: 14px;
+            display: none;
+        }
+        .toast.show {
+            display: block;
+        }
+        .warnings {
+            list-style: none;
+            margin-top: 12px;
+            font-size: 13px;
+        }
+        .warnings li {
+            padding: 8px 12px;
+            border-left: 2px solid #b58900;
+            background: #0a0a0a;
+            color: #ccc;
+            margin-bottom: 4px;
+            cursor: pointer;
+        }
+        .warnings li:hover {
+            color: #fff;
+        }
+        .warnings .line {
+            color: #b58900;
+            font-family: 'SF Mono', 'Fira Code', monospace;
+            margin-right: 8px;
+        }
+    </style>
+</head>
+<body>
+    <div class="header">
+        <h1>{{mode}} Post</h1>
+        <div class="header-actions">
+            <a href="/admin/dashboard" class="btn btn-secondary">Cancel</a>
+            <button type="button" onclick="savePost()" class="btn">Save Post</button>
+        </div>
+    </div>
+    <div class="container">
+        <form id="postForm">
+            <input type="hidden" name="slug" value="{{slug}}">
+            
+            <div class="form-group">
+                <label>Title</label>
+                <input type="text" name="title" value="{{title}}" required placeholder="Enter post title">
+            </div>
+            
+            <div class="form-group">
+                <label>Summary</label>
+                <input type="text" name="summary" value="{{summary}}" placeholder="Brief description for SEO">
+            </div>
+            
+            <div class="form-group">
+                <label>Tags</label>
+                <input type="text" name="tags" value="{{tags}}" placeholder="tag1, tag2, tag3">
+                <div class="hint">Comma-separated list of tags</div>
+            </div>
+            
+            <div class="form-group">
+                <label>Content (Markdown)</label>
+                <textarea name="content" placeholder="Write your post in Markdown...">{{content}}</textarea>
+                <ul class="warnings" id="warnings"></ul>
+            </div>
+        </form>
+    </div>
+    
+    <div class="toast" id="toast"></div>
+    
+    <script>
+        async function savePost() {
+            const form = document.getElementById('postForm');
+            const formData = new FormData(form);
+            
+            const res = await fetch('/admin/save', {
+                method: 'POST',
+                body: new URLSearchParams(formData)
+            });
+            
+            const data = await res.json();
+            
+            if (data.success) {
+                showToast('Post saved successfully!');
+                setTimeout(() => {
+                    window.location.href = '/admin/dashboard';
+                }, 1000);
+            } else {
+                showToast('Error: ' + data.error);
+            }
+        }
+        
+        // Render warnings, refreshed shortly after typing stops
+        const contentField = document.querySelector('textarea[name="content"]');
+        let warningsTimer;
+
+        async function checkWarnings() {
+            const formData = new FormData(document.getElementById('postForm'));
+            const res = await fetch('/admin/api/warnings', {
+                method: 'POST',
+                body: new URLSearchParams(formData)
+            });
+            const data = await res.json();
+            const list = document.getElementById('warnings');
+            list.innerHTML = '';
+            if (!data.success) return;
+            for (const warning of data.warnings) {
+                const item = document.createElement('li');
+                const line = document.createElement('span');
+                line.className = 'line';
+                line.textContent = 'Line ' + warning.line;
+                item.appendChild(line);
+                item.appendChild(document.createTextNode(warning.message));
+                item.onclick = () => goToLine(warning.line);
+                list.appendChild(item);
+            }
+        }
+
+        function goToLine(number) {
+            const lines = contentField.value.split('\n');
+            const start = lines.slice(0, number - 1).reduce((sum, l) => sum + l.length + 1, 0);
+            contentField.focus();
+            contentField.setSelectionRange(start, start + (lines[number - 1] || '').length);
+        }
+
+        contentField.addEventListener('input', () => {
+            clearTimeout(warningsTimer);
+            warningsTimer = setTimeout(checkWarnings, 800);
+        });
+        checkWarnings();
+
+        function showToast(msg) {
+            const toast = document.getElementById('toast');
+            toast.textContent = msg;
+            toast.classList.add('show');
+            setTimeout(() => toast.classList.remove('show'), 3000);
+        }
+        
+        // Keyboard shortcut: Cmd/Ctrl + S to save
+        document.addEventListener('keydown', (e) => {
+            if ((e.metaKey || e.ctrlKey) && e.key === 's') {
+                e.preventDefault();
+                savePost();
+            }
+        });
+    </script>
+</body>
+</html>"#;
+
+const ADMIN_GITHUB_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Import from GitHub</title>
+    <style>
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        body {
+            background: #000;
+            color: #fff;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            min-height: 100vh;
+        }
+        .header {
+            border-bottom: 1px solid #1a1a1a;
+            padding: 16px 24px;
+            display: flex;
+            justify-content: space-between;
+            align-items: center;
+        }
+        .header h1 {
+            font-size: 16px;
+            font-weight: 600;
+        }
+        .btn {
+            background: #fff;
+            color: #000;
+            padding: 8px 16px;
+            font-size: 13px;
+            font-weight: 500;
+            border-radius: 4px;
+            text-decoration: none;
+            border: none;
+            cursor: pointer;
+        }
+        .btn:hover {
+            background: #e0e0e0;
+        }
+        .btn-secondary {
+            background: transparent;
+            color: #666;
+            border: 1px solid #333;
+        }
+        .btn-secondary:hover {
+            color: #fff;
+            border-color: #555;
+        }
+        .btn-small {
+            padding: 6px 12px;
+            font-size: 12px;
+        }
+        .container {
+            max-width: 800px;
+            margin: 0 auto;
+            padding: 32px 24px;
+        }
+        .info {
+            background: #0a0a0a;
+            border: 1px solid #1a1a1a;
+            border-radius: 4px;
+            padding: 16px;
+            margin-bottom: 24px;
+            font-size: 13px;
+            color: #999;
+        }
+        .repos-list {
+            display: flex;
+            flex-direction: column;
+            gap: 8px;
+        }
+        .repo {
+            background: #0a0a0a;
+            border: 1px solid #1a1a1a;
+            border-radius: 4px;
+            padding: 16px;
+            display: flex;
+            justify-content: space-between;
+            align-items: center;
+        }
+        .repo-info h3 {
+            font-size: 14px;
+            font-weight: 500;
+            margin-bottom: 4px;
+        }
+        .repo-info p {
+            font-size: 12px;
+            color: #666;
+            margin-bottom: 4px;
+        }
+        .repo-meta {
+            font-size: 11px;
+            color: #444;
+            display: flex;
+            gap: 12px;
+        }
+        .repo-actions {
+            display: flex;
+            gap: 8px;
+            align-items: center;
+        }
+        .checkbox-label {
+            display: flex;
+            align-items: center;
+            gap: 6px;
+            font-size: 12px;
+            color: #666;
+        }
+        .checkbox-label input {
+            width: 14px;
+            height: 14px;
         }
-        state.save_github_links(&links);
-    }
+        .loading {
+            text-align: center;
+            padding: 40px;
+            color: #666;
+        }
+        .linked {
+            background: #0d1a0d;
+            border-color: #1a2e1a;
+        }
+        .linked-badge {
+            background: #1a2e1a;
+            color: #4a8;
+            padding: 2px 6px;
+            border-radius: 2px;
+            font-size: 10px;
+            margin-left: 8px;
+        }
+        .toast {
+            position: fixed;
+            bottom: 24px;
+            right: 24px;
+            background: #1a1a1a;
+            color: #fff;
+            padding: 12px 20px;
+            border-radius: 4px;
+            font-size: 14px;
+            display: none;
+        }
+        .toast.show {
+            display: block;
+        }
+    </style>
+</head>
+<body>
+    <div class="header">
+        <h1>Import from GitHub</h1>
+        <a href="/admin/dashboard" class="btn btn-secondary">Back to Dashboard</a>
+    </div>
+    <div class="container">
+        <div class="info">
+            <strong>Auto-sync:</strong> Enable auto-sync to automatically update blog posts when you push changes to your GitHub repository. 
+            Set up a webhook in your GitHub repo settings pointing to <code>/api/webhook/github</code> to enable this feature.
+        </div>
+        
+        <div id="repos" class="repos-list">
+            <div class="loading">Loading repositories...</div>
+        </div>
+    </div>
     
-    println!("Webhook: Auto-synced {} from GitHub", slug);
+    <div class="toast" id="toast"></div>
     
-    Json(serde_json::json!({"success": true, "message": format!("Auto-synced {}", slug)}))
-}
-
-// ============================================================================
-// Admin Panel - HTML Templates (embedded for simplicity)
-// ============================================================================
+    <script>
+        async function loadRepos() {
+            const res = await fetch('/admin/api/repos');
+            const data = await res.json();
+            
+            if (!data.success) {
+                document.getElementById('repos').innerHTML = '<div class="loading">Error: ' + data.error + '</div>';
+                return;
+            }
+            
+            const html = data.repos.map(repo => `
+                <div class="repo ${repo.is_linked ? 'linked' : ''}">
+                    <div class="repo-info">
+                        <h3>${repo.name}${repo.is_linked ? '<span class="linked-badge">Linked</span>' : ''}</h3>
+                        <p>${repo.description || 'No description'}</p>
+                        <div class="repo-meta">
+                            <span>${repo.language || 'Unknown'}</span>
+                            <span>${repo.stars} stars</span>
+                        </div>
+                    </div>
+                    <div class="repo-actions">
+                        ${repo.is_linked ? `
+                            <button class="btn btn-small btn-secondary" onclick="syncRepo('${repo.name}')">Sync Now</button>
+                        ` : `
+                            <label class="checkbox-label">
+                                <input type="checkbox" id="auto-${repo.name}">
+                                Auto-sync
+                            </label>
+                            <button class="btn btn-small" onclick="importRepo('${repo.name}')">Import</button>
+                        `}
+                    </div>
+                </div>
+            `).join('');
+            
+            document.getElementById('repos').innerHTML = html;
+        }
+        
+        async function importRepo(name) {
+            const autoSync = document.getElementById('auto-' + name)?.checked ? 'on' : '';
+            
+            const res = await fetch('/admin/github/import', {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/x-www-form-urlencoded' },
+                body: 'repo_name=' + encodeURIComponent(name) + '&auto_sync=' + autoSync
+            });
+            
+            const data = await res.json();
+            
+            if (data.success) {
+                showToast('Imported successfully!');
+                loadRepos();
+            } else {
+                showToast('Error: ' + data.error);
+            }
+        }
+        
+        async function syncRepo(name) {
+            const res = await fetch('/admin/sync/github-' + name.toLowerCase(), { method: 'POST' });
+            const data = await res.json();
+            
+            if (data.success) {
+                showToast('Synced successfully!');
+            } else {
+                showToast('Error: ' + data.error);
+            }
+        }
+        
+        function showToast(msg) {
+            const toast = document.getElementById('toast');
+            toast.textContent = msg;
+            toast.classList.add('show');
+            setTimeout(() => toast.classList.remove('show'), 3000);
+        }
+        
+        loadRepos();
+    </script>
+</body>
+</html>"#;
 
-const ADMIN_LOGIN_HTML: &str = r#"<!DOCTYPE html>
+const ADMIN_LINKS_HTML: &str = r#"<!DOCTYPE html>
 <html lang="en">
 <head>
     <meta charset="UTF-8">
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Admin Login</title>
-    <link rel="preconnect" href="https://fonts.googleapis.com">
-    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
-    <link href="https://fonts.googleapis.com/css2?family=Inter:wght@400;500;600;700&display=swap" rel="stylesheet">
+    <title>Link Check</title>
     <style>
         * { margin: 0; padding: 0; box-sizing: border-box; }
         body {
             background: #000;
             color: #fff;
-            font-family: 'Inter', -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
             min-height: 100vh;
+        }
+        .header {
+            border-bottom: 1px solid #1a1a1a;
+            padding: 16px 24px;
             display: flex;
+            justify-content: space-between;
             align-items: center;
-            justify-content: center;
-            padding: 1rem;
         }
-        .login-container {
-            width: 100%;
-            max-width: 400px;
+        .header h1 {
+            font-size: 16px;
+            font-weight: 600;
         }
-        .login-header {
-            text-align: center;
-            margin-bottom: 2rem;
+        .header-actions {
+            display: flex;
+            gap: 8px;
+            align-items: center;
         }
-        .login-header h1 {
-            font-size: 1.75rem;
-            font-weight: 600;
-            letter-spacing: -0.01em;
-            margin-bottom: 0.5rem;
+        .btn {
+            background: #fff;
+            color: #000;
+            padding: 8px 16px;
+            font-size: 13px;
+            font-weight: 500;
+            border-radius: 4px;
+            text-decoration: none;
+            border: none;
+            cursor: pointer;
         }
-        .login-header p {
-            font-size: 0.875rem;
-            color: #a0a0a0;
+        .btn:hover {
+            background: #e0e0e0;
         }
-        .login-form {
-            display: flex;
-            flex-direction: column;
-            gap: 1rem;
+        .btn:disabled {
+            opacity: 0.5;
+            cursor: default;
         }
-        .form-group {
-            display: flex;
-            flex-direction: column;
-            gap: 0.5rem;
+        .btn-secondary {
+            background: transparent;
+            color: #666;
+            border: 1px solid #333;
         }
-        .form-group label {
-            font-size: 0.875rem;
-            font-weight: 500;
+        .btn-secondary:hover {
             color: #fff;
+            border-color: #555;
         }
-        input[type="password"] {
-            padding: 0.75rem;
+        .checkbox-label {
+            display: flex;
+            align-items: center;
+            gap: 6px;
+            font-size: 12px;
+            color: #666;
+        }
+        .container {
+            max-width: 800px;
+            margin: 0 auto;
+            padding: 32px 24px;
+        }
+        .summary {
             background: #0a0a0a;
             border: 1px solid #1a1a1a;
-            color: #fff;
-            font-size: 0.9375rem;
-            border-radius: 6px;
-            transition: all 0.15s ease;
-            font-family: inherit;
+            border-radius: 4px;
+            padding: 16px;
+            margin-bottom: 24px;
+            font-size: 13px;
+            color: #999;
         }
-        input[type="password"]:focus {
-            outline: none;
-            border-color: #444;
-            background: #0f0f0f;
+        .links-list {
+            display: flex;
+            flex-direction: column;
+            gap: 8px;
         }
-        .submit-btn {
-            padding: 0.75rem;
-            background: #fff;
-            color: #000;
-            border: none;
-            font-size: 0.9375rem;
-            font-weight: 600;
-            border-radius: 6px;
-            cursor: pointer;
-            transition: background 0.15s ease;
-            font-family: inherit;
-            margin-top: 0.5rem;
+        .link {
+            background: #1a0d0d;
+            border: 1px solid #2e1a1a;
+            border-radius: 4px;
+            padding: 16px;
         }
-        .submit-btn:hover {
-            background: #f0f0f0;
+        .link-url {
+            font-family: monospace;
+            font-size: 13px;
+            word-break: break-all;
+            margin-bottom: 4px;
         }
-        .login-footer {
-            text-align: center;
-            margin-top: 1.5rem;
+        .link-reason {
+            font-size: 12px;
+            color: #c66;
+            margin-bottom: 4px;
         }
-        .login-footer a {
+        .link-source {
+            font-size: 11px;
             color: #666;
-            text-decoration: none;
-            font-size: 0.8125rem;
-            transition: color 0.15s ease;
         }
-        .login-footer a:hover {
-            color: #999;
+        .loading {
+            text-align: center;
+            padding: 40px;
+            color: #666;
         }
     </style>
 </head>
 <body>
-    <div class="login-container">
-        <div class="login-header">
-            <h1>Admin</h1>
-            <p>Secure access required</p>
-        </div>
-        <form method="POST" action="/admin/login" class="login-form">
-            <div class="form-group">
-                <label for="password">Password</label>
-                <input type="password" id="password" name="password" required autofocus placeholder="Enter admin password">
-            </div>
-            <button type="submit" class="submit-btn">Sign In</button>
-        </form>
-        <div class="login-footer">
-            <a href="/">← Back to Blog</a>
+    <div class="header">
+        <h1>Link Check</h1>
+        <div class="header-actions">
+            <label class="checkbox-label">
+                <input type="checkbox" id="external" checked>
+                External links
+            </label>
+            <button class="btn" id="run" onclick="runCheck()">Run Check</button>
+            <a href="/admin/dashboard" class="btn btn-secondary">Back to Dashboard</a>
         </div>
     </div>
+    <div class="container">
+        <div id="summary" class="summary">Checks every link in every post: internal posts, tags and anchors against the site, external URLs with HEAD requests (cached).</div>
+        <div id="links" class="links-list"></div>
+    </div>
+    
+    <script>
+        function escapeHtml(text) {
+            const div = document.createElement('div');
+            div.textContent = text;
+            return div.innerHTML;
+        }
+        
+        function showReport(report) {
+            document.getElementById('summary').textContent =
+                `Checked ${report.links} links (${report.external} external) in ${report.posts} posts: ${report.broken.length} broken`;
+            
+            document.getElementById('links').innerHTML = report.broken.map(link => `
+                <div class="link">
+                    <div class="link-url">${escapeHtml(link.url)}</div>
+                    <div class="link-reason">${escapeHtml(link.reason)}</div>
+                    <div class="link-source">${escapeHtml(link.post)} &middot; content/${escapeHtml(link.file)}:${link.line ?? '?'}</div>
+                </div>
+            `).join('') || '<div class="loading">No broken links</div>';
+        }
+        
+        // Show the job's state, polling until it finishes
+        function showJob(data) {
+            if (!data.success) {
+                document.getElementById('links').innerHTML = '<div class="loading">Error: ' + escapeHtml(data.error) + '</div>';
+                return;
+            }
+            
+            const job = data.job;
+            document.getElementById('run').disabled = job.running;
+            if (job.running) {
+                document.getElementById('links').innerHTML = job.total
+                    ? `<div class="loading">Checking links... ${job.checked} of ${job.total} posts</div>`
+                    : '<div class="loading">Checking links...</div>';
+                setTimeout(async () => showJob(await (await fetch('/admin/api/links')).json()), 2000);
+            } else if (job.report) {
+                showReport(job.report);
+            } else {
+                runCheck();
+            }
+        }
+        
+        async function runCheck() {
+            const external = document.getElementById('external').checked;
+            const res = await fetch('/admin/api/links?external=' + external, { method: 'POST' });
+            showJob(await res.json());
+        }
+        
+        fetch('/admin/api/links').then(res => res.json()).then(showJob);
+    </script>
 </body>
 </html>"#;
 
-const ADMIN_DASHBOARD_HTML: &str = r#"<!DOCTYPE html>
+const ADMIN_INDEXNOW_HTML: &str = r#"<!DOCTYPE html>
 <html lang="en">
 <head>
     <meta charset="UTF-8">
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Admin Dashboard</title>
-    <link rel="preconnect" href="https://fonts.googleapis.com">
-    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
-    <link href="https://fonts.googleapis.com/css2?family=Inter:wght@400;500;600;700&display=swap" rel="stylesheet">
+    <title>IndexNow</title>
     <style>
         * { margin: 0; padding: 0; box-sizing: border-box; }
         body {
             background: #000;
             color: #fff;
-            font-family: 'Inter', -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
             min-height: 100vh;
-            display: flex;
-            flex-direction: column;
         }
-        
-        .topbar {
+        .header {
             border-bottom: 1px solid #1a1a1a;
-            padding: 1.25rem 1.5rem;
+            padding: 16px 24px;
             display: flex;
             justify-content: space-between;
             align-items: center;
-            position: sticky;
-            top: 0;
-            background: rgba(0,0,0,0.95);
-            backdrop-filter: blur(10px);
-            z-index: 100;
         }
-        .topbar-left { display: flex; align-items: center; gap: 1rem; }
-        .topbar-title { font-size: 1rem; font-weight: 600; letter-spacing: -0.01em; }
-        .topbar-right { display: flex; gap: 0.75rem; }
-        .topbar-link {
-            color: #a0a0a0;
-            text-decoration: none;
-            font-size: 0.8125rem;
-            font-weight: 500;
-            padding: 0.5rem 0.75rem;
-            border-radius: 4px;
-            transition: all 0.15s ease;
+        .header h1 {
+            font-size: 16px;
+            font-weight: 600;
+        }
+        .btn-secondary {
+            background: transparent;
+            color: #666;
+            border: 1px solid #333;
+            padding: 8px 16px;
+            font-size: 13px;
+            font-weight: 500;
+            border-radius: 4px;
+            text-decoration: none;
+        }
+        .btn-secondary:hover {
+            color: #fff;
+            border-color: #555;
+        }
+        .container {
+            max-width: 800px;
+            margin: 0 auto;
+            padding: 32px 24px;
+        }
+        .summary {
+            background: #0a0a0a;
+            border: 1px solid #1a1a1a;
+            border-radius: 4px;
+            padding: 16px;
+            margin-bottom: 24px;
+            font-size: 13px;
+            color: #999;
+        }
+        .summary a {
+            color: #fff;
+        }
+        .submissions {
+            display: flex;
+            flex-direction: column;
+            gap: 8px;
+        }
+        .submission {
+            background: #0a0a0a;
+            border: 1px solid #1a1a1a;
+            border-radius: 4px;
+            padding: 12px 16px;
+            font-size: 13px;
+        }
+        .submission summary {
+            cursor: pointer;
+            color: #999;
+        }
+        .ok {
+            color: #6c6;
+        }
+        .failed {
+            color: #c66;
+        }
+        .urls {
+            list-style: none;
+            margin-top: 8px;
+            font-family: monospace;
+            font-size: 12px;
+            color: #666;
+            word-break: break-all;
+        }
+        .loading {
+            text-align: center;
+            padding: 40px;
+            color: #666;
+        }
+    </style>
+</head>
+<body>
+    <div class="header">
+        <h1>IndexNow</h1>
+        <a href="/admin/dashboard" class="btn-secondary">Back to Dashboard</a>
+    </div>
+    <div class="container">
+        <div class="summary">{{status}}</div>
+        <div class="submissions">{{submissions}}</div>
+    </div>
+</body>
+</html>"#;
+
+#[tokio::main]
+async fn main() {
+    let _ = dotenv();
+
+    // `aryansrao-blog check-links [--internal-only]`: print broken links and exit non-zero if any
+    if env::args().nth(1).as_deref() == Some("check-links") {
+        let external = !env::args().any(|a| a == "--internal-only");
+        let site_config = SiteConfig::default();
+        let report = check_links(&get_posts(&site_config), &site_config.url, &LinkCheckOptions::from_env(external), |_| {}).await;
+        for broken in &report.broken {
+            let line = broken.line.map(|l| l.to_string()).unwrap_or_else(|| "?".into());
+            println!("content/{}:{}: {} - {} ({})", broken.file, line, broken.url, broken.reason, broken.post);
+        }
+        println!(
+            "Checked {} links ({} external) in {} posts: {} broken",
+            report.links, report.external, report.posts, report.broken.len()
+        );
+        std::process::exit(if report.broken.is_empty() { 0 } else { 1 });
+    }
+
+    // `aryansrao-blog check`: print render warnings and exit non-zero if any
+    if env::args().nth(1).as_deref() == Some("check") {
+        let posts = get_posts(&SiteConfig::default());
+        let mut count = 0;
+        for post in &posts {
+            for warning in &post.warnings {
+                println!("content/{}:{}: {}", post.filename, warning.line, warning.message);
+                count += 1;
+            }
+        }
+        println!("Checked {} posts: {} warnings", posts.len(), count);
+        std::process::exit(if count == 0 { 0 } else { 1 });
+    }
+
+    let mut hb = Handlebars::new();
+    hb.set_strict_mode(false); // Allow missing variables
+    
+    if let Err(e) = hb.register_template_file("index.html", "templates/index.html") {
+        eprintln!("Failed to register index template: {}", e);
+        return;
+    }
+    if let Err(e) = hb.register_template_file("single.html", "templates/single.html") {
+        eprintln!("Failed to register single template: {}", e);
+        return;
+    }
+    let hb = Arc::new(hb);
+    
+    // Initialize admin state
+    let admin_state = AdminState::new();
+
+    // Build-time work: fetch link previews and render diagrams the posts need
+    on_startup();
+
+    // Pages and feeds rendered from content/ answer conditional requests before rendering
+    let pages = Router::new()
+        .route("/", get(index))
+        .route("/tags/{tag}", get(tag_page))
+        .route("/blog/{post_title}", get(single_post))
+        .route("/blogroll", get(blogroll_page))
+        .route("/api/search", get(search_posts))
+        .route_layer(axum::middleware::from_fn_with_state(None, content_validators));
+    let feeds = Router::new()
+        .route("/sitemap.xml", get(sitemap))
+        .route("/sitemap-posts.xml", get(sitemap_posts))
+        .route("/sitemap-posts-{page}", get(sitemap_posts_page))
+        .route("/sitemap-tags.xml", get(sitemap_tags_xml))
+        .route("/sitemap-pages.xml", get(sitemap_pages))
+        .route("/rss.xml", get(rss_feed))
+        .route("/feed.xml", get(rss_feed))
+        .route("/atom.xml", get(atom_feed))
+        .route("/feed.json", get(json_feed))
+        .route("/tags/{tag}/{format}", get(tag_feed))
+        .route("/authors/{author}/{format}", get(author_feed))
+        .route("/feed", get(rss_feed))
+        .route("/podcast.xml", get(podcast_feed))
+        .route("/feeds.opml", get(feeds_opml))
+        .route("/blogroll.opml", get(blogroll_opml))
+        .route_layer(axum::middleware::from_fn_with_state(Some("public, max-age=3600"), content_validators));
+
+    let mut app = Router::new()
+        .merge(pages)
+        .merge(feeds)
+        // Public routes
+        .route("/blog", get(blog_redirect))
+        .route("/blog/", get(blog_redirect))
+        // Static assets - favicon/logo
+        .route("/logo.webp", get(serve_logo))
+        .route("/favicon.webp", get(serve_logo))
+        .route("/favicon.png", get(serve_logo))
+        .route("/favicon.ico", get(serve_logo))
+        .route("/apple-touch-icon.webp", get(serve_logo))
+        .route("/apple-touch-icon.png", get(serve_logo))
+        // Post images and their responsive variants
+        .route("/images/{*path}", get(serve_image))
+        .route("/media/{file}", get(serve_image_variant))
+        // Dynamic OG Image generation
+        .route("/blog/recents.png", get(og_image_recents))
+        .route("/blog/{slug}/og.png", get(og_image))
+        .route("/blog/{slug}/{*asset}", get(serve_bundle_asset))
+        .route("/og.png", get(og_image_default))
+        .route("/robots.txt", get(robots_txt))
+        .route("/websub", post(websub_hub))
+        // PWA & SEO metadata files
+        .route("/manifest.json", get(manifest_json))
+        .route("/browserconfig.xml", get(browserconfig_xml))
+        .route("/humans.txt", get(humans_txt))
+        .route("/.well-known/security.txt", get(security_txt))
+        // GitHub integration routes (public)
+        .route("/api/github/repos", get(list_github_repos))
+        .route("/api/github/sync", get(sync_github_repos))
+        // Admin routes
+        .route("/admin", get(admin_login_page))
+        .route("/admin/login", post(admin_login_submit))
+        .route("/admin/logout", get(admin_logout))
+        .route("/admin/dashboard", get(admin_dashboard))
+        .route("/admin/new", get(admin_new_post))
+        .route("/admin/edit/{slug}", get(admin_edit_post))
+        .route("/admin/save", post(admin_save_post))
+        .route("/admin/delete/{slug}", delete(admin_delete_post))
+        .route("/admin/github", get(admin_github_page))
+        .route("/admin/api/repos", get(admin_list_repos))
+        .route("/admin/links", get(admin_links_page))
+        .route("/admin/indexnow", get(admin_indexnow_page))
+        .route("/admin/api/links", get(admin_link_check_status).post(admin_check_links))
+        .route("/admin/api/warnings", post(admin_render_warnings))
+        .route("/admin/github/import", post(admin_import_repo))
+        .route("/admin/sync/{slug}", post(admin_sync_repo))
+        // Webhook for GitHub auto-sync
+        .route("/api/webhook/github", post(github_webhook));
+    // IndexNow key file; a static route, since the key is only known at startup
+    if let Some(key) = INDEXNOW_KEY.as_deref() {
+        app = app.route(&format!("/{}.txt", key), get(indexnow_key_file));
+    }
+    let app = app
+        .layer(axum::middleware::from_fn(conditional_get))
+        .layer(Extension(hb))
+        .with_state(admin_state);
+
+    let base_url = SiteConfig::default().url;
+    println!("Server running at {}", base_url);
+    println!("Blog index: {}/", base_url);
+    println!("Admin panel: {}/admin", base_url);
+    println!("Sitemap: {}/sitemap.xml", base_url);
+    println!("RSS Feed: {}/rss.xml", base_url);
+    
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
+    axum::serve(listener, app).await.unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A hand-written post with no siblings, rendered for a page
+    fn test_ctx<'a>(posts: &'a [PostRef], disabled_stages: &'a [String]) -> RenderContext<'a> {
+        RenderContext {
+            posts,
+            sanitize: SanitizeProfile::Standard,
+            slug: "test",
+            bundle: None,
+            disabled_stages,
+            line_offset: 0,
+            profile: RenderProfile::Page,
+            site_url: "https://example.com",
+        }
+    }
+
+    fn render(markdown: &str) -> RenderedDocument {
+        render_markdown(markdown, &test_ctx(&[], &[]))
+    }
+
+    fn warning_lines(document: &RenderedDocument) -> Vec<(usize, String)> {
+        document.warnings.iter().map(|w| (w.line, w.message.clone())).collect()
+    }
+
+    // A post with nothing but a title and slug; tests fill in what they need
+    fn test_post(slug: &str) -> Post {
+        Post {
+            title: format!("Post {}", slug),
+            content: String::new(),
+            summary: String::new(),
+            date: String::new(),
+            date_iso: String::new(),
+            updated_iso: String::new(),
+            tags: Vec::new(),
+            filename: format!("{}.md", slug),
+            slug: slug.to_string(),
+            author: String::new(),
+            image: String::new(),
+            image_alt: String::new(),
+            keywords: String::new(),
+            canonical: String::new(),
+            reading_time: 1,
+            word_count: 0,
+            github_repo: None,
+            website: None,
+            excerpt: None,
+            toc: Vec::new(),
+            enclosure: None,
+            text: String::new(),
+            links: Vec::new(),
+            images: Vec::new(),
+            ids: Default::default(),
+            warnings: Vec::new(),
+            published: Local::now(),
+            updated: Local::now(),
+        }
+    }
+
+    // Serve a stub on a free local port, returning its base URL
+    async fn serve(app: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    // A path under the system temp directory no other test uses
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("{}-{}", Uuid::new_v4(), name))
+    }
+
+    mod diagrams {
+        use super::*;
+
+        #[test]
+        fn sanitize_svg_drops_script_capable_elements() {
+            let svg = concat!(
+                r#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" onload="alert(1)">"#,
+                r#"<style>body { display: none }</style>"#,
+                r#"<foreignObject><div onclick="x()">label</div></foreignObject>"#,
+                r#"<a href="javascript:alert(1)"><text>link</text></a>"#,
+                r#"<set attributeName="href" to="javascript:alert(1)"/>"#,
+                r#"<animate attributeName="href" values="javascript:alert(1)"></animate>"#,
+                r#"<script>alert(1)</script><rect width="10" height="10"/></svg>"#,
+            );
+            let clean = sanitize_svg(svg);
+            for banned in ["<?xml", "onload", "<style", "foreignObject", "javascript:", "<set", "<animate", "<script"] {
+                assert!(!clean.contains(banned), "{} survived: {}", banned, clean);
+            }
+            assert!(clean.starts_with("<svg"));
+            assert!(clean.contains(r#"<rect width="10" height="10"/>"#));
+            assert!(clean.contains("<text>link</text>"));
+        }
+
+        #[test]
+        fn failed_diagrams_are_not_requeued_until_retry() {
+            let source = "digraph { failed_diagram_test -> b }";
+            let hash = content_hash(&format!("dot\n{}", source));
+            DIAGRAM_FAILURES.lock().unwrap().insert(hash.clone(), Instant::now());
+            assert!(render_diagram(source, "dot").is_none());
+            assert!(!PENDING_DIAGRAMS.lock().unwrap().contains_key(&hash));
+
+            DIAGRAM_FAILURES.lock().unwrap().insert(hash.clone(), Instant::now() - DIAGRAM_RETRY);
+            assert!(render_diagram(source, "dot").is_none());
+            assert!(PENDING_DIAGRAMS.lock().unwrap().remove(&hash).is_some());
+        }
+    }
+
+    mod shortcodes {
+        use super::*;
+
+        #[test]
+        fn unknown_shortcodes_and_posts_are_warnings() {
+            let document = render("Intro\n\n{{< nope >}}\n\nSee {{< post missing-post >}} here.\n");
+            assert_eq!(
+                warning_lines(&document),
+                vec![
+                    (3, "Unknown shortcode `nope`".to_string()),
+                    (5, "Shortcode `post` references unknown post `missing-post`".to_string()),
+                ]
+            );
+            assert!(!document.html.contains("{{<"));
+        }
+
+        #[test]
+        fn figure_rejects_script_urls() {
+            let document = render("{{< figure src=\"javascript:alert(1)\" caption=\"x\" >}}\n");
+            assert!(!document.html.contains("javascript:"));
+            assert_eq!(document.warnings.len(), 1);
+
+            let document = render("{{< figure src=\"/images/a.png\" caption=\"A <b>\" >}}\n");
+            assert!(document.html.contains(r#"<img src="/images/a.png" alt="A &lt;b&gt;""#));
+            assert!(document.warnings.is_empty());
+        }
+
+        #[test]
+        fn gist_needs_a_well_formed_id() {
+            let document = render("{{< gist \"x.js\\\"></script><script>alert(1)//\" >}}\n");
+            assert!(!document.html.contains("<script"));
+            assert_eq!(document.warnings.len(), 1);
+
+            let document = render("{{< gist octocat/6cad326836d38bd3a7ae >}}\n");
+            assert!(document.html.contains(r#"<script src="https://gist.github.com/octocat/6cad326836d38bd3a7ae.js"></script>"#));
+            assert!(document.warnings.is_empty());
+        }
+
+        #[test]
+        fn shortcodes_in_code_are_left_alone() {
+            let document = render("`{{< nope >}}`\n\n```text\n{{< nope >}}\n```\n");
+            assert!(document.warnings.is_empty());
+            assert_eq!(document.html.matches("{{&lt; nope &gt;}}").count(), 2);
+        }
+    }
+
+    mod excerpts {
+        use super::*;
+
+        #[test]
+        fn excerpt_is_the_document_above_the_marker() {
+            let document = render("Intro with a note.[^1]\n\n<!--more-->\n\nBody.\n\n[^1]: The note.\n");
+            let excerpt = document.excerpt.expect("marker should produce an excerpt");
+            assert!(excerpt.contains("Intro with a note."));
+            assert!(!excerpt.contains("Body."));
+            assert!(!document.html.contains("more"), "marker left in page: {}", document.html);
+            assert!(document.html.contains("Body."));
+            assert!(document.html.contains("The note."));
+        }
+
+        #[test]
+        fn markers_in_code_and_lists_are_ignored() {
+            let document = render("```html\n<!--more-->\n```\n\n- item\n\n  <!--more-->\n");
+            assert!(document.excerpt.is_none());
         }
-        .topbar-link:hover { color: #fff; background: #1a1a1a; }
-        
-        .main {
-            flex: 1;
-            max-width: 1000px;
-            width: 100%;
-            margin: 0 auto;
-            padding: 2rem 1.5rem;
+
+        #[test]
+        fn unwrap_links_keeps_text_and_survives_unclosed_tags() {
+            assert_eq!(unwrap_links(r#"<p>See <a href="/x">this</a></p>"#), "<p>See this</p>");
+            assert_eq!(unwrap_links("a <é"), "a <é");
+            assert_eq!(unwrap_links("<"), "<");
         }
-        
-        .stats-grid {
-            display: grid;
-            grid-template-columns: repeat(auto-fit, minmax(200px, 1fr));
-            gap: 1rem;
-            margin-bottom: 2rem;
+    }
+
+    mod images {
+        use super::*;
+
+        async fn variant(file: String) -> StatusCode {
+            serve_image_variant(Path(file)).await.into_response().status()
         }
-        .stat-card {
-            background: #0a0a0a;
-            border: 1px solid #1a1a1a;
-            border-radius: 8px;
-            padding: 1.5rem;
+
+        #[tokio::test]
+        async fn variants_are_served_only_for_recorded_hashes_and_widths() {
+            let path = temp_path("variant.png");
+            image::RgbImage::from_pixel(40, 30, image::Rgb([12, 34, 56])).save(&path).unwrap();
+            let hash = format!("{:x}", Sha256::digest(fs::read(&path).unwrap()));
+            IMAGE_SOURCES.lock().unwrap().insert(hash.clone(), path.clone());
+
+            assert_eq!(variant(format!("{}-40.webp", hash)).await, StatusCode::OK);
+            let _ = fs::remove_file(PathBuf::from(IMAGE_CACHE_DIR).join(format!("{}-40.webp", hash)));
+
+            let unknown = format!("{:x}", Sha256::digest(b"never rendered"));
+            assert_eq!(variant(format!("{}-40.webp", unknown)).await, StatusCode::NOT_FOUND);
+            // Only the image's own width fits under it; larger or unlisted widths are never generated
+            assert_eq!(variant(format!("{}-41.webp", hash)).await, StatusCode::NOT_FOUND);
+            assert_eq!(variant(format!("{}-{}.webp", hash, IMAGE_WIDTHS[0])).await, StatusCode::NOT_FOUND);
+            assert_eq!(variant(format!("{}-40.png", hash)).await, StatusCode::NOT_FOUND);
+            assert_eq!(variant(format!("{}-x.webp", hash)).await, StatusCode::NOT_FOUND);
+
+            // A recorded hash whose file has since changed no longer matches it
+            image::RgbImage::from_pixel(50, 30, image::Rgb([12, 34, 56])).save(&path).unwrap();
+            assert_eq!(variant(format!("{}-40.webp", hash)).await, StatusCode::NOT_FOUND);
+
+            IMAGE_SOURCES.lock().unwrap().retain(|_, source| *source != path);
+            let _ = fs::remove_file(path);
         }
-        .stat-value { font-size: 2rem; font-weight: 700; letter-spacing: -0.02em; }
-        .stat-label { font-size: 0.75rem; color: #666; margin-top: 0.5rem; text-transform: uppercase; letter-spacing: 0.05em; }
-        
-        .section {
-            margin-bottom: 2rem;
+    }
+
+    mod bundles {
+        use super::*;
+
+        #[test]
+        fn bundle_files_stay_inside_the_bundle_and_skip_the_post() {
+            let dir = std::path::Path::new("content/my-post");
+            assert_eq!(bundle_file(dir, "cover.png"), Some(dir.join("cover.png")));
+            assert_eq!(bundle_file(dir, "audio/episode.mp3"), Some(dir.join("audio/episode.mp3")));
+            for asset in ["index.md", "../other/index.md", "audio/../../secret", "./index.md", "/etc/passwd", ""] {
+                assert_eq!(bundle_file(dir, asset), None, "{} was served", asset);
+            }
         }
-        .section-header {
-            display: flex;
-            justify-content: space-between;
-            align-items: center;
-            margin-bottom: 1rem;
-            padding-bottom: 0.75rem;
-            border-bottom: 1px solid #1a1a1a;
+
+        #[tokio::test]
+        async fn unsafe_asset_paths_are_not_found() {
+            for asset in ["index.md", "../Cargo.toml", "../../Cargo.toml"] {
+                let response = serve_bundle_asset(HeaderMap::new(), Path(("my-post".to_string(), asset.to_string())))
+                    .await
+                    .into_response();
+                assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", asset);
+            }
         }
-        .section-title { font-size: 0.875rem; font-weight: 600; color: #fff; }
-        .btn-group { display: flex; gap: 0.5rem; }
-        
-        .btn {
-            padding: 0.625rem 1rem;
-            font-size: 0.8125rem;
-            font-weight: 500;
-            border: none;
-            border-radius: 6px;
-            cursor: pointer;
-            transition: all 0.15s ease;
-            font-family: inherit;
-            display: inline-flex;
-            align-items: center;
-            gap: 0.375rem;
-            text-decoration: none;
+
+        #[test]
+        fn relative_urls_resolve_against_the_bundle() {
+            let dir = PathBuf::from("content/my-post");
+            let mut ctx = test_ctx(&[], &[]);
+            ctx.bundle = Some(&dir);
+            assert_eq!(bundle_asset("./x.png", &ctx), Some(("/blog/test/x.png".to_string(), dir.join("x.png"))));
+            assert_eq!(
+                bundle_asset("img/x.png?v=2#top", &ctx),
+                Some(("/blog/test/img/x.png?v=2#top".to_string(), dir.join("img/x.png")))
+            );
+            for url in ["../x.png", "/images/x.png", "https://example.com/x.png", "mailto:a@b", "#x", ""] {
+                assert_eq!(bundle_asset(url, &ctx), None, "{} resolved", url);
+            }
+
+            ctx.bundle = None;
+            assert_eq!(bundle_asset("./x.png", &ctx), None);
         }
-        .btn-primary {
-            background: #fff;
-            color: #000;
+    }
+
+    mod readme_urls {
+        use super::*;
+
+        // Prefix relative URLs, marking images so the tests can tell them apart
+        fn rewrite(markdown: &str) -> String {
+            rewrite_markdown_urls(markdown, |url, is_image| {
+                (!url.starts_with(['#', '/']) && !url.contains(':'))
+                    .then(|| format!("{}https://x.test/{}", if is_image { "img:" } else { "" }, url))
+            })
         }
-        .btn-primary:hover { background: #f0f0f0; }
-        .btn-secondary {
-            background: #1a1a1a;
-            color: #a0a0a0;
-            border: 1px solid #333;
+
+        #[test]
+        fn rewrites_the_destination_not_the_title() {
+            assert_eq!(rewrite(r#"![a.png](a.png "a.png")"#), r#"![a.png](img:https://x.test/a.png "a.png")"#);
+            assert_eq!(rewrite("[docs](docs.md 'docs.md')"), "[docs](https://x.test/docs.md 'docs.md')");
         }
-        .btn-secondary:hover { background: #222; color: #fff; }
-        
-        .posts-table {
-            width: 100%;
-            border-collapse: collapse;
-            background: #0a0a0a;
-            border: 1px solid #1a1a1a;
-            border-radius: 8px;
-            overflow: hidden;
+
+        #[test]
+        fn rewrites_escaped_and_angle_destinations() {
+            assert_eq!(rewrite(r"[x](a\_b.md)"), "[x](https://x.test/a_b.md)");
+            assert_eq!(rewrite("[x](<my file.md>)"), "[x](<https://x.test/my file.md>)");
+            assert_eq!(rewrite("[x](a(1).md)"), "[x](<https://x.test/a(1).md>)");
         }
-        .posts-table thead {
-            background: #0f0f0f;
-            border-bottom: 1px solid #1a1a1a;
+
+        #[test]
+        fn rewrites_nested_images_and_references() {
+            assert_eq!(
+                rewrite("[![badge](b.svg)](page.md)"),
+                "[![badge](img:https://x.test/b.svg)](https://x.test/page.md)"
+            );
+            assert_eq!(
+                rewrite("![logo][l] and [docs]\n\n[l]: logo.png \"logo.png\"\n[docs]: docs.md\n"),
+                "![logo][l] and [docs]\n\n[l]: img:https://x.test/logo.png \"logo.png\"\n[docs]: https://x.test/docs.md\n"
+            );
         }
-        .posts-table th {
-            padding: 0.875rem;
-            text-align: left;
-            font-size: 0.75rem;
-            font-weight: 600;
-            text-transform: uppercase;
-            letter-spacing: 0.05em;
-            color: #666;
+
+        #[test]
+        fn rewrites_html_tags_across_lines_and_keeps_anchors() {
+            assert_eq!(
+                rewrite("<p align=\"center\">\n  <img\n    src=\"shot.png\"\n    width=\"200\">\n</p>\n"),
+                "<p align=\"center\">\n  <img src=\"img:https://x.test/shot.png\" width=\"200\">\n</p>\n"
+            );
+            assert_eq!(rewrite("[top](#top) and [site](https://a.test/x)"), "[top](#top) and [site](https://a.test/x)");
         }
-        .posts-table td {
-            padding: 0.875rem;
-            border-top: 1px solid #1a1a1a;
-            font-size: 0.9375rem;
+    }
+
+    mod mirrors {
+        use super::*;
+
+        fn test_repo() -> GitHubRepo {
+            GitHubRepo {
+                name: "repo".to_string(),
+                full_name: "owner/repo".to_string(),
+                description: None,
+                html_url: "https://github.com/owner/repo".to_string(),
+                pushed_at: None,
+                updated_at: None,
+                language: None,
+                stargazers_count: 0,
+                fork: false,
+                archived: false,
+                topics: None,
+                homepage: None,
+                default_branch: None,
+            }
         }
-        .post-title { font-weight: 600; color: #fff; }
-        .post-meta { font-size: 0.8125rem; color: #666; }
-        .table-actions { display: flex; gap: 0.5rem; }
-        .btn-sm {
-            padding: 0.375rem 0.75rem;
-            font-size: 0.75rem;
-            background: #1a1a1a;
-            color: #a0a0a0;
-            border: none;
-            border-radius: 4px;
-            cursor: pointer;
-            transition: all 0.15s ease;
+
+        #[tokio::test]
+        async fn identical_images_are_mirrored_once_with_every_origin() {
+            // A color of its own, so no file mirrored earlier has the same content
+            let [r, g, b, ..] = *Uuid::new_v4().as_bytes();
+            let mut png = Vec::new();
+            image::RgbImage::from_pixel(2, 2, image::Rgb([r, g, b]))
+                .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+                .unwrap();
+            let served = png.clone();
+            let base = serve(Router::new().route(
+                "/{*path}",
+                get(move || {
+                    let png = served.clone();
+                    async move { png }
+                }),
+            ))
+            .await;
+            let manifest_before = fs::read_to_string(MIRROR_MANIFEST).ok();
+
+            let (first, second) = (format!("{}/logo.png", base), format!("{}/docs/logo-copy", base));
+            let url = mirror_image(&first, &test_repo()).await.unwrap();
+            assert_eq!(mirror_image(&second, &test_repo()).await.unwrap(), url);
+
+            let file = url.strip_prefix("/images/github/").unwrap();
+            assert!(file.ends_with(".png"));
+            let path = PathBuf::from(MIRROR_DIR).join(file);
+            assert_eq!(fs::read(&path).unwrap(), png);
+            let manifest: HashMap<String, MirroredImage> =
+                serde_json::from_str(&fs::read_to_string(MIRROR_MANIFEST).unwrap()).unwrap();
+            let entry = &manifest[file];
+            assert_eq!(entry.origins, vec![first, second]);
+            assert_eq!(entry.repos, vec!["owner/repo"]);
+            assert_eq!(entry.size, png.len());
+
+            let _ = fs::remove_file(path);
+            let _ = fs::remove_dir(MIRROR_DIR);
+            let _ = fs::remove_dir(IMAGES_DIR);
+            match manifest_before {
+                Some(json) => fs::write(MIRROR_MANIFEST, json).unwrap(),
+                None => fs::remove_file(MIRROR_MANIFEST).unwrap(),
+            }
         }
-        .btn-sm:hover { background: #2a2a2a; color: #fff; }
-        .btn-danger { color: #ff6b6b; }
-        .btn-danger:hover { background: #330000; }
-        
-        .empty-state {
-            text-align: center;
-            padding: 3rem 1rem;
-            color: #666;
+    }
+
+    mod links {
+        use super::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // A site with a working page, a missing one and redirects to each, counting requests
+        async fn stub() -> (String, Arc<AtomicUsize>) {
+            let hits = Arc::new(AtomicUsize::new(0));
+            let counter = hits.clone();
+            let app = Router::new()
+                .route("/ok", get(|| async { "ok" }))
+                .route("/missing", get(|| async { StatusCode::NOT_FOUND }))
+                .route("/moved", get(|| async { Redirect::permanent("/ok") }))
+                .route("/moved-missing", get(|| async { Redirect::temporary("/missing") }))
+                .layer(axum::middleware::from_fn(move |request: axum::extract::Request, next: axum::middleware::Next| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    next.run(request)
+                }));
+            (serve(app).await, hits)
         }
-        .empty-state-icon {
-            font-size: 2.5rem;
-            margin-bottom: 0.5rem;
-            opacity: 0.3;
+
+        fn options(cache_file: PathBuf) -> LinkCheckOptions {
+            LinkCheckOptions { external: true, interval: Duration::ZERO, cache_file, cache_ttl: 3600 }
         }
-        
-        @media (max-width: 640px) {
-            .main { padding: 1rem; }
-            .stats-grid { grid-template-columns: 1fr; }
-            .section-header { flex-direction: column; align-items: flex-start; gap: 0.75rem; }
-            .btn-group { width: 100%; flex-wrap: wrap; }
+
+        fn broken(report: &LinkReport) -> Vec<(String, String)> {
+            report.broken.iter().map(|b| (b.url.clone(), b.reason.clone())).collect()
         }
-    </style>
-</head>
-<body>
-    <div class="topbar">
-        <div class="topbar-left">
-            <span class="topbar-title">Admin Dashboard</span>
-        </div>
-        <div class="topbar-right">
-            <a href="/" class="topbar-link">← View Blog</a>
-            <a href="/admin/logout" class="topbar-link">Logout</a>
-        </div>
-    </div>
-    
-    <div class="main">
-        <div class="stats-grid">
-            <div class="stat-card">
-                <div class="stat-value">{{posts_count}}</div>
-                <div class="stat-label">Total Posts</div>
-            </div>
-        </div>
-        
-        <div class="section">
-            <div class="section-header">
-                <span class="section-title">Posts</span>
-                <div class="btn-group">
-                    <a href="/admin/github" class="btn btn-secondary">GitHub</a>
-                    <a href="/admin/new" class="btn btn-primary">+ New Post</a>
-                </div>
-            </div>
-            <div>{{posts_list}}</div>
-        </div>
-    </div>
-    
-    <script>
-        async function deletePost(slug) {
-            if (!confirm('Delete this post?')) return;
-            const res = await fetch('/admin/delete/' + slug, { method: 'DELETE' });
-            const data = await res.json();
-            if (data.success) location.reload();
-            else alert('Error: ' + data.error);
+
+        #[tokio::test]
+        async fn reports_error_statuses_and_follows_redirects() {
+            let (base, _) = stub().await;
+            let mut post = test_post("links");
+            post.links = ["/ok", "/missing", "/moved", "/moved-missing"].iter().map(|p| format!("{}{}", base, p)).collect();
+            let cache = temp_path("links.json");
+
+            let report = check_links(&[post], "https://example.com", &options(cache.clone()), |_| {}).await;
+            assert_eq!(report.external, 4);
+            assert_eq!(
+                broken(&report),
+                vec![
+                    (format!("{}/missing", base), "HTTP 404 Not Found".to_string()),
+                    (format!("{}/moved-missing", base), "HTTP 404 Not Found".to_string()),
+                ]
+            );
+            let _ = fs::remove_file(cache);
         }
-    </script>
-</body>
-</html>"#;
 
-const ADMIN_EDITOR_HTML: &str = r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Edit Post</title>
-    <style>
-        * { margin: 0; padding: 0; box-sizing: border-box; }
-        body {
-            background: #000;
-            color: #fff;
-            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
-            min-height: 100vh;
+        #[tokio::test]
+        async fn cached_results_are_reused_until_they_expire() {
+            let (base, hits) = stub().await;
+            let mut post = test_post("links");
+            post.links = vec![format!("{}/ok", base), format!("{}/missing", base)];
+            let posts = [post];
+            let cache = temp_path("links.json");
+
+            let first = check_links(&posts, "https://example.com", &options(cache.clone()), |_| {}).await;
+            let requests = hits.load(Ordering::SeqCst);
+            assert!(requests >= 2);
+
+            // Both the working and the broken link come from the cache
+            let second = check_links(&posts, "https://example.com", &options(cache.clone()), |_| {}).await;
+            assert_eq!(hits.load(Ordering::SeqCst), requests);
+            assert_eq!(broken(&second), broken(&first));
+
+            let expired = LinkCheckOptions { cache_ttl: 0, ..options(cache.clone()) };
+            check_links(&posts, "https://example.com", &expired, |_| {}).await;
+            assert!(hits.load(Ordering::SeqCst) > requests);
+            let _ = fs::remove_file(cache);
         }
-        .header {
-            border-bottom: 1px solid #1a1a1a;
-            padding: 16px 24px;
-            display: flex;
-            justify-content: space-between;
-            align-items: center;
-            position: sticky;
-            top: 0;
-            background: #000;
-            z-index: 100;
+
+        #[tokio::test]
+        async fn unreachable_hosts_are_reported_but_not_cached() {
+            // Bind and drop a listener to find a port nothing is listening on
+            let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+            let url = format!("http://127.0.0.1:{}/gone", port);
+            let mut post = test_post("links");
+            post.links = vec![url.clone()];
+            let cache = temp_path("links.json");
+
+            let report = check_links(&[post], "https://example.com", &options(cache.clone()), |_| {}).await;
+            assert_eq!(report.broken.len(), 1);
+            assert!(report.broken[0].reason.starts_with("request failed"));
+            let cached: HashMap<String, ExternalCheck> = serde_json::from_str(&fs::read_to_string(&cache).unwrap()).unwrap();
+            assert!(!cached.contains_key(&url));
+            let _ = fs::remove_file(cache);
         }
-        .header h1 {
-            font-size: 16px;
-            font-weight: 600;
+
+        #[tokio::test]
+        async fn internal_links_are_checked_against_the_site() {
+            let mut post = test_post("first");
+            post.ids.insert("intro".to_string());
+            post.links = vec![
+                "#intro".to_string(),
+                "#outro".to_string(),
+                "/blog/first#intro".to_string(),
+                "https://example.com/blog/second".to_string(),
+            ];
+            let options = LinkCheckOptions { external: false, ..options(temp_path("links.json")) };
+
+            let report = check_links(&[post], "https://example.com", &options, |_| {}).await;
+            assert_eq!(report.external, 0);
+            assert_eq!(
+                broken(&report),
+                vec![
+                    ("#outro".to_string(), "no element with id 'outro' on this page".to_string()),
+                    ("https://example.com/blog/second".to_string(), "no post with slug 'second'".to_string()),
+                ]
+            );
         }
-        .header-actions {
-            display: flex;
-            gap: 12px;
+    }
+
+    mod reading_time {
+        use super::*;
+
+        #[test]
+        fn cjk_is_counted_by_character() {
+            assert_eq!(calculate_reading_time("<p>Rust 很快，也很安全。</p>"), (1, 7));
+            // 1,500 characters at 500 a minute
+            let html = format!("<p>{}</p>", "字".repeat(1500));
+            assert_eq!(calculate_reading_time(&html), (3, 1500));
         }
-        .btn {
-            background: #fff;
-            color: #000;
-            padding: 8px 16px;
-            font-size: 13px;
-            font-weight: 500;
-            border-radius: 4px;
-            text-decoration: none;
-            border: none;
-            cursor: pointer;
+
+        #[test]
+        fn code_is_read_slower_and_its_header_is_skipped() {
+            let document = render("Intro words here.\n\n```rust\nlet x = 1;\n```\n");
+            assert!(document.html.contains("code-header"), "no header rendered: {}", document.html);
+            assert_eq!(calculate_reading_time(&document.html).1, 3 + 4);
+
+            // The same 400 words take 2 minutes as prose and 4 as code
+            let words = "word ".repeat(400);
+            assert_eq!(calculate_reading_time(&format!("<p>{}</p>", words)).0, 2);
+            assert_eq!(calculate_reading_time(&format!("<pre><code>{}</code></pre>", words)).0, 4);
+        }
+
+        #[test]
+        fn images_and_diagrams_add_time_but_no_words() {
+            let images = r#"<img src="a.png" alt="A long description">"#.repeat(8);
+            let diagram = r#"<figure class="diagram"><svg><text>node label</text></svg></figure>"#;
+            // 10 pictures at 12 seconds each
+            let html = format!("<p>One word</p>{}{}{}", images, diagram, diagram);
+            assert_eq!(calculate_reading_time(&html), (2, 2));
+        }
+    }
+
+    mod link_previews {
+        use super::*;
+
+        #[tokio::test]
+        async fn http_images_are_not_fetched() {
+            let page = r#"<html><head><meta property="og:title" content="Stub &amp; Co"><meta property="og:image" content="/card.png"></head></html>"#;
+            let base = serve(Router::new().route("/", get(move || async move { Html(page) }))).await;
+
+            let preview = fetch_link_preview(&format!("{}/", base)).await.unwrap();
+            assert_eq!(preview.title, "Stub & Co");
+            assert_eq!(preview.image, None);
+        }
+
+        // A server whose page never ends: `head` followed by filler for as long as the client reads
+        async fn endless_page(head: &'static str) -> String {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move {
+                while let Ok((mut socket, _)) = listener.accept().await {
+                    tokio::spawn(async move {
+                        use tokio::io::AsyncWriteExt;
+                        let mut request = [0; 1024];
+                        let _ = tokio::io::AsyncReadExt::read(&mut socket, &mut request).await;
+                        let header = "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n";
+                        if socket.write_all(format!("{}{}", header, head).as_bytes()).await.is_err() {
+                            return;
+                        }
+                        let filler = "<p>and on</p>".repeat(1000);
+                        while socket.write_all(filler.as_bytes()).await.is_ok() {}
+                    });
+                }
+            });
+            format!("http://{}/", addr)
+        }
+
+        #[tokio::test]
+        async fn pages_are_read_only_up_to_the_head_or_the_cap() {
+            let closed = endless_page("<html><head><title>Closed</title></head><body>").await;
+            assert_eq!(fetch_link_preview(&closed).await.unwrap().title, "Closed");
+
+            // No `</head>` ever arrives, so reading stops at MAX_PREVIEW_PAGE
+            let open = endless_page("<html><head><title>Open</title>").await;
+            let preview = tokio::time::timeout(Duration::from_secs(5), fetch_link_preview(&open)).await;
+            assert_eq!(preview.unwrap().unwrap().title, "Open");
         }
-        .btn:hover {
-            background: #e0e0e0;
+
+        #[test]
+        fn cards_never_hotlink_images() {
+            let url = "https://cards.example/hotlinked";
+            LINK_PREVIEWS.lock().unwrap().insert(
+                url.to_string(),
+                LinkPreview {
+                    url: url.to_string(),
+                    title: "Hotlinked".to_string(),
+                    image: Some("https://cdn.example/card.png".to_string()),
+                    ..Default::default()
+                },
+            );
+
+            let html = render_link_preview(url);
+            assert!(html.contains("Hotlinked"));
+            assert!(!html.contains("<img"));
         }
-        .btn-secondary {
-            background: transparent;
-            color: #666;
-            border: 1px solid #333;
+    }
+
+    mod stages {
+        use super::*;
+
+        // Render with only the given stages, as for_post would after `disable_stages`
+        fn render_with(stages: RenderPipeline, markdown: &str) -> RenderedDocument {
+            stages.render(markdown, &test_ctx(&[], &[]))
         }
-        .btn-secondary:hover {
-            color: #fff;
-            border-color: #555;
+
+        #[test]
+        fn sanitize_and_feed_cannot_be_disabled() {
+            let disabled = vec!["sanitize".to_string(), "feed".to_string(), "callouts".to_string()];
+            let ctx = RenderContext { profile: RenderProfile::Feed, ..test_ctx(&[], &disabled) };
+            let document = render_markdown("<script>alert(1)</script>\n\n[up](/blog)\n\n> [!NOTE]\n> hi\n", &ctx);
+
+            assert!(!document.html.contains("<script"));
+            assert!(document.html.contains(r#"href="https://example.com/blog""#));
+            assert!(!document.html.contains("callout"));
+            assert_eq!(
+                warning_lines(&document),
+                vec![
+                    (1, "`disable_stages` can't turn off `sanitize`; it always runs".to_string()),
+                    (1, "`disable_stages` can't turn off `feed`; it always runs".to_string()),
+                ]
+            );
         }
-        .container {
-            max-width: 800px;
-            margin: 0 auto;
-            padding: 32px 24px;
+
+        #[test]
+        fn lint_only_reports() {
+            let document = render_with(RenderPipeline::new().register(LintStage), "![](a.png)\n\n#### Deep\n");
+            assert_eq!(
+                warning_lines(&document),
+                vec![(1, "Image has no alt text".to_string()), (3, "Heading skips from h1 to h4".to_string())]
+            );
+            assert!(document.html.contains(r#"<img src="a.png" alt="" />"#));
         }
-        .form-group {
-            margin-bottom: 24px;
+
+        #[test]
+        fn lint_pipeline_reports_what_the_full_one_does() {
+            let markdown = "![](a.png)\n\n{{< nope >}}\n\n```nosuchlang\nx\n```\n\n[](/blog)\n";
+            let ctx = test_ctx(&[], &[]);
+            let lint = RenderPipeline::for_lint().render(markdown, &ctx);
+            assert_eq!(warning_lines(&lint).len(), 4);
+            assert_eq!(warning_lines(&lint), warning_lines(&render_markdown(markdown, &ctx)));
         }
-        label {
-            display: block;
-            font-size: 11px;
-            font-weight: 500;
-            text-transform: uppercase;
-            letter-spacing: 0.05em;
-            color: #666;
-            margin-bottom: 8px;
+
+        #[test]
+        fn heading_ids_come_from_text_or_attributes() {
+            let document = render_with(RenderPipeline::new().register(HeadingIdStage), "## Hello, World\n\n### Custom {#mine}\n");
+            assert!(document.html.contains(r#"<h2 id="hello-world">"#));
+            assert!(document.html.contains(r#"<h3 id="mine">"#));
+            let toc: Vec<(u32, &str)> = document.toc.iter().map(|t| (t.level, t.id.as_str())).collect();
+            assert_eq!(toc, vec![(2, "hello-world"), (3, "mine")]);
         }
-        input, textarea {
-            width: 100%;
-            background: #0a0a0a;
-            border: 1px solid #222;
-            color: #fff;
-            padding: 12px;
-            font-size: 14px;
-            border-radius: 4px;
-            outline: none;
-            font-family: inherit;
+
+        #[test]
+        fn callouts_replace_alert_blockquotes() {
+            let markdown = "> [!WARNING]\n> Careful\n";
+            let document = render_with(RenderPipeline::new().register(CalloutStage), markdown);
+            assert!(document.html.contains(r#"<div class="callout callout-warning" role="note">"#));
+            assert!(!document.html.contains("<blockquote"));
+            assert!(render_with(RenderPipeline::new(), markdown).html.contains("<blockquote"));
         }
-        input:focus, textarea:focus {
-            border-color: #444;
+
+        #[test]
+        fn markup_adds_site_classes() {
+            let document = render_with(RenderPipeline::new().register(MarkupStage), "| a |\n|---|\n| b |\n\n~~old~~\n");
+            assert!(document.html.contains(r#"<div class="table-container"><table><thead><tr><th>a</th></tr></thead><tbody><tr><td>b</td></tr></tbody></table></div>"#));
+            assert!(document.html.contains(r#"<del class="line-through text-gray-500">old</del>"#));
         }
-        textarea {
-            min-height: 400px;
-            resize: vertical;
-            font-family: 'SF Mono', 'Fira Code', monospace;
-            font-size: 13px;
-            line-height: 1.6;
+
+        #[test]
+        fn footnotes_are_numbered_by_first_reference() {
+            let markdown = "One[^b] two[^a] three[^b]\n\n[^a]: First\n[^b]: Second\n";
+            let document = render_with(RenderPipeline::new().register(FootnoteStage), markdown);
+            let html = &document.html;
+            assert!(html.contains(r##"<a href="#fn-1" id="fnref-1""##));
+            assert!(html.contains(r##"<a href="#fn-2" id="fnref-2""##));
+            assert!(html.contains(r##"<a href="#fn-1" id="fnref-1-2""##));
+            let endnotes = &html[html.find("role=\"doc-endnotes\"").unwrap()..];
+            assert!(endnotes.find("Second").unwrap() < endnotes.find("First").unwrap());
         }
-        .hint {
-            font-size: 12px;
-            color: #666;
-            margin-top: 6px;
+
+        #[test]
+        fn code_blocks_are_highlighted_for_pages_and_plain_for_feeds() {
+            let markdown = "```rust\nfn main() {}\n```\n";
+            let page = render_with(RenderPipeline::new().register(CodeBlockStage), markdown);
+            assert!(page.html.contains(r#"<span class="code-lang font-mono">rust</span>"#));
+
+            let ctx = RenderContext { profile: RenderProfile::Feed, ..test_ctx(&[], &[]) };
+            let feed = RenderPipeline::new().register(CodeBlockStage).render(markdown, &ctx);
+            assert!(feed.html.contains(r#"<pre><code class="language-rust">fn main() {}"#));
+            assert!(!feed.html.contains("copy-btn"));
         }
-        .toast {
-            position: fixed;
-            bottom: 24px;
-            right: 24px;
-            background: #1a1a1a;
-            color: #fff;
-            padding: 12px 20px;
-            border-radius: 4px;
-            font-size: 14px;
-            display: none;
+
+        #[test]
+        fn sanitize_filters_author_html_but_not_fragments() {
+            let markdown = "<img src=\"x.png\" onerror=\"alert(1)\">\n\n```sh\necho hi\n```\n";
+            let stages = RenderPipeline::new().register(CodeBlockStage).register(SanitizeStage(SanitizeProfile::Standard));
+            let document = render_with(stages, markdown);
+            assert!(!document.html.contains("onerror"));
+            // The code block's copy button is generated markup, spliced in after sanitizing
+            assert!(document.html.contains(r#"onclick="copyCode(this)""#));
         }
-        .toast.show {
-            display: block;
+    }
+
+    mod feeds {
+        use super::*;
+
+        // An element with its attributes and text (entities decoded, CDATA included)
+        #[derive(Debug)]
+        struct Element {
+            name: String,
+            attrs: HashMap<String, String>,
+            text: String,
         }
-    </style>
-</head>
-<body>
-    <div class="header">
-        <h1>{{mode}} Post</h1>
-        <div class="header-actions">
-            <a href="/admin/dashboard" class="btn btn-secondary">Cancel</a>
-            <button type="button" onclick="savePost()" class="btn">Save Post</button>
-        </div>
-    </div>
-    <div class="container">
-        <form id="postForm">
-            <input type="hidden" name="slug" value="{{slug}}">
-            
-            <div class="form-group">
-                <label>Title</label>
-                <input type="text" name="title" value="{{title}}" required placeholder="Enter post title">
-            </div>
-            
-            <div class="form-group">
-                <label>Summary</label>
-                <input type="text" name="summary" value="{{summary}}" placeholder="Brief description for SEO">
-            </div>
-            
-            <div class="form-group">
-                <label>Tags</label>
-                <input type="text" name="tags" value="{{tags}}" placeholder="tag1, tag2, tag3">
-                <div class="hint">Comma-separated list of tags</div>
-            </div>
-            
-            <div class="form-group">
-                <label>Content (Markdown)</label>
-                <textarea name="content" placeholder="Write your post in Markdown...">{{content}}</textarea>
-            </div>
-        </form>
-    </div>
-    
-    <div class="toast" id="toast"></div>
-    
-    <script>
-        async function savePost() {
-            const form = document.getElementById('postForm');
-            const formData = new FormData(form);
-            
-            const res = await fetch('/admin/save', {
-                method: 'POST',
-                body: new URLSearchParams(formData)
-            });
-            
-            const data = await res.json();
-            
-            if (data.success) {
-                showToast('Post saved successfully!');
-                setTimeout(() => {
-                    window.location.href = '/admin/dashboard';
-                }, 1000);
-            } else {
-                showToast('Error: ' + data.error);
+
+        // Every element of a document in order; panics on anything a strict XML parser rejects
+        fn parse_xml(xml: &str) -> Vec<Element> {
+            let mut reader = quick_xml::Reader::from_str(xml);
+            let mut elements: Vec<Element> = Vec::new();
+            let mut open: Vec<usize> = Vec::new();
+            loop {
+                let event = reader.read_event().unwrap_or_else(|e| panic!("malformed XML: {}\n{}", e, xml));
+                let text = match event {
+                    XmlEvent::Start(ref tag) | XmlEvent::Empty(ref tag) => {
+                        let attrs = tag
+                            .attributes()
+                            .map(|attr| {
+                                let attr = attr.expect("well-formed attribute");
+                                let value = attr.normalized_value(quick_xml::XmlVersion::Implicit1_0).expect("valid attribute value");
+                                (attr.key.as_ref().to_string(), value.into_owned())
+                            })
+                            .collect();
+                        let name = tag.name().as_ref().to_string();
+                        if matches!(event, XmlEvent::Start(_)) {
+                            open.push(elements.len());
+                        }
+                        elements.push(Element { name, attrs, text: String::new() });
+                        continue;
+                    }
+                    XmlEvent::End(_) => {
+                        open.pop();
+                        continue;
+                    }
+                    XmlEvent::Text(text) => text.xml10_content().into_owned(),
+                    XmlEvent::CData(data) => data.into_inner().into_owned(),
+                    XmlEvent::GeneralRef(entity) => match entity.resolve_char_ref().expect("valid character reference") {
+                        Some(c) => c.to_string(),
+                        None => quick_xml::escape::resolve_xml_entity(&entity.xml10_content()).expect("predefined entity").to_string(),
+                    },
+                    XmlEvent::Eof => break,
+                    _ => continue,
+                };
+                if let Some(&current) = open.last() {
+                    elements[current].text.push_str(&text);
+                }
             }
+            assert!(open.is_empty(), "unclosed elements in\n{}", xml);
+            elements
         }
-        
-        function showToast(msg) {
-            const toast = document.getElementById('toast');
-            toast.textContent = msg;
-            toast.classList.add('show');
-            setTimeout(() => toast.classList.remove('show'), 3000);
+
+        fn texts<'a>(elements: &'a [Element], name: &str) -> Vec<&'a str> {
+            elements.iter().filter(|e| e.name == name).map(|e| e.text.as_str()).collect()
         }
-        
-        // Keyboard shortcut: Cmd/Ctrl + S to save
-        document.addEventListener('keydown', (e) => {
-            if ((e.metaKey || e.ctrlKey) && e.key === 's') {
-                e.preventDefault();
-                savePost();
-            }
-        });
-    </script>
-</body>
-</html>"#;
 
-const ADMIN_GITHUB_HTML: &str = r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Import from GitHub</title>
-    <style>
-        * { margin: 0; padding: 0; box-sizing: border-box; }
-        body {
-            background: #000;
-            color: #fff;
-            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
-            min-height: 100vh;
+        fn attrs<'a>(elements: &'a [Element], name: &str, attr: &str) -> Vec<&'a str> {
+            elements.iter().filter(|e| e.name == name).filter_map(|e| e.attrs.get(attr).map(String::as_str)).collect()
         }
-        .header {
-            border-bottom: 1px solid #1a1a1a;
-            padding: 16px 24px;
-            display: flex;
-            justify-content: space-between;
-            align-items: center;
+
+        const TITLE: &str = "Tom & Jerry's <script>alert(\"hi\")</script> ]]>";
+
+        // A post whose every field tries to break out of its element
+        fn hostile_post() -> Post {
+            let mut post = test_post("hostile");
+            post.title = format!("{}\u{1}", TITLE);
+            post.summary = "a < b && c > d".to_string();
+            post.content = "<p>CDATA ends here: ]]> and <b>continues</b></p>".to_string();
+            post.tags = vec!["c++ & <rust>".to_string(), "\"quoted\"".to_string()];
+            post.author = "A & B <a@b>".to_string();
+            post
         }
-        .header h1 {
-            font-size: 16px;
-            font-weight: 600;
+
+        fn full_site_scope(site_config: &SiteConfig) -> FeedScope {
+            FeedScope { content: FeedContent::Full, ..FeedScope::site(site_config) }
         }
-        .btn {
-            background: #fff;
-            color: #000;
-            padding: 8px 16px;
-            font-size: 13px;
-            font-weight: 500;
-            border-radius: 4px;
-            text-decoration: none;
-            border: none;
-            cursor: pointer;
+
+        async fn body(response: Response) -> String {
+            String::from_utf8(axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
         }
-        .btn:hover {
-            background: #e0e0e0;
+
+        #[test]
+        fn rss_has_required_elements_and_escapes_fields() {
+            let site_config = SiteConfig::default();
+            let xml = build_rss(&[hostile_post(), test_post("plain")], &site_config, &full_site_scope(&site_config));
+            let elements = parse_xml(&xml);
+
+            assert_eq!(attrs(&elements, "rss", "version"), vec!["2.0"]);
+            for name in ["title", "link", "description"] {
+                assert!(elements.iter().any(|e| e.name == name), "channel has no <{}>", name);
+            }
+            assert_eq!(texts(&elements, "item").len(), 2);
+            assert_eq!(texts(&elements, "guid").len(), 2);
+            assert_eq!(texts(&elements, "pubDate").len(), 2);
+            assert!(texts(&elements, "title").contains(&TITLE));
+            assert!(texts(&elements, "description").contains(&"a < b && c > d"));
+            assert_eq!(texts(&elements, "category"), vec!["c++ & <rust>", "\"quoted\""]);
+            assert!(texts(&elements, "dc:creator").contains(&"A & B <a@b>"));
+            assert_eq!(
+                texts(&elements, "content:encoded")[0],
+                "<p>CDATA ends here: ]]> and <b>continues</b></p>"
+            );
         }
-        .btn-secondary {
-            background: transparent;
-            color: #666;
-            border: 1px solid #333;
+
+        #[test]
+        fn atom_has_required_elements_and_escapes_fields() {
+            let site_config = SiteConfig::default();
+            let xml = build_atom(&[hostile_post()], &site_config, &full_site_scope(&site_config));
+            let elements = parse_xml(&xml);
+
+            assert_eq!(attrs(&elements, "feed", "xmlns"), vec!["http://www.w3.org/2005/Atom"]);
+            assert_eq!(texts(&elements, "entry").len(), 1);
+            // Feed and entry each have an id, title and updated
+            assert_eq!(texts(&elements, "id"), vec![format!("{}/", site_config.url), format!("{}/blog/hostile", site_config.url)]);
+            assert_eq!(texts(&elements, "updated").len(), 2);
+            assert_eq!(texts(&elements, "title")[1], TITLE);
+            assert!(attrs(&elements, "link", "rel").contains(&"self"));
+            assert!(texts(&elements, "name").contains(&"A & B <a@b>"));
+            assert_eq!(attrs(&elements, "category", "term"), vec!["c++ & <rust>", "\"quoted\""]);
+            assert_eq!(texts(&elements, "summary"), vec!["a < b && c > d"]);
+            assert_eq!(texts(&elements, "content"), vec!["<p>CDATA ends here: ]]> and <b>continues</b></p>"]);
         }
-        .btn-secondary:hover {
-            color: #fff;
-            border-color: #555;
+
+        #[test]
+        fn json_feed_has_required_fields_and_keeps_fields_verbatim() {
+            let site_config = SiteConfig::default();
+            let json = build_json_feed(&[hostile_post()], &site_config, &full_site_scope(&site_config));
+            let feed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+            assert!(feed["title"].is_string());
+            let item = &feed["items"][0];
+            assert_eq!(item["id"], format!("{}/blog/hostile", site_config.url));
+            assert_eq!(item["url"], item["id"]);
+            assert_eq!(item["title"], format!("{}\u{1}", TITLE));
+            assert_eq!(item["tags"], serde_json::json!(["c++ & <rust>", "\"quoted\""]));
+            assert_eq!(item["content_html"], "<p>CDATA ends here: ]]> and <b>continues</b></p>");
+            assert_eq!(item["authors"][0]["name"], "A & B <a@b>");
         }
-        .btn-small {
-            padding: 6px 12px;
-            font-size: 12px;
+
+        #[tokio::test]
+        async fn sitemaps_have_required_elements_and_escape_urls() {
+            let site_config = SiteConfig::default();
+            let mut post = hostile_post();
+            post.image = "https://cdn.example/a.png?w=1&h=2".to_string();
+            let posts = [post];
+
+            let elements = parse_xml(&body(posts_sitemap(&posts, &site_config, &HashMap::new())).await);
+            assert_eq!(attrs(&elements, "urlset", "xmlns"), vec!["http://www.sitemaps.org/schemas/sitemap/0.9"]);
+            assert_eq!(texts(&elements, "loc")[0], format!("{}/blog/hostile", site_config.url));
+            assert!(texts(&elements, "image:loc").contains(&"https://cdn.example/a.png?w=1&h=2"));
+            assert!(texts(&elements, "image:title").contains(&TITLE));
+            let lastmod = texts(&elements, "lastmod")[0];
+            assert!(DateTime::parse_from_rfc3339(lastmod).is_ok(), "lastmod {} isn't W3C datetime", lastmod);
+
+            let elements = parse_xml(&body(tags_sitemap(&posts, &site_config, &HashMap::new())).await);
+            assert_eq!(
+                texts(&elements, "loc"),
+                vec![
+                    format!("{}/tags/%22quoted%22", site_config.url),
+                    format!("{}/tags/c%2B%2B%20%26%20%3Crust%3E", site_config.url),
+                ]
+            );
         }
-        .container {
-            max-width: 800px;
-            margin: 0 auto;
-            padding: 32px 24px;
+
+        #[tokio::test]
+        async fn sitemap_lastmod_is_the_recorded_change_not_the_feed_update() {
+            let site_config = SiteConfig::default();
+            let (mut edited, mut untracked) = (test_post("edited"), test_post("untracked"));
+            edited.updated = Local.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap();
+            untracked.updated = Local.with_ymd_and_hms(2026, 2, 3, 0, 0, 0).unwrap();
+            let changed = Local.with_ymd_and_hms(2026, 3, 4, 5, 6, 7).unwrap();
+            let modified = HashMap::from([("edited".to_string(), changed)]);
+            let posts = [edited, untracked];
+
+            let elements = parse_xml(&body(posts_sitemap(&posts, &site_config, &modified)).await);
+            let lastmods = texts(&elements, "lastmod");
+            let w3c = |time: DateTime<Local>| time.to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+            assert_eq!(lastmods[0], w3c(changed));
+            assert_eq!(lastmods[1], w3c(posts[1].updated));
+            assert_eq!(newest_modified(&posts, &modified), Some(changed));
+            assert_eq!(newest_update(&posts), Some(posts[1].updated));
         }
-        .info {
-            background: #0a0a0a;
-            border: 1px solid #1a1a1a;
-            border-radius: 4px;
-            padding: 16px;
-            margin-bottom: 24px;
-            font-size: 13px;
-            color: #999;
+
+        #[tokio::test]
+        async fn tag_feeds_list_only_that_tags_posts() {
+            let site_config = SiteConfig::default();
+            let mut posts = vec![test_post("a"), test_post("b"), test_post("c")];
+            posts[0].tags = vec!["Rust".to_string(), "web".to_string()];
+            posts[1].tags = vec!["c++".to_string()];
+            posts[2].tags = vec!["rust".to_string()];
+            let links = |topic: &str| {
+                let (xml, _) = render_feed(&format!("{}{}", site_config.url, topic), &posts, &site_config).unwrap();
+                texts(&parse_xml(&xml), "link").iter().filter(|l| l.contains("/blog/")).map(|l| l.to_string()).collect::<Vec<_>>()
+            };
+
+            let blog = |slug: &str| format!("{}/blog/{}", site_config.url, slug);
+            assert_eq!(links("/tags/rust/rss.xml"), vec![blog("a"), blog("c")]);
+            assert_eq!(links("/tags/c%2B%2B/rss.xml"), vec![blog("b")]);
+            assert!(render_feed(&format!("{}/tags/go/rss.xml", site_config.url), &posts, &site_config).is_none());
+
+            let query = Query(FeedQuery { content: None });
+            let response = tag_feed(Path(("no-such-tag-anywhere".to_string(), "rss.xml".to_string())), query).await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
         }
-        .repos-list {
-            display: flex;
-            flex-direction: column;
-            gap: 8px;
+
+        #[test]
+        fn feeds_opml_lists_every_tag_and_author_feed() {
+            let site_config = SiteConfig::default();
+            let mut posts = vec![test_post("a"), test_post("b"), test_post("c")];
+            posts[0].tags = vec!["Rust".to_string(), "c++".to_string()];
+            posts[0].author = "Ann Lee".to_string();
+            posts[1].tags = vec!["rust".to_string(), "go".to_string()];
+            posts[1].author = "Bob".to_string();
+            posts[2].author = "Ann Lee".to_string();
+
+            let elements = parse_xml(&build_feeds_opml(&posts, &site_config).to_xml());
+            assert_eq!(attrs(&elements, "opml", "version"), vec!["2.0"]);
+            let url = |path: &str| format!("{}{}/rss.xml", site_config.url, path);
+            assert_eq!(
+                attrs(&elements, "outline", "xmlUrl"),
+                vec![url(""), url("/tags/c%2B%2B"), url("/tags/go"), url("/tags/Rust"), url("/authors/ann-lee"), url("/authors/bob")]
+            );
+            let folders: Vec<&str> =
+                elements.iter().filter(|e| e.name == "outline" && !e.attrs.contains_key("xmlUrl")).map(|e| e.attrs["text"].as_str()).collect();
+            assert_eq!(folders, vec!["Tags", "Authors"]);
         }
-        .repo {
-            background: #0a0a0a;
-            border: 1px solid #1a1a1a;
-            border-radius: 4px;
-            padding: 16px;
-            display: flex;
-            justify-content: space-between;
-            align-items: center;
+
+        #[test]
+        fn news_language_keeps_the_chinese_variants() {
+            assert_eq!(news_language("en-US"), "en");
+            assert_eq!(news_language("pt_BR"), "pt");
+            assert_eq!(news_language("zh-TW"), "zh-tw");
+            assert_eq!(news_language("zh_CN"), "zh-cn");
+            assert_eq!(news_language("zh-Hant-HK"), "zh-tw");
+            assert_eq!(news_language("zh"), "zh");
         }
-        .repo-info h3 {
-            font-size: 14px;
-            font-weight: 500;
-            margin-bottom: 4px;
+    }
+
+    mod conditional_get {
+        use super::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[tokio::test]
+        async fn matching_content_etag_skips_the_handler() {
+            let renders = Arc::new(AtomicUsize::new(0));
+            let counter = renders.clone();
+            let app = Router::new()
+                .route(
+                    "/page",
+                    get(move || {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        async { Html("rendered") }
+                    }),
+                )
+                .route_layer(axum::middleware::from_fn_with_state(Some("public, max-age=3600"), content_validators));
+            let base = serve(app).await;
+            let client = reqwest::Client::new();
+
+            let first = client.get(format!("{}/page", base)).send().await.unwrap();
+            let etag = first.headers()[header::ETAG].to_str().unwrap().to_string();
+            assert_eq!(etag, content_etag("/page"));
+
+            let second = client.get(format!("{}/page", base)).header(header::IF_NONE_MATCH, &etag).send().await.unwrap();
+            assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(second.headers()[header::CACHE_CONTROL], "public, max-age=3600");
+            assert_eq!(renders.load(Ordering::SeqCst), 1);
+
+            // A different URL is a different representation
+            let other = client.get(format!("{}/page?q=1", base)).header(header::IF_NONE_MATCH, &etag).send().await.unwrap();
+            assert_eq!(other.status(), StatusCode::OK);
         }
-        .repo-info p {
-            font-size: 12px;
-            color: #666;
-            margin-bottom: 4px;
+
+        #[tokio::test]
+        async fn og_images_are_validated_before_drawing() {
+            let etag = etag_for(&[OG_IMAGE_VERSION, &content_etag("/blog/no-such-post/og.png")]);
+            let mut headers = HeaderMap::new();
+            headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(&etag).unwrap());
+
+            let response = og_image(headers, Path("no-such-post".to_string())).await.into_response();
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        }
+
+        #[test]
+        fn only_small_text_bodies_are_hashed() {
+            let response = |mime: &str, body: Vec<u8>| {
+                Response::builder().header(header::CONTENT_TYPE, mime).body(axum::body::Body::from(body)).unwrap()
+            };
+            assert!(is_hashable(&response("text/plain; charset=utf-8", b"hi".to_vec())));
+            assert!(is_hashable(&response("application/manifest+json", b"{}".to_vec())));
+            assert!(!is_hashable(&response("image/webp", b"RIFF".to_vec())));
+            assert!(!is_hashable(&response("text/plain", vec![b'a'; MAX_HASHED_BODY as usize + 1])));
+        }
+    }
+
+    mod websub {
+        use super::*;
+
+        type Received = Arc<Mutex<Vec<(String, Option<String>, String)>>>;
+
+        // A subscriber that echoes challenges at /echo, ignores them at /ignore, records
+        // deliveries to /inbox (link header, signature, body) and answers 410 at /gone
+        async fn subscriber() -> (String, Received) {
+            let received: Received = Arc::default();
+            let inbox = received.clone();
+            let app = Router::new()
+                .route(
+                    "/echo",
+                    get(|Query(query): Query<HashMap<String, String>>| async move {
+                        assert_eq!(query.get("hub.topic").map(String::as_str), Some("https://example.com/rss"));
+                        query.get("hub.challenge").cloned().unwrap_or_default()
+                    }),
+                )
+                .route("/ignore", get(|| async { "not the challenge" }))
+                .route(
+                    "/inbox",
+                    post(move |headers: HeaderMap, body: String| async move {
+                        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+                        inbox.lock().unwrap().push((header("link").unwrap_or_default(), header("x-hub-signature"), body));
+                        StatusCode::NO_CONTENT
+                    }),
+                )
+                .route("/gone", post(|| async { StatusCode::GONE }));
+            (serve(app).await, received)
         }
-        .repo-meta {
-            font-size: 11px;
-            color: #444;
-            display: flex;
-            gap: 12px;
+
+        // A subscriber answering each delivery with the next of `statuses`, then 204; counts deliveries
+        async fn flaky_subscriber(statuses: &[u16]) -> (String, Arc<Mutex<Vec<u16>>>) {
+            let statuses = Arc::new(Mutex::new(statuses.to_vec()));
+            let answered: Arc<Mutex<Vec<u16>>> = Arc::default();
+            let log = answered.clone();
+            let app = Router::new().route(
+                "/inbox",
+                post(move || async move {
+                    let mut statuses = statuses.lock().unwrap();
+                    let status = if statuses.is_empty() { 204 } else { statuses.remove(0) };
+                    log.lock().unwrap().push(status);
+                    StatusCode::from_u16(status).unwrap()
+                }),
+            );
+            (format!("{}/inbox", serve(app).await), answered)
         }
-        .repo-actions {
-            display: flex;
-            gap: 8px;
-            align-items: center;
+
+        async fn deliver_to(callback: String) -> Vec<String> {
+            let subscribers = [subscription(callback, None)];
+            let client = websub_client().unwrap();
+            let (hub, topic) = ("https://example.com/websub", "https://example.com/rss");
+            websub_deliver(&client, hub, topic, "<rss/>", "application/rss+xml", &subscribers, Duration::from_millis(1)).await
         }
-        .checkbox-label {
-            display: flex;
-            align-items: center;
-            gap: 6px;
-            font-size: 12px;
-            color: #666;
+
+        #[tokio::test]
+        async fn failed_deliveries_are_retried() {
+            let (callback, answered) = flaky_subscriber(&[503, 429]).await;
+            assert!(deliver_to(callback).await.is_empty());
+            assert_eq!(*answered.lock().unwrap(), vec![503, 429, 204]);
+
+            // Gives up after the last attempt
+            let (callback, answered) = flaky_subscriber(&[500; 10]).await;
+            deliver_to(callback).await;
+            assert_eq!(answered.lock().unwrap().len(), WEBSUB_DELIVERY_ATTEMPTS as usize);
+
+            // Other refusals are final
+            let (callback, answered) = flaky_subscriber(&[400]).await;
+            deliver_to(callback).await;
+            assert_eq!(*answered.lock().unwrap(), vec![400]);
         }
-        .checkbox-label input {
-            width: 14px;
-            height: 14px;
+
+        fn subscription(callback: String, secret: Option<&str>) -> WebSubSubscription {
+            WebSubSubscription {
+                topic: "https://example.com/rss".to_string(),
+                callback,
+                secret: secret.map(str::to_string),
+                expires: Local::now().timestamp() + 3600,
+            }
         }
-        .loading {
-            text-align: center;
-            padding: 40px;
-            color: #666;
+
+        #[test]
+        fn hmac_matches_rfc_4231() {
+            assert_eq!(
+                hmac_sha256(&[0x0b; 20], b"Hi There"),
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+            );
+            // Keys longer than a block are hashed first
+            assert_eq!(
+                hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First"),
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+            );
         }
-        .linked {
-            background: #0d1a0d;
-            border-color: #1a2e1a;
+
+        #[tokio::test]
+        async fn verification_needs_the_challenge_echoed() {
+            let (base, _) = subscriber().await;
+            let client = websub_client().unwrap();
+
+            let echoed = subscription(format!("{}/echo", base), None);
+            assert_eq!(websub_confirm(&client, "subscribe", &echoed, 3600).await, Ok(()));
+            let ignored = subscription(format!("{}/ignore", base), None);
+            assert!(websub_confirm(&client, "subscribe", &ignored, 3600).await.is_err());
+            let missing = subscription(format!("{}/nowhere", base), None);
+            assert!(websub_confirm(&client, "unsubscribe", &missing, 3600).await.is_err());
         }
-        .linked-badge {
-            background: #1a2e1a;
-            color: #4a8;
-            padding: 2px 6px;
-            border-radius: 2px;
-            font-size: 10px;
-            margin-left: 8px;
+
+        #[tokio::test]
+        async fn delivery_is_signed_and_reports_gone_subscribers() {
+            let (base, received) = subscriber().await;
+            let client = websub_client().unwrap();
+            let subscribers = [
+                subscription(format!("{}/inbox", base), Some("s3cret")),
+                subscription(format!("{}/inbox", base), None),
+                subscription(format!("{}/gone", base), None),
+            ];
+
+            let gone = websub_deliver(
+                &client,
+                "https://example.com/websub",
+                "https://example.com/rss",
+                "<rss/>",
+                "application/rss+xml",
+                &subscribers,
+                Duration::from_millis(1),
+            )
+            .await;
+            assert_eq!(gone, vec![format!("{}/gone", base)]);
+
+            let received = received.lock().unwrap();
+            assert_eq!(received.len(), 2);
+            for (link, _, body) in received.iter() {
+                assert_eq!(link, "<https://example.com/websub>; rel=\"hub\", <https://example.com/rss>; rel=\"self\"");
+                assert_eq!(body, "<rss/>");
+            }
+            let signature = format!("sha256={}", hmac_sha256(b"s3cret", b"<rss/>"));
+            assert_eq!(received[0].1.as_deref(), Some(signature.as_str()));
+            assert_eq!(received[1].1, None);
         }
-        .toast {
-            position: fixed;
-            bottom: 24px;
-            right: 24px;
-            background: #1a1a1a;
-            color: #fff;
-            padding: 12px 20px;
-            border-radius: 4px;
-            font-size: 14px;
-            display: none;
+
+        #[test]
+        fn callbacks_must_be_public() {
+            for callback in [
+                "https://example.com/cb",
+                "http://93.184.216.34/cb",
+                "http://[2606:2800:220:1::]/cb",
+            ] {
+                assert!(websub_callback(callback).is_ok(), "{}", callback);
+            }
+            for callback in [
+                "ftp://example.com/cb",
+                "not a url",
+                "http://localhost:8080/cb",
+                "http://api.localhost/cb",
+                "http://127.0.0.1/cb",
+                "http://10.0.0.5/cb",
+                "http://192.168.1.1/cb",
+                "http://169.254.169.254/latest/meta-data",
+                "http://100.64.0.1/cb",
+                "http://0.0.0.0/cb",
+                "http://[::1]/cb",
+                "http://[fd00::1]/cb",
+                "http://[fe80::1]/cb",
+                "http://[::ffff:127.0.0.1]/cb",
+            ] {
+                assert!(websub_callback(callback).is_err(), "{}", callback);
+            }
         }
-        .toast.show {
-            display: block;
+
+        #[tokio::test]
+        async fn callback_client_refuses_names_that_resolve_privately() {
+            let (base, received) = subscriber().await;
+            let port = base.rsplit(':').next().unwrap();
+            let client = websub_callback_client().unwrap();
+
+            let result = client.post(format!("http://localhost:{}/inbox", port)).send().await;
+            assert!(result.is_err());
+            assert!(received.lock().unwrap().is_empty());
         }
-    </style>
-</head>
-<body>
-    <div class="header">
-        <h1>Import from GitHub</h1>
-        <a href="/admin/dashboard" class="btn btn-secondary">Back to Dashboard</a>
-    </div>
-    <div class="container">
-        <div class="info">
-            <strong>Auto-sync:</strong> Enable auto-sync to automatically update blog posts when you push changes to your GitHub repository. 
-            Set up a webhook in your GitHub repo settings pointing to <code>/api/webhook/github</code> to enable this feature.
-        </div>
-        
-        <div id="repos" class="repos-list">
-            <div class="loading">Loading repositories...</div>
-        </div>
-    </div>
-    
-    <div class="toast" id="toast"></div>
-    
-    <script>
-        async function loadRepos() {
-            const res = await fetch('/admin/api/repos');
-            const data = await res.json();
-            
-            if (!data.success) {
-                document.getElementById('repos').innerHTML = '<div class="loading">Error: ' + data.error + '</div>';
-                return;
+    }
+
+    mod ranges {
+        use super::*;
+
+        async fn fetch(range: Option<&str>, if_range: Option<&str>) -> (StatusCode, HeaderMap, Vec<u8>) {
+            let file = temp_path("media.bin");
+            fs::write(&file, b"0123456789").unwrap();
+            let mut headers = HeaderMap::new();
+            if let Some(range) = range {
+                headers.insert(header::RANGE, HeaderValue::from_str(range).unwrap());
             }
-            
-            const html = data.repos.map(repo => `
-                <div class="repo ${repo.is_linked ? 'linked' : ''}">
-                    <div class="repo-info">
-                        <h3>${repo.name}${repo.is_linked ? '<span class="linked-badge">Linked</span>' : ''}</h3>
-                        <p>${repo.description || 'No description'}</p>
-                        <div class="repo-meta">
-                            <span>${repo.language || 'Unknown'}</span>
-                            <span>${repo.stars} stars</span>
-                        </div>
-                    </div>
-                    <div class="repo-actions">
-                        ${repo.is_linked ? `
-                            <button class="btn btn-small btn-secondary" onclick="syncRepo('${repo.name}')">Sync Now</button>
-                        ` : `
-                            <label class="checkbox-label">
-                                <input type="checkbox" id="auto-${repo.name}">
-                                Auto-sync
-                            </label>
-                            <button class="btn btn-small" onclick="importRepo('${repo.name}')">Import</button>
-                        `}
-                    </div>
-                </div>
-            `).join('');
-            
-            document.getElementById('repos').innerHTML = html;
+            if let Some(if_range) = if_range {
+                headers.insert(header::IF_RANGE, HeaderValue::from_str(if_range).unwrap());
+            }
+            let response = Response::builder()
+                .header(header::CONTENT_TYPE, "application/octet-stream")
+                .header(header::CONTENT_SECURITY_POLICY, "sandbox");
+            let response = file_response(&headers, &file, "\"tag\"", response).await.unwrap();
+            let _ = fs::remove_file(file);
+            let (parts, body) = response.into_parts();
+            let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+            (parts.status, parts.headers, body.to_vec())
         }
-        
-        async function importRepo(name) {
-            const autoSync = document.getElementById('auto-' + name)?.checked ? 'on' : '';
-            
-            const res = await fetch('/admin/github/import', {
-                method: 'POST',
-                headers: { 'Content-Type': 'application/x-www-form-urlencoded' },
-                body: 'repo_name=' + encodeURIComponent(name) + '&auto_sync=' + autoSync
-            });
-            
-            const data = await res.json();
-            
-            if (data.success) {
-                showToast('Imported successfully!');
-                loadRepos();
-            } else {
-                showToast('Error: ' + data.error);
+
+        #[tokio::test]
+        async fn partial_responses_keep_the_full_headers() {
+            let (status, headers, body) = fetch(Some("bytes=2-4"), None).await;
+            assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+            assert_eq!(body, b"234");
+            assert_eq!(headers[header::CONTENT_RANGE], "bytes 2-4/10");
+            assert_eq!(headers[header::CONTENT_LENGTH], "3");
+            assert_eq!(headers[header::ETAG], "\"tag\"");
+            assert_eq!(headers[header::CONTENT_SECURITY_POLICY], "sandbox");
+            assert_eq!(headers[header::ACCEPT_RANGES], "bytes");
+
+            let (status, _, body) = fetch(Some("bytes=-3"), Some("\"tag\"")).await;
+            assert_eq!((status, body.as_slice()), (StatusCode::PARTIAL_CONTENT, b"789".as_slice()));
+            let (status, _, body) = fetch(Some("bytes=8-100"), None).await;
+            assert_eq!((status, body.as_slice()), (StatusCode::PARTIAL_CONTENT, b"89".as_slice()));
+        }
+
+        #[tokio::test]
+        async fn other_requests_get_the_whole_file_or_416() {
+            for (range, if_range) in [(None, None), (Some("bytes=0-1,4-5"), None), (Some("bytes=2-4"), Some("\"old\""))] {
+                let (status, headers, body) = fetch(range, if_range).await;
+                assert_eq!((status, body.as_slice()), (StatusCode::OK, b"0123456789".as_slice()), "{:?}", range);
+                assert_eq!(headers[header::CONTENT_SECURITY_POLICY], "sandbox");
             }
+            let (status, headers, _) = fetch(Some("bytes=10-"), None).await;
+            assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+            assert_eq!(headers[header::CONTENT_RANGE], "bytes */10");
         }
-        
-        async function syncRepo(name) {
-            const res = await fetch('/admin/sync/github-' + name.toLowerCase(), { method: 'POST' });
-            const data = await res.json();
-            
-            if (data.success) {
-                showToast('Synced successfully!');
-            } else {
-                showToast('Error: ' + data.error);
+    }
+
+    mod indexnow {
+        use super::*;
+        use std::collections::VecDeque;
+
+        type Requests = Arc<Mutex<Vec<serde_json::Value>>>;
+
+        // An IndexNow endpoint answering with `statuses` in turn, then 200, recording each request body
+        async fn endpoint(statuses: &[u16]) -> (IndexNowOptions, Requests) {
+            let statuses = Arc::new(Mutex::new(statuses.iter().copied().collect::<VecDeque<u16>>()));
+            let requests: Requests = Arc::default();
+            let received = requests.clone();
+            let app = Router::new().route(
+                "/indexnow",
+                post(move |Json(body): Json<serde_json::Value>| async move {
+                    received.lock().unwrap().push(body);
+                    let status = statuses.lock().unwrap().pop_front().unwrap_or(200);
+                    StatusCode::from_u16(status).unwrap()
+                }),
+            );
+            let base = serve(app).await;
+            (options(format!("{}/indexnow", base)), requests)
+        }
+
+        fn options(endpoint: String) -> IndexNowOptions {
+            IndexNowOptions {
+                endpoint,
+                host: "example.com".to_string(),
+                key: "0123456789abcdef".to_string(),
+                key_location: "https://example.com/0123456789abcdef.txt".to_string(),
+                backoff: Duration::from_millis(1),
             }
         }
-        
-        function showToast(msg) {
-            const toast = document.getElementById('toast');
-            toast.textContent = msg;
-            toast.classList.add('show');
-            setTimeout(() => toast.classList.remove('show'), 3000);
+
+        fn urls(count: usize) -> Vec<String> {
+            (0..count).map(|i| format!("https://example.com/blog/post-{}", i)).collect()
         }
-        
-        loadRepos();
-    </script>
-</body>
-</html>"#;
 
-#[tokio::main]
-async fn main() {
-    let _ = dotenv();
+        #[tokio::test]
+        async fn retries_rate_limits_and_server_errors() {
+            let (options, requests) = endpoint(&[429, 503]).await;
+            let submission = indexnow_send(&indexnow_client().unwrap(), &options, &urls(2)).await;
+            assert_eq!((submission.attempts, submission.status, submission.error), (3, Some(200), None));
+
+            let body = &requests.lock().unwrap()[0];
+            assert_eq!(body["host"], "example.com");
+            assert_eq!(body["key"], "0123456789abcdef");
+            assert_eq!(body["keyLocation"], "https://example.com/0123456789abcdef.txt");
+            assert_eq!(body["urlList"], serde_json::json!(urls(2)));
+        }
 
-    let mut hb = Handlebars::new();
-    hb.set_strict_mode(false); // Allow missing variables
-    
-    if let Err(e) = hb.register_template_file("index.html", "templates/index.html") {
-        eprintln!("Failed to register index template: {}", e);
-        return;
-    }
-    if let Err(e) = hb.register_template_file("single.html", "templates/single.html") {
-        eprintln!("Failed to register single template: {}", e);
-        return;
+        #[tokio::test]
+        async fn gives_up_after_the_last_attempt() {
+            let (options, requests) = endpoint(&[500; 10]).await;
+            let submission = indexnow_send(&indexnow_client().unwrap(), &options, &urls(1)).await;
+            assert_eq!((submission.attempts, submission.status), (INDEXNOW_ATTEMPTS, Some(500)));
+            assert!(submission.error.is_some());
+            assert_eq!(requests.lock().unwrap().len(), INDEXNOW_ATTEMPTS as usize);
+        }
+
+        #[tokio::test]
+        async fn stops_on_client_errors() {
+            for status in [400, 403, 422] {
+                let (options, requests) = endpoint(&[status]).await;
+                let submission = indexnow_send(&indexnow_client().unwrap(), &options, &urls(1)).await;
+                assert_eq!((submission.attempts, submission.status), (1, Some(status)));
+                assert!(submission.error.unwrap().starts_with(&status.to_string()));
+                assert_eq!(requests.lock().unwrap().len(), 1);
+            }
+        }
+
+        #[tokio::test]
+        async fn sends_batches_of_at_most_the_limit() {
+            let (options, requests) = endpoint(&[]).await;
+            let submissions = indexnow_submit(&indexnow_client().unwrap(), &options, &urls(INDEXNOW_BATCH + 1)).await;
+            assert_eq!(submissions.iter().map(|s| s.urls.len()).collect::<Vec<_>>(), vec![INDEXNOW_BATCH, 1]);
+            let sizes: Vec<usize> = requests.lock().unwrap().iter().map(|r| r["urlList"].as_array().unwrap().len()).collect();
+            assert_eq!(sizes, vec![INDEXNOW_BATCH, 1]);
+        }
+
+        #[test]
+        fn submits_changed_added_and_removed_pages() {
+            let site_config = SiteConfig::default();
+            let mut kept = test_post("kept");
+            kept.tags = vec!["rust".to_string()];
+            let mut edited = test_post("edited");
+            edited.tags = vec!["web".to_string()];
+            let previous = indexnow_pages(&[kept.clone(), edited.clone(), test_post("removed")], &site_config);
+
+            // A new modification time alone is not a change
+            kept.updated_iso = "2030-01-01T00:00:00+00:00".to_string();
+            edited.content = "<p>Rewritten</p>".to_string();
+            let current = indexnow_pages(&[kept, edited, test_post("added")], &site_config);
+
+            let page = |path: &str| format!("{}{}", site_config.url, path);
+            assert_eq!(
+                indexnow_changes(&previous, &current),
+                vec![page("/"), page("/blog/added"), page("/blog/edited"), page("/blog/removed"), page("/tags/web")]
+            );
+            assert!(indexnow_changes(&current, &current).is_empty());
+        }
     }
-    let hb = Arc::new(hb);
-    
-    // Initialize admin state
-    let admin_state = AdminState::new();
 
-    let app = Router::new()
-        // Public routes
-        .route("/", get(index))
-        .route("/tags/{tag}", get(tag_page))
-        .route("/blog", get(blog_redirect))
-        .route("/blog/", get(blog_redirect))
-        .route("/blog/{post_title}", get(single_post))
-        // Static assets - favicon/logo
-        .route("/logo.webp", get(serve_logo))
-        .route("/favicon.webp", get(serve_logo))
-        .route("/favicon.png", get(serve_logo))
-        .route("/favicon.ico", get(serve_logo))
-        .route("/apple-touch-icon.webp", get(serve_logo))
-        .route("/apple-touch-icon.png", get(serve_logo))
-        // Dynamic OG Image generation
-        .route("/blog/recents.png", get(og_image_recents))
-        .route("/blog/{slug}/og.png", get(og_image))
-        .route("/og.png", get(og_image_default))
-        // SEO routes - comprehensive feed & sitemap support
-        .route("/sitemap.xml", get(sitemap))
-        .route("/sitemap-posts.xml", get(sitemap_posts))
-        .route("/robots.txt", get(robots_txt))
-        .route("/rss.xml", get(rss_feed))
-        .route("/feed.xml", get(rss_feed))
-        .route("/atom.xml", get(atom_feed))
-        .route("/feed", get(rss_feed))
-        // PWA & SEO metadata files
-        .route("/manifest.json", get(manifest_json))
-        .route("/browserconfig.xml", get(browserconfig_xml))
-        .route("/humans.txt", get(humans_txt))
-        .route("/.well-known/security.txt", get(security_txt))
-        .route("/api/search", get(search_posts))
-        // GitHub integration routes (public)
-        .route("/api/github/repos", get(list_github_repos))
-        .route("/api/github/sync", get(sync_github_repos))
-        // Admin routes
-        .route("/admin", get(admin_login_page))
-        .route("/admin/login", post(admin_login_submit))
-        .route("/admin/logout", get(admin_logout))
-        .route("/admin/dashboard", get(admin_dashboard))
-        .route("/admin/new", get(admin_new_post))
-        .route("/admin/edit/{slug}", get(admin_edit_post))
-        .route("/admin/save", post(admin_save_post))
-        .route("/admin/delete/{slug}", delete(admin_delete_post))
-        .route("/admin/github", get(admin_github_page))
-        .route("/admin/api/repos", get(admin_list_repos))
-        .route("/admin/github/import", post(admin_import_repo))
-        .route("/admin/sync/{slug}", post(admin_sync_repo))
-        // Webhook for GitHub auto-sync
-        .route("/api/webhook/github", post(github_webhook))
-        .layer(Extension(hb))
-        .with_state(admin_state);
+    mod admin {
+        use super::*;
 
-    let base_url = SiteConfig::default().url;
-    println!("Server running at {}", base_url);
-    println!("Blog index: {}/", base_url);
-    println!("Admin panel: {}/admin", base_url);
-    println!("Sitemap: {}/sitemap.xml", base_url);
-    println!("RSS Feed: {}/rss.xml", base_url);
-    
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
-    axum::serve(listener, app).await.unwrap();
-}
\ No newline at end of file
+        fn form(title: &str, content: &str) -> PostForm {
+            PostForm {
+                title: title.to_string(),
+                content: content.to_string(),
+                tags: "rust, audio".to_string(),
+                summary: "Summary".to_string(),
+                slug: Some("episode".to_string()),
+            }
+        }
+
+        const EXISTING: &str = "---\ntitle: \"Episode\"\ndate: \"2025-01-02\"\nupdated: \"2025-02-03\"\ntags: [\"rust\", \"audio\"]\nsummary: \"Summary\"\nenclosure: \"episode.mp3\"\nenclosure_duration: \"12:34\"\ndisable_stages: [diagrams]\ngithub_repo: \"owner/repo\"\n---\n\nText\n";
+
+        #[test]
+        fn saving_keeps_fields_the_editor_does_not_show() {
+            let saved = post_file_from_form(Some(EXISTING), &form("Episode", "Text"), "2026-10-18");
+            let meta = parse_metadata(&saved).unwrap();
+            assert_eq!(meta.date, "2025-01-02");
+            assert_eq!(meta.enclosure.as_deref(), Some("episode.mp3"));
+            assert_eq!(meta.enclosure_duration.as_deref(), Some("12:34"));
+            assert_eq!(meta.disable_stages, vec!["diagrams"]);
+            assert_eq!(meta.github_repo.as_deref(), Some("owner/repo"));
+            // Nothing changed, so the last edit stays where it was
+            assert_eq!(meta.updated.as_deref(), Some("2025-02-03"));
+            assert_eq!(saved.matches("title:").count(), 1);
+        }
+
+        #[test]
+        fn saving_changed_text_bumps_updated() {
+            let saved = post_file_from_form(Some(EXISTING), &form("Episode", "New text"), "2026-10-18");
+            let meta = parse_metadata(&saved).unwrap();
+            assert_eq!(meta.updated.as_deref(), Some("2026-10-18"));
+            assert_eq!(meta.date, "2025-01-02");
+            assert_eq!(saved.matches("updated:").count(), 1);
+            assert!(saved.ends_with("---\n\nNew text"));
+
+            let new = parse_metadata(&post_file_from_form(None, &form("Episode", "Text"), "2026-10-18")).unwrap();
+            assert_eq!(new.date, "2026-10-18");
+            assert_eq!(new.updated, None);
+            assert_eq!(new.tags, vec!["rust", "audio"]);
+        }
+    }
+}